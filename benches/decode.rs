@@ -0,0 +1,31 @@
+/*!
+	Compares decoding the most frequently received world messages with the owned and the borrowed path.
+
+	Game messages have no borrowed form, so for them the borrowed path is the header parse followed by `SubjectGameMessageRef::decode`, which is the owned game message decoding, so that both paths decode the whole message. The difference measured for them is only that of the header, not how a borrowed game message would perform.
+*/
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lu_packets::world::server::{WorldMessage, WorldMessageRef};
+
+fn decode(c: &mut Criterion) {
+	let fixtures: [(&str, &[u8]); 2] = [("GeneralChatMessage", include_bytes!("../src/world/server/tests/GeneralChatMessage.bin")), ("PositionUpdate", include_bytes!("../src/world/server/tests/PositionUpdate.bin"))];
+	for &(name, data) in &fixtures {
+		let mut group = c.benchmark_group(name);
+		group.bench_function("owned", |b| b.iter(|| lu_packets::from_slice::<WorldMessage>(black_box(data)).unwrap()));
+		group.bench_function("borrowed", |b| b.iter(|| lu_packets::decode_borrowed::<WorldMessageRef>(black_box(data)).unwrap()));
+		group.finish();
+	}
+
+	let data = &include_bytes!("../src/world/server/tests/SubjectGameMessage.bin")[..];
+	let mut group = c.benchmark_group("SubjectGameMessage");
+	group.bench_function("owned", |b| b.iter(|| lu_packets::from_slice::<WorldMessage>(black_box(data)).unwrap()));
+	group.bench_function("borrowed", |b| {
+		b.iter(|| match lu_packets::decode_borrowed::<WorldMessageRef>(black_box(data)).unwrap() {
+			WorldMessageRef::SubjectGameMessage(msg) => msg.decode().unwrap(),
+			_ => unreachable!(),
+		})
+	});
+	group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
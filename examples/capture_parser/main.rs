@@ -1,18 +1,14 @@
-mod zip_context;
-
 use std::collections::HashMap;
 use std::env;
-use std::io::{BufReader, Result as Res};
+use std::io::Result as Res;
 use std::fs;
-use std::fs::File;
 use std::path::Path;
 use std::time::Instant;
 
+use lu_packets::capture::{Capture, EntryMeta};
+use lu_packets::raknet::client::replica::context::{CdClientContext, ComponentSource};
 use lu_packets::world::Lot;
-use lu_packets::unified::Message;
 use rusqlite::{params, Connection};
-use zip::ZipArchive;
-use self::zip_context::ZipContext;
 
 static mut PRINT_PACKETS: bool = false;
 
@@ -32,6 +28,12 @@ impl Cdclient {
 	}
 }
 
+impl ComponentSource for Cdclient {
+	fn components(&mut self, lot: Lot) -> Vec<u32> {
+		self.get_comps(lot).clone()
+	}
+}
+
 fn visit_dirs(dir: &Path, cdclient: &mut Cdclient, level: usize) -> Res<usize> {
 	let mut packet_count = 0;
 	if dir.is_dir() {
@@ -45,139 +47,47 @@ fn visit_dirs(dir: &Path, cdclient: &mut Cdclient, level: usize) -> Res<usize> {
 	Ok(packet_count)
 }
 
-fn parse(path: &Path, cdclient: &mut Cdclient) -> Res<usize> {
-	use endio::LERead;
+/// Packets that can be parsed, leaving out those with known unsupported contents.
+fn is_parseable(meta: &EntryMeta) -> bool {
+	const WORLD_GMS: [u16; 6] = [230, 875, 1046, 1097, 1197, 1308];
+	const CLIENT_GMS: [u16; 18] = [118, 230, 255, 417, 639, 675, 716, 821, 822, 845, 877, 913, 1306, 1510, 1558, 1564, 1647, 1648];
+	#[rustfmt::skip]
+	const LOTS: [Lot; 50] = [
+		2365, 4734, 4930, 4955, 4967, 4990, 5635, 5651, 5652, 5903, 5904, 5958, 6007, 6010, 6097, 6209, 6267, 6289, 6290, 6319,
+		7001, 7100, 7282, 7796, 8304, 8575, 9741, 10039, 10042, 10046, 10055, 10097, 12916, 13773, 14376, 14447, 14449, 14476, 14477, 14505,
+		14510, 14539, 14540, 14541, 14542, 14543, 14544, 14545, 14546, 14547,
+	];
+	let excluded = |ids: &[u16]| meta.game_message_id.map_or(false, |x| ids.contains(&x));
+
+	match (meta.raknet_id, meta.service) {
+		(Some(0x53), Some(1)) | (Some(0x53), Some(2)) => true,
+		(Some(0x53), Some(4)) => meta.message_id != Some(0x16) && !excluded(&WORLD_GMS),
+		(Some(0x53), Some(5)) => meta.message_id == Some(0) || (meta.message_id != Some(0x31) && !excluded(&CLIENT_GMS)),
+		(Some(0x24), _) => !meta.lot.map_or(false, |x| LOTS.contains(&x)),
+		(Some(0x27), _) => true,
+		_ => false,
+	}
+}
 
+fn parse(path: &Path, cdclient: &mut Cdclient) -> Res<usize> {
 	if path.extension().unwrap() != "zip" {
 		return Ok(0);
 	}
 
-	let src = BufReader::new(File::open(path).unwrap());
-	let mut zip = ZipArchive::new(src).unwrap();
-	let mut comps = HashMap::new();
-	let mut i = 0;
+	let mut capture = Capture::open(path)?;
+	let mut context = CdClientContext::new(cdclient);
 	let mut packet_count = 0;
-	while i < zip.len() {
-		let mut file = zip.by_index(i).unwrap();
-		if file.name().contains("of") {
-			i += 1;
+	for i in 0..capture.len() {
+		let entry = capture.entry(i)?;
+		// split packets
+		if entry.name.contains("of") || !is_parseable(&entry.meta) {
 			continue;
 		}
-		if (file.name().contains("[53-01-"))
-			|| (file.name().contains("[53-05-00-00]"))
-			|| (file.name().contains("[53-04-") && !file.name().contains("[53-04-00-16]") && !file.name().contains("[e6-00]") && !file.name().contains("[6b-03]") && !file.name().contains("[16-04]") && !file.name().contains("[49-04]") && !file.name().contains("[ad-04]") && !file.name().contains("[1c-05]") && !file.name().contains("[230]") && !file.name().contains("[875]") && !file.name().contains("[1046]") && !file.name().contains("[1097]") && !file.name().contains("[1197]") && !file.name().contains("[1308]"))
-			|| (file.name().contains("[53-02-")
-				|| (file.name().contains("[53-05-")
-					&& !file.name().contains("[53-05-00-00]")
-					&& !file.name().contains("[53-05-00-31]")
-					&& !file.name().contains("[e6-00]")
-					&& !file.name().contains("[ff-00]")
-					&& !file.name().contains("[a1-01]")
-					&& !file.name().contains("[7f-02]")
-					&& !file.name().contains("[a3-02]")
-					&& !file.name().contains("[cc-02]")
-					&& !file.name().contains("[35-03]")
-					&& !file.name().contains("[36-03]")
-					&& !file.name().contains("[4d-03]")
-					&& !file.name().contains("[6d-03]")
-					&& !file.name().contains("[91-03]")
-					&& !file.name().contains("[1a-05]")
-					&& !file.name().contains("[e6-05]")
-					&& !file.name().contains("[16-06]")
-					&& !file.name().contains("[1c-06]")
-					&& !file.name().contains("[6f-06]")
-					&& !file.name().contains("[70-06]")
-					&& !file.name().contains("[118]")
-					&& !file.name().contains("[230]")
-					&& !file.name().contains("[255]")
-					&& !file.name().contains("[417]")
-					&& !file.name().contains("[639]")
-					&& !file.name().contains("[675]")
-					&& !file.name().contains("[716]")
-					&& !file.name().contains("[821]")
-					&& !file.name().contains("[822]")
-					&& !file.name().contains("[845]")
-					&& !file.name().contains("[877]")
-					&& !file.name().contains("[913]")
-					&& !file.name().contains("[1306]")
-					&& !file.name().contains("[1510]")
-					&& !file.name().contains("[1558]")
-					&& !file.name().contains("[1564]")
-					&& !file.name().contains("[1647]")
-					&& !file.name().contains("[1648]"))
-				|| (file.name().contains("[24]")
-					&& !file.name().contains("(2365)")
-					&& !file.name().contains("(4734)")
-					&& !file.name().contains("(4930)")
-					&& !file.name().contains("(4955)")
-					&& !file.name().contains("(4967)")
-					&& !file.name().contains("(4990)")
-					&& !file.name().contains("(5635)")
-					&& !file.name().contains("(5651)")
-					&& !file.name().contains("(5652)")
-					&& !file.name().contains("(5903)")
-					&& !file.name().contains("(5904)")
-					&& !file.name().contains("(5958)")
-					&& !file.name().contains("(6007)")
-					&& !file.name().contains("(6010)")
-					&& !file.name().contains("(6097)")
-					&& !file.name().contains("(6209)")
-					&& !file.name().contains("(6267)")
-					&& !file.name().contains("(6289)")
-					&& !file.name().contains("(6290)")
-					&& !file.name().contains("(6319)")
-					&& !file.name().contains("(7001)")
-					&& !file.name().contains("(7100)")
-					&& !file.name().contains("(7282)")
-					&& !file.name().contains("(7796)")
-					&& !file.name().contains("(8304)")
-					&& !file.name().contains("(8575)")
-					&& !file.name().contains("(9741)")
-					&& !file.name().contains("(10039)")
-					&& !file.name().contains("(10042)")
-					&& !file.name().contains("(10046)")
-					&& !file.name().contains("(10055)")
-					&& !file.name().contains("(10097)")
-					&& !file.name().contains("(12916)")
-					&& !file.name().contains("(13773)")
-					&& !file.name().contains("(14376)")
-					&& !file.name().contains("(14447)")
-					&& !file.name().contains("(14449)")
-					&& !file.name().contains("(14476)")
-					&& !file.name().contains("(14477)")
-					&& !file.name().contains("(14505)")
-					&& !file.name().contains("(14510)")
-					&& !file.name().contains("(14539)")
-					&& !file.name().contains("(14540)")
-					&& !file.name().contains("(14541)")
-					&& !file.name().contains("(14542)")
-					&& !file.name().contains("(14543)")
-					&& !file.name().contains("(14544)")
-					&& !file.name().contains("(14545)")
-					&& !file.name().contains("(14546)")
-					&& !file.name().contains("(14547)"))
-				|| file.name().contains("[27]"))
-		{
-			let mut ctx = ZipContext { zip: file, comps: &mut comps, cdclient, assert_fully_read: true };
-			let msg: Message = ctx.read().expect(&format!("Zip: {}, Filename: {}, {} bytes", path.to_str().unwrap(), ctx.zip.name(), ctx.zip.size()));
-			file = ctx.zip;
-			if unsafe { PRINT_PACKETS } {
-				dbg!(&msg);
-			}
-			packet_count += 1;
-
-			if ctx.assert_fully_read {
-				// assert fully read
-				let mut rest = vec![];
-				std::io::Read::read_to_end(&mut file, &mut rest).unwrap();
-				assert_eq!(rest, vec![], "Zip: {}, Filename: {}, {} bytes", path.to_str().unwrap(), file.name(), file.size());
-			}
-			i += 1;
-			continue;
-		} else {
-			i += 1;
-			continue;
+		let msg = entry.decode(&mut context).expect(&format!("Zip: {}, Filename: {}, {} bytes", path.to_str().unwrap(), entry.name, entry.data.len()));
+		if unsafe { PRINT_PACKETS } {
+			dbg!(&msg);
 		}
+		packet_count += 1;
 	}
 	Ok(packet_count)
 }
@@ -0,0 +1,14 @@
+use std::env;
+use std::fs;
+
+use lu_packets::dissect::wireshark::lua_dissector;
+
+fn main() {
+	let args: Vec<String> = env::args().collect();
+	if args.len() < 2 {
+		println!("Usage: wireshark_dissector output_path");
+		return;
+	}
+	fs::write(&args[1], lua_dissector()).unwrap();
+	println!("Wrote dissector to {}", args[1]);
+}
@@ -0,0 +1,249 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Fields, GenericParam, LitInt, Type};
+
+use crate::replica_serde::{get_enum_type, get_field_padding, get_post_disc_padding, get_pre_disc_padding, get_trailing_padding, is_bool, option_inner};
+
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	gen_impl(&input, false).into()
+}
+
+/**
+	Generates the `Dissect` impl, including its `layout`, for a type with the layout of the endio derives.
+
+	If `bits` is set, the layout is the one of the `ReplicaSerde` derive instead, with `bool`s as single bits and `Option`s prefixed with a bit.
+*/
+pub(crate) fn gen_impl(input: &DeriveInput, bits: bool) -> TokenStream {
+	let name = &input.ident;
+	let dissect_code = match &input.data {
+		Data::Struct(data) => {
+			let (pat, code) = gen_dissect_fields(&data.fields, bits);
+			quote! {
+				match self {
+					#name #pat => { #code }
+				}
+			}
+		}
+		Data::Enum(data) => gen_dissect_enum(data, input, bits),
+		Data::Union(_) => unimplemented!(),
+	};
+	let layout_code = match &input.data {
+		Data::Struct(data) => {
+			let fields = gen_layout_fields(&data.fields, &None, &get_trailing_padding(input), bits);
+			quote! { crate::dissect::Layout::Struct(#fields) }
+		}
+		Data::Enum(data) => gen_layout_enum(data, input, bits),
+		Data::Union(_) => unimplemented!(),
+	};
+	let skip_trailing_padding = gen_skip_padding(&get_trailing_padding(input));
+
+	let mut impl_generics = input.generics.clone();
+	for param in &mut impl_generics.params {
+		if let GenericParam::Type(param) = param {
+			param.bounds.push(parse_quote!(crate::dissect::Dissect));
+		}
+	}
+	let (impl_generics, _, _) = impl_generics.split_for_impl();
+	let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	quote! {
+		impl #impl_generics crate::dissect::Dissect for #name #ty_generics #where_clause {
+			fn dissect(&self, dissector: &mut crate::dissect::Dissector) -> ::std::io::Result<()> {
+				#dissect_code
+				#skip_trailing_padding
+				Ok(())
+			}
+
+			fn layout() -> crate::dissect::Layout {
+				#layout_code
+			}
+		}
+	}
+}
+
+/// Generates the pattern binding the fields, and the code dissecting them.
+fn gen_dissect_fields(fields: &Fields, bits: bool) -> (TokenStream, TokenStream) {
+	match fields {
+		Fields::Named(fields) => {
+			let mut pat = vec![];
+			let mut dissect = vec![];
+			for f in &fields.named {
+				let ident = &f.ident;
+				let skip_padding = gen_skip_padding(&get_field_padding(f));
+				let field = gen_dissect_type(&f.ty, quote! { stringify!(#ident) }, quote! { #ident }, bits, 0);
+				pat.push(quote! { #ident, });
+				dissect.push(quote! {
+					#skip_padding
+					#field
+				});
+			}
+			(quote! { { #(#pat)* } }, quote! { #(#dissect)* })
+		}
+		Fields::Unnamed(fields) => {
+			let mut pat = vec![];
+			let mut dissect = vec![];
+			for (i, f) in fields.unnamed.iter().enumerate() {
+				let ident = Ident::new(&format!("__field{}", i), Span::call_site());
+				let index = i.to_string();
+				let skip_padding = gen_skip_padding(&get_field_padding(f));
+				let field = gen_dissect_type(&f.ty, quote! { #index }, quote! { #ident }, bits, 0);
+				pat.push(quote! { #ident, });
+				dissect.push(quote! {
+					#skip_padding
+					#field
+				});
+			}
+			(quote! { ( #(#pat)* ) }, quote! { #(#dissect)* })
+		}
+		Fields::Unit => (quote! { }, quote! { }),
+	}
+}
+
+/// Generates code dissecting `value`, a reference to a value of type `ty`, as a field named `name`.
+fn gen_dissect_type(ty: &Type, name: TokenStream, value: TokenStream, bits: bool, depth: usize) -> TokenStream {
+	if bits && is_bool(ty) {
+		quote! { dissector.bit(#name, *#value)?; }
+	} else if let (true, Some(inner)) = (bits, option_inner(ty)) {
+		let ident = Ident::new(&format!("__inner{}", depth), Span::call_site());
+		let inner = gen_dissect_type(inner, quote! { "value" }, quote! { #ident }, bits, depth + 1);
+		quote! {
+			dissector.node(#name, ::std::any::type_name::<#ty>(), |dissector| {
+				dissector.bit("is_some", #value.is_some())?;
+				if let Some(#ident) = #value {
+					#inner
+				}
+				Ok(())
+			})?;
+		}
+	} else {
+		quote! { dissector.field(#name, #value)?; }
+	}
+}
+
+fn gen_dissect_enum(data: &DataEnum, input: &DeriveInput, bits: bool) -> TokenStream {
+	let name = &input.ident;
+	let ty = get_enum_type(input);
+	let (_, ty_generics, _) = input.generics.split_for_impl();
+	let mut arms = vec![];
+	for f in &data.variants {
+		let ident = &f.ident;
+		let (pat, dissect) = gen_dissect_fields(&f.fields, bits);
+		arms.push(quote! {
+			#name::#ident #pat => {
+				dissector.variant(stringify!(#ident));
+				#dissect
+			}
+		});
+	}
+	let skip_pre_padding = gen_skip_padding(&get_pre_disc_padding(input));
+	let skip_post_padding = gen_skip_padding(&get_post_disc_padding(input));
+	quote! {
+		#skip_pre_padding
+		let disc = unsafe { *(self as *const #name #ty_generics as *const #ty) };
+		dissector.field("discriminant", &disc)?;
+		#skip_post_padding
+		match self {
+			#(#arms)*
+		}
+	}
+}
+
+/// Generates the list of field layouts, with padding before the fields and after them.
+fn gen_layout_fields(fields: &Fields, leading_padding: &Option<LitInt>, trailing_padding: &Option<LitInt>, bits: bool) -> TokenStream {
+	let mut layouts = vec![];
+	layouts.push(gen_layout_padding(leading_padding));
+	for (i, f) in fields.iter().enumerate() {
+		let name = match &f.ident {
+			Some(ident) => quote! { stringify!(#ident) },
+			None => {
+				let index = i.to_string();
+				quote! { #index }
+			}
+		};
+		let padding = gen_layout_padding(&get_field_padding(f));
+		let layout = gen_layout_type(&f.ty, bits);
+		layouts.push(quote! {
+			#padding
+			(#name, #layout),
+		});
+	}
+	layouts.push(gen_layout_padding(trailing_padding));
+	quote! { vec![#(#layouts)*] }
+}
+
+fn gen_layout_type(ty: &Type, bits: bool) -> TokenStream {
+	if bits && is_bool(ty) {
+		quote! { crate::dissect::Layout::Bit }
+	} else if let (true, Some(inner)) = (bits, option_inner(ty)) {
+		let inner = gen_layout_type(inner, bits);
+		quote! { crate::dissect::Layout::Flagged { flag: "is_some", value: Box::new(#inner) } }
+	} else {
+		quote! { crate::dissect::Layout::of::<#ty>() }
+	}
+}
+
+fn gen_layout_enum(data: &DataEnum, input: &DeriveInput, bits: bool) -> TokenStream {
+	let primitive = gen_primitive(&get_enum_type(input));
+	let post_padding = get_post_disc_padding(input);
+	let trailing_padding = get_trailing_padding(input);
+	let mut variants = vec![];
+	// implicit discriminants count up from the last explicit one
+	let mut last_disc = quote! { 0 };
+	let mut offset = 0i64;
+	for f in &data.variants {
+		let ident = &f.ident;
+		if let Some((_, expr)) = &f.discriminant {
+			last_disc = quote! { #expr };
+			offset = 0;
+		}
+		let fields = gen_layout_fields(&f.fields, &post_padding, &trailing_padding, bits);
+		variants.push(quote! {
+			crate::dissect::Variant {
+				name: stringify!(#ident),
+				discriminant: (#last_disc) as i64 + #offset,
+				layout: crate::dissect::Layout::Struct(#fields),
+			},
+		});
+		offset += 1;
+	}
+	let enum_layout = quote! {
+		crate::dissect::Layout::Enum { discriminant: #primitive, variants: vec![#(#variants)*] }
+	};
+	match get_pre_disc_padding(input) {
+		Some(x) => quote! {
+			crate::dissect::Layout::Struct(vec![("", crate::dissect::Layout::Padding(#x * 8)), ("", #enum_layout)])
+		},
+		None => enum_layout,
+	}
+}
+
+fn gen_primitive(ty: &Ident) -> TokenStream {
+	let primitive = match &*ty.to_string() {
+		"u8" => "U8",
+		"u16" => "U16",
+		"u32" => "U32",
+		"u64" => "U64",
+		"i8" => "I8",
+		"i16" => "I16",
+		"i32" => "I32",
+		"i64" => "I64",
+		_ => panic!("unsupported discriminant type {}", ty),
+	};
+	let primitive = Ident::new(primitive, Span::call_site());
+	quote! { crate::dissect::Primitive::#primitive }
+}
+
+fn gen_layout_padding(padding: &Option<LitInt>) -> TokenStream {
+	match padding {
+		Some(x) => quote! { ("", crate::dissect::Layout::Padding(#x * 8)), },
+		None => quote! { },
+	}
+}
+
+fn gen_skip_padding(padding: &Option<LitInt>) -> TokenStream {
+	match padding {
+		Some(x) => quote! { dissector.skip(#x * 8); },
+		None => quote! { },
+	}
+}
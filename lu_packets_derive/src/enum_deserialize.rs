@@ -0,0 +1,93 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
+
+use crate::replica_serde::{gen_read_padding, get_enum_type, get_field_padding, get_post_disc_padding, get_pre_disc_padding, get_trailing_padding};
+
+/**
+	Generates the `Deserialize` impl of an enum, with the same layout as the endio derive: the discriminant, followed by the fields of the variant it selects.
+
+	Unlike the endio derive, this reports unknown discriminants as `Error::UnknownDiscriminant`, and adds the variant as a frame to the path of errors in its fields, such as `WorldMessage::SubjectGameMessage`.
+*/
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let name = &input.ident;
+	let data = match &input.data {
+		Data::Enum(data) => data,
+		_ => panic!("only enums are supported"),
+	};
+	let ty = get_enum_type(&input);
+
+	let mut arms = vec![];
+	// implicit discriminants count up from the last explicit one
+	let mut last_disc = quote! { 0 };
+	let mut disc_offset = 0usize;
+	for v in &data.variants {
+		let ident = &v.ident;
+		if let Some((_, expr)) = &v.discriminant {
+			last_disc = quote! { #expr };
+			disc_offset = 0;
+		}
+		let value = match &v.fields {
+			Fields::Unit => quote! { Self::#ident },
+			fields => {
+				let deser_fields = gen_deser_fields(fields);
+				quote! { crate::error::frame(concat!(stringify!(#name), "::", stringify!(#ident)), || Ok(Self::#ident #deser_fields))? }
+			}
+		};
+		arms.push(quote! { disc if disc == (#last_disc) + (#disc_offset as #ty) => #value, });
+		disc_offset += 1;
+	}
+	let read_pre_padding = gen_read_padding(&get_pre_disc_padding(&input));
+	let read_post_padding = gen_read_padding(&get_post_disc_padding(&input));
+	let read_trailing_padding = gen_read_padding(&get_trailing_padding(&input));
+
+	let mut impl_generics = input.generics.clone();
+	impl_generics.params.push(parse_quote!(__READER: ::std::io::Read));
+	let where_clause = impl_generics.make_where_clause();
+	for f in data.variants.iter().flat_map(|v| &v.fields) {
+		let field_ty = &f.ty;
+		where_clause.predicates.push(parse_quote!(#field_ty: ::endio::Deserialize<::endio::LE, __READER>));
+	}
+	let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+	let (_, ty_generics, _) = input.generics.split_for_impl();
+
+	(quote! {
+		impl #impl_generics ::endio::Deserialize<::endio::LE, __READER> for #name #ty_generics #where_clause {
+			fn deserialize(reader: &mut __READER) -> ::std::io::Result<Self> {
+				#read_pre_padding
+				let disc: #ty = ::endio::LERead::read(reader)?;
+				#read_post_padding
+				let ret = match disc {
+					#(#arms)*
+					_ => return ::std::result::Result::Err(crate::Error::UnknownDiscriminant { type_name: stringify!(#name), value: disc as u64 }.into()),
+				};
+				#read_trailing_padding
+				Ok(ret)
+			}
+		}
+	})
+	.into()
+}
+
+fn gen_deser_fields(fields: &Fields) -> TokenStream {
+	let mut deser = vec![];
+	for f in fields {
+		let read_padding = gen_read_padding(&get_field_padding(f));
+		let value = quote! {
+			{
+				#read_padding
+				::endio::LERead::read(reader)?
+			}
+		};
+		deser.push(match &f.ident {
+			Some(ident) => quote! { #ident: #value, },
+			None => quote! { #value, },
+		});
+	}
+	match fields {
+		Fields::Named(_) => quote! { { #(#deser)* } },
+		Fields::Unnamed(_) => quote! { ( #(#deser)* ) },
+		Fields::Unit => quote! {},
+	}
+}
@@ -1,6 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, Meta, NestedMeta, Type};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, Meta, NestedMeta};
+
+use crate::replica_serde::is_bool;
 
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
@@ -15,7 +17,7 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	ser_impl_generics.params.push(parse_quote!(__WRITER: ::std::io::Write));
 	let (ser_impl_generics, _, _) = ser_impl_generics.split_for_impl();
 
-	let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
 	let data = match &input.data {
 		Data::Struct(data) => data,
@@ -24,6 +26,8 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
 	let deser_code = gen_deser_code(&data.fields);
 	let ser_code = gen_ser_code(&data.fields);
+	let dissect_code = gen_dissect_code(&data.fields);
+	let layout_code = gen_layout_code(&data.fields);
 	(quote! {
 		impl #des_impl_generics ::endio::Deserialize<::endio::LE, __READER> for #name #ty_generics #where_clause {
 			fn deserialize(reader: &mut __READER) -> ::std::io::Result<Self> {
@@ -36,6 +40,16 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 				#ser_code
 			}
 		}
+
+		impl #impl_generics crate::dissect::Dissect for #name #ty_generics #where_clause {
+			fn dissect(&self, dissector: &mut crate::dissect::Dissector) -> ::std::io::Result<()> {
+				#dissect_code
+			}
+
+			fn layout() -> crate::dissect::Layout {
+				#layout_code
+			}
+		}
 	}).into()
 }
 
@@ -51,36 +65,28 @@ fn gen_deser_code(fields: &Fields) -> TokenStream {
 		let ident = &f.ident;
 		idents.push(quote! { #ident, });
 
-		let is_bool = match &f.ty {
-			Type::Path(path) => path.path.is_ident("bool"),
-			_ => false,
-		};
-		let default = get_gm_default(&f);
-		let field_needs_bitreader = is_bool || default.is_some();
-		let create_bitreader = if !msg_needs_bitreader && field_needs_bitreader {
+		let encoding = Encoding::of(f);
+		let create_bitreader = if !msg_needs_bitreader && encoding.needs_bits() {
 			msg_needs_bitreader = true;
 			quote! { let mut reader = &mut ::endio_bit::BEBitReader::new(reader); }
 		} else {
 			quote! { }
 		};
-		let val = if is_bool {
-			quote! { reader.read_bit()? }
-		} else {
-			let parse = quote! { crate::world::gm::GmParam::deserialize(reader)? };
-			match default {
-				None => quote! { #parse },
-				Some(default) => quote! {
-					if reader.read_bit()? {
-						#parse
-					} else {
-						#default
-					}
-				},
-			}
+		let parse = quote! { crate::world::gm::GmParam::deserialize(reader)? };
+		let val = match encoding {
+			Encoding::Bit => quote! { reader.read_bit()? },
+			Encoding::Param => parse,
+			Encoding::Defaulted(default) => quote! {
+				if reader.read_bit()? {
+					#parse
+				} else {
+					#default
+				}
+			},
 		};
 		deser.push(quote! {
 			#create_bitreader
-			let #ident = #val;
+			let #ident = crate::error::frame(stringify!(#ident), || Ok(#val))?;
 		});
 	}
 	quote! {
@@ -99,32 +105,24 @@ fn gen_ser_code(fields: &Fields) -> TokenStream {
 	for f in &fields.named {
 		let ident = &f.ident;
 
-		let is_bool = match &f.ty {
-			Type::Path(path) => path.path.is_ident("bool"),
-			_ => false,
-		};
-		let default = get_gm_default(&f);
-		let field_needs_bitwriter = is_bool || default.is_some();
-		let create_bitwriter = if !msg_needs_bitwriter && field_needs_bitwriter {
+		let encoding = Encoding::of(f);
+		let create_bitwriter = if !msg_needs_bitwriter && encoding.needs_bits() {
 			msg_needs_bitwriter = true;
 			quote! { let mut writer = &mut ::endio_bit::BEBitWriter::new(writer); }
 		} else {
 			quote! { }
 		};
-		let write = if is_bool {
-			quote! { writer.write_bit(self.#ident)?; }
-		} else {
-			let write_field = quote! { crate::world::gm::GmParam::serialize(&self.#ident, writer)? };
-			match default {
-				None => quote! { #write_field; },
-				Some(default) => quote! {
-					let is_not_default = self.#ident != #default;
-					writer.write_bit(is_not_default)?;
-					if is_not_default {
-						#write_field;
-					}
-				},
-			}
+		let write_field = quote! { crate::world::gm::GmParam::serialize(&self.#ident, writer)? };
+		let write = match encoding {
+			Encoding::Bit => quote! { writer.write_bit(self.#ident)?; },
+			Encoding::Param => quote! { #write_field; },
+			Encoding::Defaulted(default) => quote! {
+				let is_not_default = self.#ident != #default;
+				writer.write_bit(is_not_default)?;
+				if is_not_default {
+					#write_field;
+				}
+			},
 		};
 		ser.push(quote! {
 			#create_bitwriter
@@ -137,7 +135,121 @@ fn gen_ser_code(fields: &Fields) -> TokenStream {
 	}
 }
 
+/// Generates code dissecting the fields, with the parameters as leaves, as their layout is only known to `GmParam`.
+fn gen_dissect_code(fields: &Fields) -> TokenStream {
+	let fields = match fields {
+		Fields::Named(fields) => fields,
+		_ => unimplemented!(),
+	};
+	let mut msg_needs_bitwriter = false;
+	let mut dissect = vec![];
+	for f in &fields.named {
+		let ident = &f.ident;
+		let ty = &f.ty;
+
+		let encoding = Encoding::of(f);
+		msg_needs_bitwriter |= encoding.needs_bits();
+		let dissect_param = quote! {
+			dissector.decoded(&self.#ident, |writer| crate::world::gm::GmParam::serialize(&self.#ident, writer), |reader| <#ty as crate::world::gm::GmParam>::deserialize(reader).map(drop))
+		};
+		dissect.push(match encoding {
+			Encoding::Bit => quote! { dissector.bit(stringify!(#ident), self.#ident)?; },
+			Encoding::Param => quote! {
+				dissector.node(stringify!(#ident), ::std::any::type_name::<#ty>(), |dissector| #dissect_param)?;
+			},
+			Encoding::Defaulted(default) => quote! {
+				dissector.node(stringify!(#ident), ::std::any::type_name::<#ty>(), |dissector| {
+					let is_not_default = self.#ident != #default;
+					dissector.bit("is_not_default", is_not_default)?;
+					if is_not_default {
+						dissector.node("value", ::std::any::type_name::<#ty>(), |dissector| #dissect_param)?;
+					}
+					Ok(())
+				})?;
+			},
+		});
+	}
+	// the bit writer pads to the next byte when it's dropped
+	let align = if msg_needs_bitwriter {
+		quote! { dissector.align(); }
+	} else {
+		quote! { }
+	};
+	quote! {
+		#(#dissect)*
+		#align
+		Ok(())
+	}
+}
+
+/// Generates the layout of the fields, with the parameters as described by `GmParam`.
+fn gen_layout_code(fields: &Fields) -> TokenStream {
+	let fields = match fields {
+		Fields::Named(fields) => fields,
+		_ => unimplemented!(),
+	};
+	let mut msg_needs_bitwriter = false;
+	let mut layouts = vec![];
+	for f in &fields.named {
+		let ident = &f.ident;
+		let ty = &f.ty;
+
+		let encoding = Encoding::of(f);
+		msg_needs_bitwriter |= encoding.needs_bits();
+		let param_layout = quote! { <#ty as crate::world::gm::GmParam>::layout() };
+		let layout = match encoding {
+			Encoding::Bit => quote! { crate::dissect::Layout::Bit },
+			Encoding::Param => param_layout,
+			Encoding::Defaulted(_) => quote! {
+				crate::dissect::Layout::Flagged { flag: "is_not_default", value: Box::new(#param_layout) }
+			},
+		};
+		layouts.push(quote! { (stringify!(#ident), #layout), });
+	}
+	let align = if msg_needs_bitwriter {
+		quote! { ("", crate::dissect::Layout::Align), }
+	} else {
+		quote! { }
+	};
+	quote! {
+		crate::dissect::Layout::Struct(vec![#(#layouts)* #align])
+	}
+}
+
+/// How a field of a game message is encoded.
+enum Encoding {
+	/// A single bit, for `bool`s.
+	Bit,
+	/// A parameter, encoded by `GmParam`.
+	Param,
+	/// A parameter preceded by a bit indicating whether it differs from the default, and only present if it does.
+	Defaulted(NestedMeta),
+}
+
+impl Encoding {
+	fn of(input: &Field) -> Self {
+		if is_bool(&input.ty) {
+			return Self::Bit;
+		}
+		match get_gm_default(input) {
+			None => Self::Param,
+			Some(default) => Self::Defaulted(default),
+		}
+	}
+
+	/// Whether the field is part of the bit stream, which starts at the first such field and lasts until the end of the message.
+	fn needs_bits(&self) -> bool {
+		!matches!(self, Self::Param)
+	}
+}
+
+/// The default value of a game message field, which always needs an expression since it's compared against.
 fn get_gm_default(input: &Field) -> Option<NestedMeta> {
+	get_default(input).map(|x| x.expect("default attribute should have exactly one argument"))
+}
+
+/// `None` if there's no default attribute, `Some(None)` for `#[default]`, and `Some(Some(expr))` for `#[default(expr)]`.
+pub(crate) fn get_default(input: &Field) -> Option<Option<NestedMeta>> {
 	for attr in &input.attrs {
 		if !attr.path.is_ident("default") {
 			continue;
@@ -146,12 +258,11 @@ fn get_gm_default(input: &Field) -> Option<NestedMeta> {
 			Err(_) => panic!("encountered unparseable default attribute"),
 			Ok(x) => x,
 		};
-		let list = match meta {
-			Meta::List(x) => x,
+		return match meta {
+			Meta::Path(_) => Some(None),
+			Meta::List(list) => Some(Some(list.nested.first().expect("default attribute should have exactly one argument").clone())),
 			_ => panic!("default attribute has wrong format"),
 		};
-		let nested_meta = list.nested.first().expect("default attribute should have exactly one argument");
-		return Some(nested_meta.clone());
 	}
 	None
 }
@@ -15,6 +15,10 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 			fn serialize<W: ::std::io::Write>(&self, writer: &mut W) -> ::std::io::Result<()> {
 				::endio::LEWrite::write(writer, self)
 			}
+
+			fn layout() -> crate::dissect::Layout {
+				crate::dissect::Layout::of::<Self>()
+			}
 		}
 	}).into()
 }
@@ -1,6 +1,9 @@
+mod dissect;
+mod enum_deserialize;
 mod from_variants;
 mod game_message;
 mod gm_type;
+mod lnv;
 mod replica_serde;
 mod variant_tests;
 
@@ -14,11 +17,26 @@ pub fn derive_bit_variant_tests(input: TokenStream) -> TokenStream {
 	variant_tests::derive(input, quote!(::endio_bit::BEBitReader::new(&mut bin)), quote!(::endio_bit::BEBitWriter::new(&mut out)))
 }
 
+#[proc_macro_derive(Dissect, attributes(padding, pre_disc_padding, post_disc_padding, trailing_padding))]
+pub fn derive_dissect(input: TokenStream) -> TokenStream {
+	dissect::derive(input)
+}
+
+#[proc_macro_derive(EnumDeserialize, attributes(padding, pre_disc_padding, post_disc_padding, trailing_padding))]
+pub fn derive_enum_deserialize(input: TokenStream) -> TokenStream {
+	enum_deserialize::derive(input)
+}
+
 #[proc_macro_derive(FromVariants)]
 pub fn derive_from_variants(input: TokenStream) -> TokenStream {
 	from_variants::derive(input, None)
 }
 
+#[proc_macro_derive(FromLnv, attributes(default, rename))]
+pub fn derive_from_lnv(input: TokenStream) -> TokenStream {
+	lnv::derive_from(input)
+}
+
 #[proc_macro_derive(GameMessage, attributes(default))]
 pub fn derive_game_message_deserialize(input: TokenStream) -> TokenStream {
 	game_message::derive(input)
@@ -29,6 +47,11 @@ pub fn derive_gm_type(input: TokenStream) -> TokenStream {
 	gm_type::derive(input)
 }
 
+#[proc_macro_derive(IntoLnv, attributes(default, rename))]
+pub fn derive_into_lnv(input: TokenStream) -> TokenStream {
+	lnv::derive_into(input)
+}
+
 #[proc_macro_derive(MessageFromVariants)]
 pub fn derive_message_from_variants(input: TokenStream) -> TokenStream {
 	from_variants::derive(input, Some(&Ident::new("Message", Span::call_site())))
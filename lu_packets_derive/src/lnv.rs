@@ -0,0 +1,106 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, FieldsNamed, Lit, LitStr, Meta};
+
+use crate::game_message::get_default;
+use crate::replica_serde::option_inner;
+
+pub fn derive_from(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut fields = vec![];
+	for f in &get_fields(&input.data).named {
+		let ident = &f.ident;
+		let key = get_key(f);
+		let val = match (option_inner(&f.ty), get_default(f)) {
+			(Some(inner), _) => quote! { lnv.get_field::<#inner>(#key)? },
+			(None, Some(None)) => quote! { lnv.get_field(#key)?.unwrap_or_default() },
+			(None, Some(Some(default))) => quote! { lnv.get_field(#key)?.unwrap_or_else(|| #default) },
+			(None, None) => quote! { lnv.get_field(#key)?.ok_or(::lu_packets::Error::LnvField { name: #key, reason: "is missing" })? },
+		};
+		fields.push(quote! { #ident: #val, });
+	}
+
+	(quote! {
+		impl #impl_generics ::lu_packets::world::FromLnv for #name #ty_generics #where_clause {
+			fn from_lnv(lnv: &::lu_packets::world::LuNameValue) -> ::std::result::Result<Self, ::lu_packets::Error> {
+				Ok(Self {
+					#(#fields)*
+				})
+			}
+		}
+	}).into()
+}
+
+pub fn derive_into(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut inserts = vec![];
+	for f in &get_fields(&input.data).named {
+		let ident = &f.ident;
+		let key = get_key(f);
+		let insert = |val| quote! {
+			lnv.insert(::std::convert::TryInto::try_into(#key).unwrap(), ::std::convert::Into::into(::std::clone::Clone::clone(#val)));
+		};
+		inserts.push(match option_inner(&f.ty) {
+			Some(_) => {
+				let insert = insert(quote! { val });
+				quote! {
+					if let Some(val) = &self.#ident {
+						#insert
+					}
+				}
+			}
+			None => insert(quote! { &self.#ident }),
+		});
+	}
+
+	(quote! {
+		impl #impl_generics ::lu_packets::world::IntoLnv for #name #ty_generics #where_clause {
+			fn to_lnv(&self) -> ::lu_packets::world::LuNameValue {
+				let mut lnv = ::lu_packets::world::LuNameValue::new();
+				#(#inserts)*
+				lnv
+			}
+		}
+	}).into()
+}
+
+fn get_fields(data: &Data) -> &FieldsNamed {
+	match data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => fields,
+			_ => panic!("LNV conversion can only be derived for structs with named fields"),
+		},
+		_ => panic!("LNV conversion can only be derived for structs"),
+	}
+}
+
+/// The name of the value of the field, checked to be valid as a LNV key, which is UCS-2.
+fn get_key(input: &Field) -> LitStr {
+	let mut key = None;
+	for attr in &input.attrs {
+		if !attr.path.is_ident("rename") {
+			continue;
+		}
+		match attr.parse_meta() {
+			Ok(Meta::NameValue(x)) => match x.lit {
+				Lit::Str(x) => key = Some(x),
+				_ => panic!("rename needs to be a string"),
+			},
+			_ => panic!("rename needs to be name=value"),
+		}
+	}
+	let key = key.unwrap_or_else(|| {
+		let ident = input.ident.as_ref().unwrap();
+		LitStr::new(&ident.to_string(), ident.span())
+	});
+	if key.value().chars().any(|c| c.len_utf16() != 1) {
+		panic!("LNV name {:?} contains characters outside of UCS-2", key.value());
+	}
+	key
+}
@@ -1,15 +1,28 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, Attribute, Data, DataEnum, DeriveInput, Field, Fields, Generics, Lit, LitInt, Meta, NestedMeta};
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DataEnum, DeriveInput, Field, Fields, GenericArgument, Generics, Lit, LitInt, Meta, NestedMeta, Path, PathArguments, Type};
 
+/**
+	Generates the replica bit stream (de-)serialization, see [`gen_deser_type`] for how fields are encoded.
+
+	`Option`s and `bool`s are recognized by how their type is written, as `Option<T>` and `bool`, or by their full paths like `std::option::Option<T>`. Other paths ending in `Option` or `bool` are rejected with a compile error. Type aliases of them can't be recognized, and are encoded with their regular `Deserialize` implementation, so they shouldn't be used for fields.
+*/
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
 	let name = &input.ident;
+	let fields: Vec<&Field> = match &input.data {
+		Data::Struct(data) => data.fields.iter().collect(),
+		Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+		Data::Union(_) => unimplemented!(),
+	};
+	if let Err(err) = fields.iter().try_for_each(|f| check_type(&f.ty)) {
+		return err.to_compile_error().into();
+	}
 	let deser_code;
 	let ser_code;
 	match &input.data {
 		Data::Struct(data) => {
-			deser_code = gen_deser_code_struct(&data.fields);
+			deser_code = gen_deser_code_struct(&data.fields, &name);
 			ser_code = gen_ser_code_struct(&data.fields, &name);
 		},
 		Data::Enum(data) => {
@@ -37,6 +50,8 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	ser_impl_generics.params.push(parse_quote!(__WRITER: ::std::io::Write));
 	let (ser_impl_generics, _, _) = ser_impl_generics.split_for_impl();
 
+	let dissect_impl = crate::dissect::gen_impl(&input, true);
+
 	(quote! {
 		impl #des_impl_generics ::endio::Deserialize<::endio::LE, ::endio_bit::BEBitReader<__READER>> for #name #ty_generics #where_clause {
 			fn deserialize(reader: &mut ::endio_bit::BEBitReader<__READER>) -> ::std::io::Result<Self> {
@@ -53,6 +68,8 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 				Ok(())
 			}
 		}
+
+		#dissect_impl
 	}).into()
 }
 
@@ -64,22 +81,25 @@ fn gen_deser_code_fields(fields: &Fields) -> TokenStream {
 				let ident = &f.ident;
 				let padding = get_field_padding(f);
 				let read_padding = gen_read_padding(&padding);
-				deser.push(quote! { #ident: {
+				let value = gen_deser_type(&f.ty);
+				deser.push(quote! { #ident: crate::error::frame(stringify!(#ident), || {
 					#read_padding
-					crate::raknet::client::replica::ReplicaD::deserialize(reader)?
-				}, });
+					#value
+				})?, });
 			}
 			quote! { { #(#deser)* } }
 		}
 		Fields::Unnamed(fields) => {
 			let mut deser = vec![];
-			for f in &fields.unnamed {
+			for (i, f) in fields.unnamed.iter().enumerate() {
+				let index = i.to_string();
 				let padding = get_field_padding(f);
 				let read_padding = gen_read_padding(&padding);
-				deser.push(quote! { {
+				let value = gen_deser_type(&f.ty);
+				deser.push(quote! { crate::error::frame(#index, || {
 					#read_padding
-					crate::raknet::client::replica::ReplicaD::deserialize(reader)?
-				}, });
+					#value
+				})?, });
 			}
 			quote! { ( #(#deser)* ) }
 		}
@@ -89,9 +109,100 @@ fn gen_deser_code_fields(fields: &Fields) -> TokenStream {
 	}
 }
 
-fn gen_deser_code_struct(fields: &Fields) -> TokenStream {
+/// Whether `path` names the prelude item `name`, either directly or by its path in `std` or `core`, like `Option` or `std::option::Option`.
+fn is_prelude_path(path: &Path, module: &str, name: &str) -> bool {
+	let segments: Vec<_> = path.segments.iter().map(|x| x.ident.to_string()).collect();
+	match &segments[..] {
+		[x] => path.leading_colon.is_none() && x == name,
+		[krate, m, x] => (krate == "std" || krate == "core") && m == module && x == name,
+		_ => false,
+	}
+}
+
+/// The type inside `ty` if it's an `Option`.
+pub(crate) fn option_inner(ty: &Type) -> Option<&Type> {
+	let path = match ty {
+		Type::Path(x) if x.qself.is_none() => &x.path,
+		_ => return None,
+	};
+	if !is_prelude_path(path, "option", "Option") {
+		return None;
+	}
+	let segment = path.segments.last()?;
+	let args = match &segment.arguments {
+		PathArguments::AngleBracketed(x) if x.args.len() == 1 => x,
+		_ => return None,
+	};
+	match &args.args[0] {
+		GenericArgument::Type(x) => Some(x),
+		_ => None,
+	}
+}
+
+pub(crate) fn is_bool(ty: &Type) -> bool {
+	match ty {
+		Type::Path(x) => x.qself.is_none() && is_prelude_path(&x.path, "primitive", "bool"),
+		_ => false,
+	}
+}
+
+/// Rejects paths ending in `Option` or `bool` that [`option_inner`] and [`is_bool`] don't recognize, since they would silently be encoded differently.
+fn check_type(ty: &Type) -> syn::Result<()> {
+	let path = match ty {
+		Type::Path(x) => &x.path,
+		_ => return Ok(()),
+	};
+	let recognized = match path.segments.last() {
+		Some(x) if x.ident == "Option" => option_inner(ty).is_some(),
+		Some(x) if x.ident == "bool" => is_bool(ty),
+		_ => true,
+	};
+	if !recognized {
+		return Err(syn::Error::new_spanned(ty, "write this as `Option<T>` or `bool`, or by its full path in `std`, so that it's encoded as bits"));
+	}
+	match option_inner(ty) {
+		Some(inner) => check_type(inner),
+		None => Ok(()),
+	}
+}
+
+/**
+	Generates an expression reading a value of type `ty` from a replica bit stream, evaluating to a `Result`.
+
+	In replica bit streams, `bool`s are written as single bits, and `Option`s are prefixed with a bit indicating whether the value is present. All other types use their regular `Deserialize` implementation. Since this can't be expressed with trait impls without specialization, it's decided here based on the type as written in the field.
+*/
+fn gen_deser_type(ty: &Type) -> TokenStream {
+	if is_bool(ty) {
+		quote! { reader.read_bit() }
+	} else if let Some(inner) = option_inner(ty) {
+		let inner = gen_deser_type(inner);
+		quote! { if reader.read_bit()? { (#inner).map(Some) } else { Ok(None) } }
+	} else {
+		quote! { ::endio::LERead::read(reader) }
+	}
+}
+
+/// Generates statements writing `value`, a reference to a value of type `ty`, to a replica bit stream. See [`gen_deser_type`] for the format.
+fn gen_ser_type(ty: &Type, value: TokenStream, depth: usize) -> TokenStream {
+	if is_bool(ty) {
+		quote! { writer.write_bit(*#value)?; }
+	} else if let Some(inner) = option_inner(ty) {
+		let ident = Ident::new(&format!("__inner{}", depth), Span::call_site());
+		let inner = gen_ser_type(inner, quote! { #ident }, depth + 1);
+		quote! {
+			writer.write_bit(#value.is_some())?;
+			if let Some(#ident) = #value {
+				#inner
+			}
+		}
+	} else {
+		quote! { ::endio::LEWrite::write(writer, #value)?; }
+	}
+}
+
+fn gen_deser_code_struct(fields: &Fields, name: &Ident) -> TokenStream {
 	let deser_code = gen_deser_code_fields(fields);
-	quote! { let ret = Self #deser_code; }
+	quote! { let ret = crate::error::frame(stringify!(#name), || Ok(Self #deser_code))?; }
 }
 
 fn gen_deser_code_enum(data: &DataEnum, name: &Ident, ty: &Ident, pre_disc_padding: &Option<LitInt>, post_disc_padding: &Option<LitInt>) -> TokenStream {
@@ -106,7 +217,7 @@ fn gen_deser_code_enum(data: &DataEnum, name: &Ident, ty: &Ident, pre_disc_paddi
 			disc_offset = 0;
 		}
 		let deser_fields = gen_deser_code_fields(&f.fields);
-		let arm = quote! { disc if disc == (#last_disc + (#disc_offset as #ty)) => Self::#ident #deser_fields, };
+		let arm = quote! { disc if disc == (#last_disc + (#disc_offset as #ty)) => crate::error::frame(concat!(stringify!(#name), "::", stringify!(#ident)), || Ok(Self::#ident #deser_fields))?, };
 		disc_offset += 1;
 		arms.push(arm);
 	}
@@ -118,12 +229,12 @@ fn gen_deser_code_enum(data: &DataEnum, name: &Ident, ty: &Ident, pre_disc_paddi
 		#read_post_padding
 		let ret = match disc {
 			#(#arms)*
-			_ => return ::std::result::Result::Err(::std::io::Error::new(::std::io::ErrorKind::InvalidData, format!("invalid discriminant value for {}: {}", stringify!(#name), disc)))
+			_ => return ::std::result::Result::Err(crate::Error::UnknownDiscriminant { type_name: stringify!(#name), value: disc as u64 }.into())
 		};
 	}
 }
 
-fn gen_read_padding(padding: &Option<LitInt>) -> TokenStream {
+pub(crate) fn gen_read_padding(padding: &Option<LitInt>) -> TokenStream {
 	match padding {
 		Some(x) => quote! {
 			let mut padding = [0; #x];
@@ -142,10 +253,11 @@ fn gen_ser_code_fields(fields: &Fields) -> TokenStream {
 				let ident = &f.ident;
 				let padding = get_field_padding(f);
 				let write_padding = gen_write_padding(&padding);
+				let write = gen_ser_type(&f.ty, quote! { #ident }, 0);
 				pat.push(quote! { #ident, });
 				ser.push(quote! {
 					#write_padding
-					crate::raknet::client::replica::ReplicaS::serialize(#ident, writer)?;
+					#write
 				});
 			}
 			quote! { { #(#pat)* } => { #(#ser)* } }
@@ -158,10 +270,11 @@ fn gen_ser_code_fields(fields: &Fields) -> TokenStream {
 				let ident = Ident::new(&index, Span::call_site());
 				let padding = get_field_padding(f);
 				let write_padding = gen_write_padding(&padding);
+				let write = gen_ser_type(&f.ty, quote! { #ident }, 0);
 				pat.push(quote! { #ident, });
 				ser.push(quote! {
 					#write_padding
-					crate::raknet::client::replica::ReplicaS::serialize(#ident, writer)?;
+					#write
 				});
 				index += "a";
 			}
@@ -213,7 +326,7 @@ fn gen_write_padding(padding: &Option<LitInt>) -> TokenStream {
 	}
 }
 
-fn get_enum_type(input: &DeriveInput) -> Ident {
+pub(crate) fn get_enum_type(input: &DeriveInput) -> Ident {
 	for attr in &input.attrs {
 		if !attr.path.is_ident("repr") {
 			continue;
@@ -269,18 +382,18 @@ fn get_padding(attrs: &Vec<Attribute>, attr_name: &str) -> Option<LitInt> {
 	None
 }
 
-fn get_field_padding(input: &Field) -> Option<LitInt> {
+pub(crate) fn get_field_padding(input: &Field) -> Option<LitInt> {
 	get_padding(&input.attrs, "padding")
 }
 
-fn get_pre_disc_padding(input: &DeriveInput) -> Option<LitInt> {
+pub(crate) fn get_pre_disc_padding(input: &DeriveInput) -> Option<LitInt> {
 	get_padding(&input.attrs, "pre_disc_padding")
 }
 
-fn get_post_disc_padding(input: &DeriveInput) -> Option<LitInt> {
+pub(crate) fn get_post_disc_padding(input: &DeriveInput) -> Option<LitInt> {
 	get_padding(&input.attrs, "post_disc_padding")
 }
 
-fn get_trailing_padding(input: &DeriveInput) -> Option<LitInt> {
+pub(crate) fn get_trailing_padding(input: &DeriveInput) -> Option<LitInt> {
 	get_padding(&input.attrs, "trailing_padding")
 }
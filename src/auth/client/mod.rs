@@ -1,12 +1,14 @@
 //! Client-received auth messages.
-use std::io::{Error, ErrorKind::InvalidData, Result as Res, Read};
+use std::io::{Result as Res, Read};
 
 use endio::{LEWrite, LERead, Deserialize, Serialize};
 use endio::LittleEndian as LE;
-use lu_packets_derive::MessageFromVariants;
+use lu_packets_derive::{Dissect, EnumDeserialize, MessageFromVariants};
 use lu_packets_derive::VariantTests;
 
+use crate::Error;
 use crate::common::{LuString3, LuString33, LuString37, LuVarWString, LuWString33, ServiceId};
+use crate::dissect::Dissector;
 use crate::general::client::{DisconnectNotify, Handshake, GeneralMessage};
 use crate::world::server::Language;
 
@@ -14,7 +16,8 @@ use crate::world::server::Language;
 pub type Message = crate::raknet::client::Message<LuMessage>;
 
 /// All LU messages that can be received by a client from an auth server.
-#[derive(Debug, MessageFromVariants, PartialEq, Serialize, Deserialize, VariantTests)]
+#[derive(Debug, MessageFromVariants, PartialEq, Serialize, EnumDeserialize, Dissect, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum LuMessage {
 	General(GeneralMessage) = ServiceId::General as u16,
@@ -40,7 +43,8 @@ impl From<DisconnectNotify> for Message {
 }
 
 /// All client-received auth messages.
-#[derive(Debug, MessageFromVariants, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, MessageFromVariants, PartialEq, Serialize, EnumDeserialize, Dissect)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[post_disc_padding = 1]
 #[repr(u32)]
 pub enum ClientMessage {
@@ -67,6 +71,7 @@ pub enum ClientMessage {
 	Expect the connection to be closed soon after this message is received, if you're not closing it yourself already.
 */
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[repr(u8)]
 pub enum LoginResponse {
@@ -103,7 +108,8 @@ pub enum LoginResponse {
 	InvalidUsernamePassword = 6,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Dissect, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Stamp {
 	pub type_: u32,
 	pub value: u32,
@@ -176,6 +182,66 @@ where
 	}
 }
 
+impl crate::dissect::Dissect for LoginResponse {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		let disc = unsafe { *(self as *const LoginResponse as *const u8) };
+		dissector.field("discriminant", &disc)?;
+		match self {
+			LoginResponse::Ok { events, version, session_key, redirect_address, chat_server_address, cdn_key, cdn_ticket, language, country_code, just_upgraded_from_ftp, is_ftp, time_remaining_in_ftp, stamps } => {
+				dissector.variant("Ok");
+				dissector.node("events", crate::dissect::type_name_of(events), |dissector| {
+					dissector.field("0", &events.0)?;
+					dissector.field("1", &events.1)?;
+					dissector.field("2", &events.2)?;
+					dissector.field("3", &events.3)?;
+					dissector.field("4", &events.4)?;
+					dissector.field("5", &events.5)?;
+					dissector.field("6", &events.6)?;
+					dissector.field("7", &events.7)
+				})?;
+				dissector.node("version", crate::dissect::type_name_of(version), |dissector| {
+					dissector.field("0", &version.0)?;
+					dissector.field("1", &version.1)?;
+					dissector.field("2", &version.2)
+				})?;
+				dissector.field("session_key", session_key)?;
+				// the addresses are interleaved
+				dissector.field("redirect_address.0", &redirect_address.0)?;
+				dissector.field("chat_server_address.0", &chat_server_address.0)?;
+				dissector.field("redirect_address.1", &redirect_address.1)?;
+				dissector.field("chat_server_address.1", &chat_server_address.1)?;
+				dissector.field("cdn_key", cdn_key)?;
+				dissector.field("cdn_ticket", cdn_ticket)?;
+				dissector.field("language", language)?;
+				dissector.field("country_code", country_code)?;
+				dissector.field("just_upgraded_from_ftp", just_upgraded_from_ftp)?;
+				dissector.field("is_ftp", is_ftp)?;
+				dissector.field("time_remaining_in_ftp", time_remaining_in_ftp)?;
+				dissector.field("custom_message", &LuVarWString::<u16>::new())?;
+				dissector.field("stamps_length", &((stamps.len() * 16) as u32 + 4))?;
+				dissector.node("stamps", std::any::type_name::<Vec<Stamp>>(), |dissector| {
+					for (i, stamp) in stamps.iter().enumerate() {
+						dissector.field(&i.to_string(), stamp)?;
+					}
+					Ok(())
+				})?;
+			}
+			LoginResponse::CustomMessage(msg) => {
+				dissector.variant("CustomMessage");
+				dissector.skip(493 * 8);
+				dissector.field("0", msg)?;
+				dissector.field("stamps_length", &4u32)?;
+			}
+			LoginResponse::InvalidUsernamePassword => {
+				dissector.variant("InvalidUsernamePassword");
+				dissector.skip(495 * 8);
+				dissector.field("stamps_length", &4u32)?;
+			}
+		}
+		Ok(())
+	}
+}
+
 impl<R: Read + LERead> Deserialize<LE, R> for LoginResponse {
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let disc = LERead::read::<u8>(reader)?;
@@ -198,7 +264,7 @@ impl<R: Read + LERead> Deserialize<LE, R> for LoginResponse {
 				let _custom_message: LuVarWString<u16> = LERead::read(reader)?;
 				let buffer_len_plus_four: u32 = LERead::read(reader)?;
 				let mut stamps: Vec<Stamp> = Vec::new();
-				let stamp_count = (buffer_len_plus_four - 4) / 16;
+				let stamp_count = buffer_len_plus_four.checked_sub(4).ok_or(Error::LengthOverflow { type_name: "LoginResponse", length: buffer_len_plus_four as u64 })? / 16;
 				for _i in 0..stamp_count {
 					let stamp: Stamp = LERead::read(reader)?;
 					stamps.push(stamp);
@@ -216,7 +282,7 @@ impl<R: Read + LERead> Deserialize<LE, R> for LoginResponse {
 				Read::read_exact(reader, &mut padding)?;
 				Ok(Self::InvalidUsernamePassword)
 			}
-			_ => Err(Error::new(InvalidData, "invalid login response type")),
+			_ => Err(Error::UnknownDiscriminant { type_name: "LoginResponse", value: disc as u64 }.into()),
 		}
 	}
 }
@@ -0,0 +1,324 @@
+/*!
+	Reading of packet captures, zip archives with one entry per packet.
+
+	Entry names describe the packet, for example `123_[53-05-00-0c]_[e6-00]_(1152921504606846976).bin`:
+
+	- The leading number is the index of the packet in the capture.
+	- The first bracketed group holds the first bytes of the packet in hex: the RakNet message ID, and for LU messages the service ID and message ID.
+	- Further bracketed groups hold the game message ID, either as hex bytes (`[e6-00]`) or in decimal (`[230]`).
+	- A parenthesized number is the LOT for replica constructions, and the object ID otherwise.
+
+	All parts are optional, [`EntryMeta::from_name`] skips anything it doesn't recognize. When reading entries, fields missing from the name are filled in from the start of the packet where possible.
+
+	Requires the `capture` feature.
+
+	Example:
+
+	```no_run
+	use std::collections::HashMap;
+	use lu_packets::capture::Capture;
+	use lu_packets::raknet::client::replica::context::CdClientContext;
+
+	let mut capture = Capture::open("capture.zip")?;
+	let mut context = CdClientContext::new(HashMap::new());
+	// all game messages of object 1152921504606846976
+	for result in capture.messages(&mut context, |meta| meta.object_id == Some(1152921504606846976)) {
+		let (entry, message) = result?;
+		println!("{}: {:?}", entry.name, message);
+	}
+	# Ok::<(), std::io::Error>(())
+	```
+*/
+use std::convert::{TryFrom, TryInto};
+use std::fs::File;
+use std::io::{BufReader, Read, Result as Res, Seek};
+use std::path::Path;
+
+use endio::LERead;
+use zip::ZipArchive;
+
+use crate::{limits, Error};
+use crate::common::{ObjId, ServiceId};
+use crate::raknet::client::replica::ComponentRegistry;
+use crate::raknet::client::replica::context::{CdClientContext, ComponentSource};
+use crate::unified::Message;
+use crate::world::Lot;
+
+const USER_MESSAGE: u8 = 83;
+const REPLICA_CONSTRUCTION: u8 = 36;
+/// Message IDs of `SubjectGameMessage` in `WorldMessage` and `AnyClientMessage`.
+const WORLD_SUBJECT_GAME_MESSAGE: u32 = 5;
+const CLIENT_SUBJECT_GAME_MESSAGE: u32 = 12;
+
+/// The side a packet was sent to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+	ToClient,
+	ToServer,
+}
+
+/// Information about a packet of a capture, from its entry name and the start of its data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntryMeta {
+	/// Index of the packet in the capture.
+	pub index: Option<u64>,
+	/// The first byte of the packet, see [`unified::Message`](crate::unified::Message).
+	pub raknet_id: Option<u8>,
+	/// The [`ServiceId`] of LU messages.
+	pub service: Option<u16>,
+	/// The message ID of LU messages, within their service.
+	pub message_id: Option<u32>,
+	pub game_message_id: Option<u16>,
+	/// The subject of a game message, or any other object the packet is about.
+	pub object_id: Option<ObjId>,
+	/// The LOT of a replica construction.
+	pub lot: Option<Lot>,
+}
+
+impl EntryMeta {
+	/// Parses an entry name, see the [module documentation](self) for the format.
+	pub fn from_name(name: &str) -> Self {
+		let mut meta = Self::default();
+		let name = name.rsplit('/').next().unwrap_or(name);
+		let digits = name.len() - name.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+		meta.index = name[..digits].parse().ok();
+		let mut rest = &name[digits..];
+		let mut is_first_group = true;
+		while let Some(start) = rest.find(|c: char| c == '[' || c == '(') {
+			let close = if rest[start..].starts_with('[') { ']' } else { ')' };
+			let end = match rest[start..].find(close) {
+				Some(x) => start + x,
+				None => break,
+			};
+			let group = &rest[start + 1..end];
+			if close == ')' {
+				if let Ok(x) = group.parse() {
+					if meta.raknet_id == Some(REPLICA_CONSTRUCTION) {
+						meta.lot = u32::try_from(x).ok();
+					} else {
+						meta.object_id = Some(x);
+					}
+				}
+			} else if is_first_group {
+				is_first_group = false;
+				if let Some(bytes) = hex_bytes(group) {
+					meta.set_header(&bytes);
+				}
+			} else if group.contains('-') {
+				if let Some([low, high]) = hex_bytes(group).as_deref() {
+					meta.game_message_id = Some(u16::from_le_bytes([*low, *high]));
+				}
+			} else if let Ok(x) = group.parse() {
+				meta.game_message_id = Some(x);
+			}
+			rest = &rest[end + 1..];
+		}
+		meta
+	}
+
+	/// Sets the fields contained in the header bytes of a packet, which may be cut off.
+	fn set_header(&mut self, header: &[u8]) {
+		let raknet_id = match header.first() {
+			Some(x) => *x,
+			None => return,
+		};
+		self.raknet_id = Some(raknet_id);
+		if raknet_id == USER_MESSAGE && header.len() >= 4 {
+			self.service = Some(u16::from_le_bytes([header[1], header[2]]));
+			let mut message_id = [0; 4];
+			let len = (header.len() - 3).min(4);
+			message_id[..len].copy_from_slice(&header[3..3 + len]);
+			self.message_id = Some(u32::from_le_bytes(message_id));
+		}
+	}
+
+	/// Fills fields not given by the entry name from the data of the packet.
+	fn complete(&mut self, data: &[u8]) {
+		let mut from_data = Self::default();
+		from_data.set_header(&data[..data.len().min(7)]);
+		// LU header, including padding byte, then subject ID and game message ID
+		if from_data.is_subject_game_message() && data.len() >= 18 {
+			from_data.object_id = Some(u64::from_le_bytes(data[8..16].try_into().unwrap()));
+			from_data.game_message_id = Some(u16::from_le_bytes([data[16], data[17]]));
+		}
+		self.raknet_id = self.raknet_id.or(from_data.raknet_id);
+		self.service = self.service.or(from_data.service);
+		self.message_id = self.message_id.or(from_data.message_id);
+		self.game_message_id = self.game_message_id.or(from_data.game_message_id);
+		self.object_id = self.object_id.or(from_data.object_id);
+	}
+
+	/// Whether this is an LU message of the given service and message ID.
+	pub fn is_message(&self, service: ServiceId, message_id: u32) -> bool {
+		self.raknet_id == Some(USER_MESSAGE) && self.service == Some(service as u16) && self.message_id == Some(message_id)
+	}
+
+	/// Whether this is a game message, sent to either side.
+	pub fn is_subject_game_message(&self) -> bool {
+		self.is_message(ServiceId::World, WORLD_SUBJECT_GAME_MESSAGE) || self.is_message(ServiceId::Client, CLIENT_SUBJECT_GAME_MESSAGE)
+	}
+
+	/// The side the packet was sent to, if it is only ever sent to one side.
+	pub fn direction(&self) -> Option<Direction> {
+		match self.raknet_id? {
+			USER_MESSAGE => match self.service? {
+				x if x == ServiceId::General as u16 => None,
+				x if x == ServiceId::Client as u16 => Some(Direction::ToClient),
+				_ => Some(Direction::ToServer),
+			},
+			// InternalPing, ConnectionRequest, NewIncomingConnection
+			0 | 4 | 17 => Some(Direction::ToServer),
+			// ConnectedPong, ConnectionRequestAccepted, replica messages
+			3 | 14 | 36..=41 => Some(Direction::ToClient),
+			_ => None,
+		}
+	}
+}
+
+fn hex_bytes(group: &str) -> Option<Vec<u8>> {
+	group.split('-').map(|x| u8::from_str_radix(x, 16).ok()).collect()
+}
+
+/// A packet of a capture.
+#[derive(Clone, Debug)]
+pub struct Entry {
+	/// The name of the zip entry.
+	pub name: String,
+	pub meta: EntryMeta,
+	pub data: Vec<u8>,
+}
+
+impl Entry {
+	/**
+		Decodes the packet, with `context` keeping track of the components of replicas.
+
+		Like [`from_slice`](crate::from_slice), this requires the whole packet to be read, except for serializations of replicas whose construction isn't known, which can't be fully read.
+	*/
+	pub fn decode<S: ComponentSource, C: ComponentRegistry>(&self, context: &mut CdClientContext<S, C>) -> Res<Message> {
+		let mut reader = context.reader(&self.data[..]);
+		let message = reader.read()?;
+		let remaining = reader.get_ref().len();
+		if remaining > 0 && reader.unknown_network_id().is_none() {
+			return Err(Error::TrailingData { remaining }.into());
+		}
+		Ok(message)
+	}
+}
+
+/// A capture zip archive.
+pub struct Capture<R> {
+	zip: ZipArchive<R>,
+}
+
+impl Capture<BufReader<File>> {
+	pub fn open<P: AsRef<Path>>(path: P) -> Res<Self> {
+		Self::new(BufReader::new(File::open(path)?))
+	}
+}
+
+impl<R: Read + Seek> Capture<R> {
+	pub fn new(reader: R) -> Res<Self> {
+		Ok(Self { zip: ZipArchive::new(reader)? })
+	}
+
+	/// Number of entries.
+	pub fn len(&self) -> usize {
+		self.zip.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.zip.is_empty()
+	}
+
+	/// Reads the entry at `index`, in the order of the archive.
+	pub fn entry(&mut self, index: usize) -> Res<Entry> {
+		let mut file = self.zip.by_index(index)?;
+		let name = file.name().to_string();
+		// the size comes from the archive, so it only serves as a hint
+		let mut data = limits::vec_with_capacity(file.size().try_into().unwrap_or(usize::MAX));
+		file.read_to_end(&mut data)?;
+		let mut meta = EntryMeta::from_name(&name);
+		meta.complete(&data);
+		Ok(Entry { name, meta, data })
+	}
+
+	/// Reads all entries.
+	pub fn entries(&mut self) -> impl Iterator<Item = Res<Entry>> + '_ {
+		(0..self.len()).map(move |i| self.entry(i))
+	}
+
+	/**
+		Decodes the entries for which `filter` returns `true`, in the order of the archive.
+
+		Since replica serializations can only be decoded after their construction, filtering out constructions makes serializations of the same replica undecodable.
+	*/
+	pub fn messages<'a, S, C, F>(&'a mut self, context: &'a mut CdClientContext<S, C>, mut filter: F) -> impl Iterator<Item = Res<(Entry, Message)>> + 'a
+	where
+		S: ComponentSource,
+		C: ComponentRegistry,
+		F: FnMut(&EntryMeta) -> bool + 'a,
+	{
+		self.entries().filter_map(move |entry| {
+			let entry = match entry {
+				Ok(x) => x,
+				Err(err) => return Some(Err(err)),
+			};
+			if !filter(&entry.meta) {
+				return None;
+			}
+			Some(entry.decode(context).map(|message| (entry, message)))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use std::io::{Cursor, Write};
+
+	use zip::ZipWriter;
+	use zip::write::FileOptions;
+
+	use crate::raknet::client::replica::context::CdClientContext;
+	use crate::unified::{Message, UserMessage};
+	use crate::world::server::WorldMessage;
+	use super::{Capture, Direction, EntryMeta};
+
+	#[test]
+	fn test_from_name() {
+		let meta = EntryMeta::from_name("captures/123_[53-05-00-0c]_[e6-00]_(1152921504606846976).bin");
+		assert_eq!(meta, EntryMeta { index: Some(123), raknet_id: Some(0x53), service: Some(5), message_id: Some(12), game_message_id: Some(230), object_id: Some(1152921504606846976), lot: None });
+		assert!(meta.is_subject_game_message());
+		assert_eq!(meta.direction(), Some(Direction::ToClient));
+
+		let meta = EntryMeta::from_name("7_[24]_(2365).bin");
+		assert_eq!((meta.raknet_id, meta.lot, meta.object_id), (Some(0x24), Some(2365), None));
+		assert_eq!(EntryMeta::from_name("8_[53-04-00-05]_[1046].bin").game_message_id, Some(1046));
+		assert_eq!(EntryMeta::from_name("garbage"), EntryMeta::default());
+	}
+
+	#[test]
+	fn test_capture() {
+		let mut zip = ZipWriter::new(Cursor::new(vec![]));
+		let mut data = vec![0x53, 0x04, 0x00];
+		data.extend_from_slice(include_bytes!("world/server/tests/PositionUpdate.bin"));
+		zip.start_file("0_[53-04-00-16].bin", FileOptions::default()).unwrap();
+		zip.write_all(&data).unwrap();
+		let mut data = vec![0x53, 0x04, 0x00];
+		data.extend_from_slice(include_bytes!("world/server/tests/SubjectGameMessage.bin"));
+		zip.start_file("1.bin", FileOptions::default()).unwrap();
+		zip.write_all(&data).unwrap();
+		let zip = zip.finish().unwrap();
+
+		let mut capture = Capture::new(zip).unwrap();
+		assert_eq!(capture.len(), 2);
+		let mut context = CdClientContext::new(HashMap::new());
+		let messages: Vec<_> = capture.messages(&mut context, |meta| meta.is_subject_game_message()).collect::<Result<_, _>>().unwrap();
+		assert_eq!(messages.len(), 1);
+		let (entry, message) = &messages[0];
+		assert_eq!(entry.meta.index, Some(1));
+		assert_eq!(entry.meta.object_id, Some(1152921507012233079));
+		assert_eq!(entry.meta.direction(), Some(Direction::ToServer));
+		assert!(matches!(message, Message::UserMessage(UserMessage::World(WorldMessage::SubjectGameMessage(_)))));
+	}
+}
@@ -1,11 +1,12 @@
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::{MessageFromVariants, VariantTests};
+use lu_packets_derive::{Dissect, EnumDeserialize, MessageFromVariants, VariantTests};
 
 use crate::common::{LuWString33, ObjId};
 use crate::world::client::Message;
 pub use super::{GeneralChatMessage, PrivateChatMessage};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, MessageFromVariants, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, MessageFromVariants, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[post_disc_padding = 9]
 #[repr(u32)]
@@ -15,7 +16,8 @@ pub enum ChatMessage {
 	AchievementNotify(AchievementNotify) = 59,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AchievementNotify {
 	#[padding = 5]
 	pub sender_name: LuWString33,
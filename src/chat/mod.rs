@@ -7,10 +7,14 @@ use std::io::Result as Res;
 
 use endio::{Deserialize, LERead, LEWrite, Serialize};
 use endio::LittleEndian as LE;
+use lu_packets_derive::{Dissect, EnumDeserialize};
 
+use crate::Error;
 use crate::common::{LuVarWString, LuWString33, ObjId};
+use crate::dissect::Dissector;
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum ChatChannel {
 	SystemNotify,
@@ -39,6 +43,7 @@ pub enum ChatChannel {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct GeneralChatMessage {
 	pub chat_channel: ChatChannel,
 	pub sender_name: LuWString33,
@@ -61,7 +66,7 @@ where
 		let chat_channel     = LERead::read(reader)?;
 		let mut str_len: u32 = LERead::read(reader)?;
 		if chat_channel == ChatChannel::Team {
-			str_len -= 1;
+			str_len = str_len.checked_sub(1).ok_or(Error::InvalidString { type_name: "GeneralChatMessage", reason: "missing null terminator" })?;
 		}
 		let sender_name      = LERead::read(reader)?;
 		let sender           = LERead::read(reader)?;
@@ -97,7 +102,24 @@ where
 	}
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+impl crate::dissect::Dissect for GeneralChatMessage {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		let mut str_len = self.message.len() as u32;
+		if self.chat_channel == ChatChannel::Team {
+			str_len += 1;
+		}
+		dissector.field("chat_channel", &self.chat_channel)?;
+		dissector.field("length", &str_len)?;
+		dissector.field("sender_name", &self.sender_name)?;
+		dissector.field("sender", &self.sender)?;
+		dissector.field("source_id", &self.source_id)?;
+		dissector.field("sender_gm_level", &self.sender_gm_level)?;
+		dissect_message(&self.message, dissector)
+	}
+}
+
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum PrivateChatMessageResponseCode {
 	Sent,
@@ -110,6 +132,7 @@ pub enum PrivateChatMessageResponseCode {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PrivateChatMessage {
 	pub chat_channel: ChatChannel,
 	pub sender_name: LuWString33,
@@ -134,8 +157,8 @@ where
 	#[rustfmt::skip]
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let chat_channel       = LERead::read(reader)?;
-		let mut str_len: u32       = LERead::read(reader)?;
-		str_len -= 1;
+		let str_len: u32       = LERead::read(reader)?;
+		let str_len = str_len.checked_sub(1).ok_or(Error::InvalidString { type_name: "PrivateChatMessage", reason: "missing null terminator" })?;
 		let sender_name        = LERead::read(reader)?;
 		let sender             = LERead::read(reader)?;
 		let source_id          = LERead::read(reader)?;
@@ -173,3 +196,28 @@ where
 		LEWrite::write(writer, 0u16)
 	}
 }
+
+impl crate::dissect::Dissect for PrivateChatMessage {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("chat_channel", &self.chat_channel)?;
+		dissector.field("length", &(self.message.len() as u32 + 1))?;
+		dissector.field("sender_name", &self.sender_name)?;
+		dissector.field("sender", &self.sender)?;
+		dissector.field("source_id", &self.source_id)?;
+		dissector.field("sender_gm_level", &self.sender_gm_level)?;
+		dissector.field("recipient_name", &self.recipient_name)?;
+		dissector.field("recipient_gm_level", &self.recipient_gm_level)?;
+		dissector.field("response_code", &self.response_code)?;
+		dissect_message(&self.message, dissector)
+	}
+}
+
+/// Dissects the content of a chat message, whose length is sent separately, and its null terminator.
+fn dissect_message(message: &LuVarWString<u32>, dissector: &mut Dissector) -> Res<()> {
+	dissector.node("message", std::any::type_name::<LuVarWString<u32>>(), |dissector| {
+		dissector.leaf(message, message.len() as u64 * 16);
+		Ok(())
+	})?;
+	dissector.skip(16);
+	Ok(())
+}
@@ -1,11 +1,12 @@
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::VariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, VariantTests};
 
 use crate::common::{LuWString33, ObjId};
 pub use super::{GeneralChatMessage, PrivateChatMessage};
 use super::ChatChannel;
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[post_disc_padding = 9]
 #[repr(u32)]
 pub enum ChatMessage {
@@ -24,7 +25,8 @@ pub enum ChatMessage {
 	RequestMinimumChatModePrivate(RequestMinimumChatModePrivate) = 51,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum AddFriendResponseCode {
 	Accepted,
@@ -33,24 +35,28 @@ pub enum AddFriendResponseCode {
 	Cancelled,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddFriendRequest {
 	pub friend_name: LuWString33,
 	pub is_best_friend: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddFriendResponse {
 	pub response_code: AddFriendResponseCode,
 	pub friend_name: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddIgnore {
 	pub char_name: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum TeamInviteResponseCode {
 	Accepted,
@@ -58,28 +64,33 @@ pub enum TeamInviteResponseCode {
 	GeneralError,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamInvite {
 	pub sender_name: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamInviteResponse {
 	pub response_code: TeamInviteResponseCode,
 	pub sender: ObjId,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamLeave {
 	pub unused: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestMinimumChatMode {
 	pub chat_channel: ChatChannel,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestMinimumChatModePrivate {
 	pub chat_channel: ChatChannel,
 	pub recipient_name: LuWString33,
@@ -8,6 +8,10 @@ use std::io::Result as Res;
 use std::marker::PhantomData;
 
 use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
+use lu_packets_derive::{Dissect, EnumDeserialize};
+
+use crate::{limits, Error};
+use crate::dissect::{Dissector, Layout, Primitive};
 
 pub use self::str::*;
 
@@ -19,6 +23,24 @@ pub use self::str::*;
 #[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct LVec<L, T>(Vec<T>, PhantomData<L>);
 
+/**
+	Element types of [`LVec`], selecting which limit of [`DecodeLimits`](crate::DecodeLimits) applies to its length.
+
+	Strings are vectors of characters, but limited like other strings.
+*/
+pub trait LVecElement {
+	/// Whether the element is a character, limiting the length by `max_string_len` instead of `max_vec_len`.
+	const IS_CHAR: bool = false;
+}
+
+macro_rules! impl_lvec_element {
+	($($ty:ty),*) => {
+		$(impl LVecElement for $ty {})*
+	};
+}
+
+impl_lvec_element!(bool, u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
 impl<L, T> LVec<L, T> {
 	pub fn new() -> Self {
 		Self(Vec::new(), PhantomData)
@@ -34,14 +56,16 @@ impl<L, T> LVec<L, T> {
 
 	pub(crate) fn deser_content<R: Read>(reader: &mut R, len: L) -> Res<Self>
 	where
-		L: TryInto<usize>,
-		T: Deserialize<LE, R>,
+		L: TryInto<usize> + Copy + Into<u64>,
+		T: Deserialize<LE, R> + LVecElement,
 	{
 		let len = match len.try_into() {
 			Ok(x) => x,
-			_ => panic!(),
+			_ => return Err(Error::LengthOverflow { type_name: std::any::type_name::<Self>(), length: len.into() }.into()),
 		};
-		let mut vec = Vec::<T>::with_capacity(len);
+		let type_name = std::any::type_name::<Self>();
+		let len = if T::IS_CHAR { limits::check_string(type_name, len as u64)? } else { limits::check_vec(type_name, len as u64)? };
+		let mut vec = limits::vec_with_capacity::<T>(len);
 		for _ in 0..len {
 			vec.push(LERead::read(reader)?);
 		}
@@ -55,7 +79,7 @@ impl<L, T> LVec<L, T> {
 		let len = self.0.len();
 		let l_len = match L::try_from(len) {
 			Ok(x) => x,
-			_ => panic!(),
+			_ => return Err(Error::LengthOverflow { type_name: std::any::type_name::<Self>(), length: len as u64 }.into()),
 		};
 		writer.write(l_len)
 	}
@@ -69,6 +93,19 @@ impl<L, T> LVec<L, T> {
 		}
 		Ok(())
 	}
+
+	pub(crate) fn dissect_len(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.node("length", std::any::type_name::<L>(), |dissector| {
+			dissector.leaf(&self.0.len(), std::mem::size_of::<L>() as u64 * 8);
+			Ok(())
+		})
+	}
+}
+
+impl<L, T: Clone> Clone for LVec<L, T> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone(), PhantomData)
+	}
 }
 
 impl<L, T: Debug> Debug for LVec<L, T> {
@@ -79,8 +116,8 @@ impl<L, T: Debug> Debug for LVec<L, T> {
 
 impl<L, T, R: Read> Deserialize<LE, R> for LVec<L, T>
 where
-	L: TryInto<usize> + Deserialize<LE, R>,
-	T: Deserialize<LE, R>,
+	L: TryInto<usize> + Copy + Into<u64> + Deserialize<LE, R>,
+	T: Deserialize<LE, R> + LVecElement,
 {
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let len: L = LERead::read(reader)?;
@@ -99,6 +136,20 @@ where
 	}
 }
 
+impl<L, T: crate::dissect::Dissect> crate::dissect::Dissect for LVec<L, T> {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		self.dissect_len(dissector)?;
+		for (i, e) in self.0.iter().enumerate() {
+			dissector.field(&i.to_string(), e)?;
+		}
+		Ok(())
+	}
+
+	fn layout() -> Layout {
+		Layout::List { length: Primitive::unsigned(std::mem::size_of::<L>()), element: Box::new(Layout::of::<T>()) }
+	}
+}
+
 impl<L, T> std::ops::Deref for LVec<L, T> {
 	type Target = Vec<T>;
 
@@ -121,7 +172,23 @@ impl<L, T> From<Vec<T>> for LVec<L, T> {
 	}
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+/// Represented as a sequence of the elements. [`LuVarString`] and [`LuVarWString`] are represented as strings instead.
+#[cfg(feature = "serde")]
+impl<L, T: serde::Serialize> serde::Serialize for LVec<L, T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.0, serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L, T: serde::Deserialize<'de>> serde::Deserialize<'de> for LVec<L, T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		<Vec<T> as serde::Deserialize>::deserialize(deserializer).map(Self::from)
+	}
+}
+
+#[derive(Debug, PartialEq, EnumDeserialize, Dissect, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum ServiceId {
 	General = 0,
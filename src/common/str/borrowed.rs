@@ -0,0 +1,149 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::io::{Error, ErrorKind::UnexpectedEof, Result as Res};
+
+use super::{AsciiChar, LuStr, LuStrExt, LuVarString, LuVarWString, LuWStr, Ucs2Char};
+
+/**
+	An ASCII string borrowed from encoded data, for decoding without allocation.
+
+	The counterpart of [`LuVarString`] for [`DecodeBorrowed`](crate::DecodeBorrowed) implementations. Convert it with [`LuVarString::from`], or borrow it as a [`LuStr`] with [`as_lu_str`](Self::as_lu_str).
+*/
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct LuStrRef<'a>(&'a [u8]);
+
+impl<'a> LuStrRef<'a> {
+	/// Takes a string of `len` characters from the front of `data`.
+	pub(crate) fn take(data: &mut &'a [u8], len: usize) -> Res<Self> {
+		if len > data.len() {
+			return Err(Error::new(UnexpectedEof, "string longer than remaining data"));
+		}
+		let (string, rest) = data.split_at(len);
+		*data = rest;
+		Ok(Self(string))
+	}
+
+	/// Length in characters.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.0
+	}
+
+	/// Characters are single bytes, so unlike wide strings these can be borrowed directly.
+	pub fn as_lu_str(&self) -> &'a LuStr {
+		<LuStr as LuStrExt>::from_slice(self.0)
+	}
+}
+
+impl Debug for LuStrRef<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		LuStrExt::fmt(self.as_lu_str(), f)
+	}
+}
+
+/// Replaces invalid UTF-8 like [`LuStrExt::to_string`].
+impl Display for LuStrRef<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "{}", String::from_utf8_lossy(self.0))
+	}
+}
+
+impl PartialEq<LuStr> for LuStrRef<'_> {
+	fn eq(&self, other: &LuStr) -> bool {
+		self.0 == other.as_slice()
+	}
+}
+
+impl<L> From<LuStrRef<'_>> for LuVarString<L> {
+	fn from(string: LuStrRef<'_>) -> Self {
+		string.0.iter().copied().map(AsciiChar).collect::<Vec<_>>().into()
+	}
+}
+
+/**
+	A UCS-2 string borrowed from encoded data, for decoding without allocation.
+
+	Since the data isn't necessarily aligned, this doesn't borrow a [`LuWStr`], but the bytes of the little-endian code units. Compare it with a [`LuWStr`] directly, or convert it with [`LuVarWString::from`] or [`ToString`].
+*/
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct LuWStrRef<'a>(&'a [u8]);
+
+impl<'a> LuWStrRef<'a> {
+	/// Takes a string of `len` characters from the front of `data`.
+	pub(crate) fn take(data: &mut &'a [u8], len: usize) -> Res<Self> {
+		let byte_len = match len.checked_mul(2) {
+			Some(x) if x <= data.len() => x,
+			_ => return Err(Error::new(UnexpectedEof, "string longer than remaining data")),
+		};
+		let (string, rest) = data.split_at(byte_len);
+		*data = rest;
+		Ok(Self(string))
+	}
+
+	/// Length in characters.
+	pub fn len(&self) -> usize {
+		self.0.len() / 2
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// The code units of the string.
+	pub fn units(&self) -> impl Iterator<Item = u16> + 'a {
+		self.0.chunks_exact(2).map(|x| u16::from_le_bytes([x[0], x[1]]))
+	}
+}
+
+impl Debug for LuWStrRef<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "{:?}", self.to_string())
+	}
+}
+
+/// Replaces invalid UCS-2 like [`LuStrExt::to_string`].
+impl Display for LuWStrRef<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		for c in std::char::decode_utf16(self.units()) {
+			write!(f, "{}", c.unwrap_or(std::char::REPLACEMENT_CHARACTER))?;
+		}
+		Ok(())
+	}
+}
+
+impl PartialEq<LuWStr> for LuWStrRef<'_> {
+	fn eq(&self, other: &LuWStr) -> bool {
+		self.len() == other.len() && self.units().eq(other.as_slice().iter().copied())
+	}
+}
+
+impl<L> From<LuWStrRef<'_>> for LuVarWString<L> {
+	fn from(string: LuWStrRef<'_>) -> Self {
+		string.units().map(Ucs2Char).collect::<Vec<_>>().into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryInto;
+
+	use crate::common::{LuVarString, LuVarWString};
+	use super::{LuStrRef, LuWStrRef};
+
+	#[test]
+	fn test_take() {
+		let mut data = &b"abc\x64\x00\x65\x00rest"[..];
+		let string = LuStrRef::take(&mut data, 3).unwrap();
+		let wstring = LuWStrRef::take(&mut data, 2).unwrap();
+		assert_eq!(data, b"rest");
+		assert_eq!(LuVarString::<u8>::from(string), b"abc".try_into().unwrap());
+		assert_eq!(LuVarWString::<u8>::from(wstring), "de".try_into().unwrap());
+		assert!(LuStrRef::take(&mut data, 5).is_err());
+	}
+}
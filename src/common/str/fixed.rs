@@ -4,13 +4,15 @@ use std::io::Result as Res;
 
 use endio::{Deserialize, LE, Serialize};
 
+use crate::dissect::{Dissect, Dissector, Layout};
+
 use super::{AbstractLuStr, AsciiChar, AsciiError, LuChar, LuStrExt, Ucs2Char, Ucs2Error};
 
 // todo[const generics]: const generic strings
 // todo: exclude the final null terminator from the array
 macro_rules! abstract_lu_str {
 	($name:ident, $c:ty, $null:expr, $n:literal) => {
-		// todo: runtime type invariants checks (valid, null terminator)
+		// todo: runtime type invariants checks (valid)
 		/// A string with a maximum length of $n.
 		pub struct $name([$c; $n]);
 
@@ -31,7 +33,7 @@ macro_rules! abstract_lu_str {
 
 			#[inline]
 			fn deref(&self) -> &Self::Target {
-				let terminator = self.0.iter().position(|&c| c == $null).unwrap();
+				let terminator = self.0.iter().position(|&c| c == $null).unwrap_or($n);
 				&self.0[..terminator]
 			}
 		}
@@ -39,7 +41,7 @@ macro_rules! abstract_lu_str {
 		impl std::ops::DerefMut for $name {
 			#[inline]
 			fn deref_mut(&mut self) -> &mut Self::Target {
-				let terminator = self.0.iter().position(|&c| c == $null).unwrap();
+				let terminator = self.0.iter().position(|&c| c == $null).unwrap_or($n);
 				&mut self.0[..terminator]
 			}
 		}
@@ -47,7 +49,8 @@ macro_rules! abstract_lu_str {
 		impl<R: Read> Deserialize<LE, R> for $name {
 			fn deserialize(reader: &mut R) -> Res<Self> {
 				let mut bytes = [0u8; $n * std::mem::size_of::<$c>()];
-				reader.read(&mut bytes)?;
+				reader.read_exact(&mut bytes)?;
+				// strings filling the whole array have no null terminator
 				Ok(Self(unsafe { std::mem::transmute(bytes) }))
 			}
 		}
@@ -58,6 +61,26 @@ macro_rules! abstract_lu_str {
 				writer.write_all(&x)
 			}
 		}
+
+		impl Dissect for $name {
+			fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+				dissector.leaf(self, ($n * std::mem::size_of::<$c>() * 8) as u64);
+				Ok(())
+			}
+
+			fn layout() -> Layout {
+				Layout::FixedStr { len: $n, wide: std::mem::size_of::<$c>() == 2 }
+			}
+		}
+
+		/// Represented as a string, up to the null terminator. Strings that aren't valid UTF-8 or UTF-16 are an error.
+		#[cfg(feature = "serde")]
+		impl serde::Serialize for $name {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				let string = (&**self).to_exact_string().ok_or_else(|| <S::Error as serde::ser::Error>::custom(concat!("invalid ", stringify!($name))))?;
+				serializer.serialize_str(&string)
+			}
+		}
 	};
 }
 
@@ -69,7 +92,7 @@ macro_rules! lu_str {
 			type Error = AsciiError;
 
 			fn try_from(string: &[u8]) -> Result<Self, Self::Error> {
-				if string.len() >= $n {
+				if string.len() > $n {
 					// actually length error but whatever
 					return Err(AsciiError);
 				}
@@ -90,6 +113,14 @@ macro_rules! lu_str {
 				Self::try_from(&string[..])
 			}
 		}
+
+		#[cfg(feature = "serde")]
+		impl<'de> serde::Deserialize<'de> for $name {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let string = <String as serde::Deserialize>::deserialize(deserializer)?;
+				Self::try_from(string.as_bytes()).map_err(|_| serde::de::Error::custom(concat!("string too long for ", stringify!($name))))
+			}
+		}
 	};
 }
 
@@ -102,7 +133,7 @@ macro_rules! lu_wstr {
 
 			fn try_from(string: &str) -> Result<Self, Self::Error> {
 				let mut bytes = [0u16; $n];
-				for (i, chr) in string.encode_utf16().take($n - 1).enumerate() {
+				for (i, chr) in string.encode_utf16().take($n).enumerate() {
 					bytes[i] = chr;
 				}
 				let bytes = unsafe { std::mem::transmute(bytes) };
@@ -110,9 +141,20 @@ macro_rules! lu_wstr {
 			}
 		}
 
+		#[cfg(feature = "serde")]
+		impl<'de> serde::Deserialize<'de> for $name {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let string = <String as serde::Deserialize>::deserialize(deserializer)?;
+				if string.encode_utf16().count() > $n {
+					return Err(serde::de::Error::custom(concat!("string too long for ", stringify!($name))));
+				}
+				Self::try_from(&string[..]).map_err(|_| serde::de::Error::custom("invalid wstring"))
+			}
+		}
+
 		impl From<&$name> for String {
 			fn from(wstr: &$name) -> Self {
-				String::from_utf16(unsafe { &*(&**wstr as *const [Ucs2Char] as *const [<Ucs2Char as LuChar>::Int]) }).unwrap()
+				String::from_utf16_lossy(unsafe { &*(&**wstr as *const [Ucs2Char] as *const [<Ucs2Char as LuChar>::Int]) })
 			}
 		}
 	};
@@ -129,3 +171,53 @@ lu_wstr!(LuWString50, 50);
 lu_wstr!(LuWString128, 128);
 lu_wstr!(LuWString256, 256);
 lu_wstr!(LuWString400, 400);
+
+#[cfg(test)]
+mod tests {
+	#[cfg(feature = "json")]
+	use std::convert::TryFrom;
+
+	use endio::LERead;
+
+	use super::{LuString3, LuWString32};
+
+	#[test]
+	fn test_full_length() {
+		let string: LuString3 = LERead::read(&mut &b"abc"[..]).unwrap();
+		assert_eq!(string.len(), 3);
+		let mut bytes = vec![b'a', 0].repeat(32);
+		bytes[62] = b'b';
+		let string: LuWString32 = LERead::read(&mut &bytes[..]).unwrap();
+		assert_eq!(String::from(&string), format!("{}b", "a".repeat(31)));
+	}
+
+	#[test]
+	fn test_terminated() {
+		let string: LuString3 = LERead::read(&mut &b"a\0c"[..]).unwrap();
+		assert_eq!(string.len(), 1);
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn test_json() {
+		let string: LuString3 = LERead::read(&mut &b"abc"[..]).unwrap();
+		let json = serde_json::to_string(&string).unwrap();
+		assert_eq!(json, r#""abc""#);
+		assert_eq!(serde_json::from_str::<LuString3>(&json).unwrap(), string);
+		assert!(serde_json::from_str::<LuString3>(r#""abcd""#).is_err());
+
+		let string = LuWString32::try_from(&"a".repeat(32)[..]).unwrap();
+		assert_eq!(string.len(), 32);
+		let json = serde_json::to_string(&string).unwrap();
+		assert_eq!(serde_json::from_str::<LuWString32>(&json).unwrap(), string);
+		assert!(serde_json::from_str::<LuWString32>(&format!(r#""{}""#, "a".repeat(33))).is_err());
+
+		let string: LuString3 = LERead::read(&mut &b"\xffbc"[..]).unwrap();
+		assert!(serde_json::to_string(&string).is_err());
+		// unpaired surrogate
+		let mut bytes = vec![0; 64];
+		bytes[1] = 0xd8;
+		let string: LuWString32 = LERead::read(&mut &bytes[..]).unwrap();
+		assert!(serde_json::to_string(&string).is_err());
+	}
+}
@@ -1,8 +1,12 @@
+mod borrowed;
 mod fixed;
 mod variable;
 
 use endio::{Deserialize, Serialize};
 
+use super::LVecElement;
+
+pub use self::borrowed::*;
 pub use self::fixed::*;
 pub use self::variable::*;
 
@@ -32,6 +36,14 @@ impl LuChar for Ucs2Char {
 	type Error = Ucs2Error;
 }
 
+impl LVecElement for AsciiChar {
+	const IS_CHAR: bool = true;
+}
+
+impl LVecElement for Ucs2Char {
+	const IS_CHAR: bool = true;
+}
+
 impl From<u8> for AsciiChar {
 	fn from(byte: u8) -> Self {
 		// todo: range check
@@ -60,6 +72,8 @@ pub trait LuStrExt {
 
 	fn as_slice(&self) -> &[<Self::Char as LuChar>::Int];
 	fn to_string(&self) -> String;
+	/// The string, if it can be converted without replacing anything.
+	fn to_exact_string(&self) -> Option<String>;
 
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error>;
 }
@@ -72,7 +86,11 @@ impl LuStrExt for LuStr {
 	}
 
 	fn to_string(&self) -> String {
-		std::str::from_utf8(self.as_slice()).unwrap().into()
+		String::from_utf8_lossy(self.as_slice()).into_owned()
+	}
+
+	fn to_exact_string(&self) -> Option<String> {
+		String::from_utf8(self.as_slice().to_vec()).ok()
 	}
 
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
@@ -88,7 +106,11 @@ impl LuStrExt for LuWStr {
 	}
 
 	fn to_string(&self) -> String {
-		String::from_utf16(self.as_slice()).unwrap()
+		String::from_utf16_lossy(self.as_slice())
+	}
+
+	fn to_exact_string(&self) -> Option<String> {
+		String::from_utf16(self.as_slice()).ok()
 	}
 
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
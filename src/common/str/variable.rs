@@ -2,6 +2,7 @@ use std::convert::{TryFrom};
 use std::marker::PhantomData;
 
 use crate::common::LVec;
+use crate::dissect::{Dissect, Dissector, Layout, Primitive};
 use super::{AsciiChar, AsciiError, LuStrExt, LuWStr, Ucs2Char, Ucs2Error};
 
 pub type LuVarString<L> = LVec<L, AsciiChar>;
@@ -19,6 +20,30 @@ impl<L> std::fmt::Debug for LuVarWString<L> {
 	}
 }
 
+impl<L> Dissect for LuVarString<L> {
+	fn dissect(&self, dissector: &mut Dissector) -> std::io::Result<()> {
+		self.dissect_len(dissector)?;
+		dissector.leaf(self, self.len() as u64 * 8);
+		Ok(())
+	}
+
+	fn layout() -> Layout {
+		Layout::VarStr { length: Primitive::unsigned(std::mem::size_of::<L>()), wide: false }
+	}
+}
+
+impl<L> Dissect for LuVarWString<L> {
+	fn dissect(&self, dissector: &mut Dissector) -> std::io::Result<()> {
+		self.dissect_len(dissector)?;
+		dissector.leaf(self, self.len() as u64 * 16);
+		Ok(())
+	}
+
+	fn layout() -> Layout {
+		Layout::VarStr { length: Primitive::unsigned(std::mem::size_of::<L>()), wide: true }
+	}
+}
+
 impl<L> From<&LuVarString<L>> for String {
 	fn from(string: &LuVarString<L>) -> String {
 		(&**string).to_string()
@@ -64,3 +89,37 @@ impl<L> TryFrom<&str> for LuVarWString<L> {
 		Ok(Self(chars, PhantomData))
 	}
 }
+
+/// Represented as a string. Strings that aren't valid UTF-8 are an error, which doesn't happen with ASCII.
+#[cfg(feature = "serde")]
+impl<L> serde::Serialize for LuVarString<L> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let string = (&**self).to_exact_string().ok_or_else(|| <S::Error as serde::ser::Error>::custom("invalid string"))?;
+		serializer.serialize_str(&string)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L> serde::Deserialize<'de> for LuVarString<L> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let string = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Self::try_from(string.as_bytes()).map_err(|_| serde::de::Error::custom("invalid string"))
+	}
+}
+
+/// Represented as a string. Unpaired surrogates are an error.
+#[cfg(feature = "serde")]
+impl<L> serde::Serialize for LuVarWString<L> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let string = (&**self).to_exact_string().ok_or_else(|| <S::Error as serde::ser::Error>::custom("invalid wstring"))?;
+		serializer.serialize_str(&string)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L> serde::Deserialize<'de> for LuVarWString<L> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let string = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Self::try_from(&string[..]).map_err(|_| serde::de::Error::custom("invalid wstring"))
+	}
+}
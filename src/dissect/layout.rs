@@ -0,0 +1,117 @@
+use std::any::type_name;
+use std::fmt::{Debug, Formatter};
+
+use super::Dissect;
+
+/**
+	The encoding of a type, independent of any particular value.
+
+	Where [`Field`](super::Field) describes a message that has been decoded, `Layout` describes all messages of a type, including the choices between them, such as which variant an enum discriminant selects or whether a flag bit is followed by a value. This is what's needed to generate dissectors for other tools, see [`wireshark`](super::wireshark).
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout {
+	/// A number or address of fixed size.
+	Value(Primitive),
+	/// A single bit, used for `bool`s in bit streams.
+	Bit,
+	/// A string of `len` characters, padded with nulls. Wide strings use UCS-2 characters.
+	FixedStr { len: u64, wide: bool },
+	/// A string prefixed with its length in characters.
+	VarStr { length: Primitive, wide: bool },
+	/// A list prefixed with its length in elements.
+	List { length: Primitive, element: Box<Layout> },
+	/// A list of `len` elements, without a length prefix.
+	Array { len: u64, element: Box<Layout> },
+	/// Named fields, in the order they are encoded. Padding has an empty name, as do fields whose subfields should appear directly in the parent.
+	Struct(Vec<(&'static str, Layout)>),
+	/// Bits that are ignored when decoding.
+	Padding(u64),
+	/// A bit named `flag`, followed by `value` if the bit is set.
+	Flagged { flag: &'static str, value: Box<Layout> },
+	/// A discriminant, followed by the layout of the variant it selects.
+	Enum { discriminant: Primitive, variants: Vec<Variant> },
+	/// Padding up to the next byte boundary, as at the end of bit streams.
+	Align,
+	/// The layout of another type, referenced so that its definition can be shared.
+	Type(TypeRef),
+	/// Data that can't be described without decoding it, for example because it depends on context or is compressed. Extends to the end of the message.
+	Opaque,
+}
+
+impl Layout {
+	/// A reference to the layout of `T`.
+	pub fn of<T: Dissect>() -> Self {
+		Self::Type(TypeRef { name: type_name::<T>(), layout: T::layout })
+	}
+}
+
+/// Fixed size values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+	Bool,
+	U8,
+	U16,
+	U32,
+	U64,
+	I8,
+	I16,
+	I32,
+	I64,
+	F32,
+	F64,
+	/// An IPv4 address, with the octets in order.
+	Ipv4,
+}
+
+impl Primitive {
+	/// The unsigned integer of `size` bytes, as used for length prefixes.
+	pub fn unsigned(size: usize) -> Self {
+		match size {
+			1 => Self::U8,
+			2 => Self::U16,
+			4 => Self::U32,
+			8 => Self::U64,
+			_ => panic!("no unsigned integer with {} bytes", size),
+		}
+	}
+
+	/// The size when encoded, in bits.
+	pub fn bits(self) -> u64 {
+		match self {
+			Self::Bool | Self::U8 | Self::I8 => 8,
+			Self::U16 | Self::I16 => 16,
+			Self::U32 | Self::I32 | Self::F32 | Self::Ipv4 => 32,
+			Self::U64 | Self::I64 | Self::F64 => 64,
+		}
+	}
+}
+
+/// A variant of [`Layout::Enum`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+	pub name: &'static str,
+	pub discriminant: i64,
+	/// The fields following the discriminant, including padding after it.
+	pub layout: Layout,
+}
+
+/// A reference to the layout of a type, which is only created on demand, so that types can contain themselves.
+#[derive(Clone, Copy)]
+pub struct TypeRef {
+	/// Name of the type, as returned by [`std::any::type_name`].
+	pub name: &'static str,
+	pub layout: fn() -> Layout,
+}
+
+impl Debug for TypeRef {
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		f.debug_tuple("TypeRef").field(&self.name).finish()
+	}
+}
+
+/// Type names are unique, so comparing them is enough.
+impl PartialEq for TypeRef {
+	fn eq(&self, other: &Self) -> bool {
+		self.name == other.name
+	}
+}
@@ -0,0 +1,398 @@
+/*!
+	Walking decoded messages as trees of fields, for debugging tools.
+
+	[`dissect`] returns the fields of a message with their names, types, values, and positions in the encoded message. This can be used for annotated hexdumps, showing for example which bits of a [`ReplicaConstruction`](crate::raknet::client::replica::ReplicaConstruction) belong to which `Option` flag and field.
+
+	Positions are in bits from the start of the message, with bit 0 being the most significant bit of the first byte, as in LU's bit streams.
+
+	```
+	use lu_packets::dissect::dissect;
+	use lu_packets::raknet::client::replica::{ParentChildInfo, ParentInfo};
+
+	let info = ParentChildInfo { parent_info: Some(ParentInfo { parent_id: 42, update_position_with_parent: true }), child_info: None };
+	let field = dissect(&info).unwrap();
+	let parent_info = field.get("parent_info").unwrap();
+	assert_eq!(parent_info.get("is_some").unwrap().span, 0..1);
+	assert_eq!(parent_info.get("value").unwrap().get("parent_id").unwrap().span, 1..65);
+	assert_eq!(field.get("child_info").unwrap().span, 66..67);
+	```
+
+	### Notes
+	Dissection walks the decoded message, adding up the sizes of its fields. Most fields have a size that follows from their value, such as numbers and length-prefixed lists. Values without one, such as [`LuNameValue`](crate::world::LuNameValue) and [`Amf3`](crate::world::amf3::Amf3), are measured by decoding them again from the original data with [`dissect_slice`], so that the positions match the data even where encoding the value again would differ. [`dissect`] has no original data and measures them by encoding them instead. Custom components of replicas need context to be decoded and are always measured by encoding them. Data that is ignored when decoding, such as padding, is skipped and doesn't appear as a field.
+
+	The `GameMessage`, `ReplicaSerde` and endio `Deserialize` derives generate [`Dissect`] implementations following the layout they use for (de-)serialization. Parameters of game messages are dissected as leaves, with the value of the parameter.
+
+	The derived implementations also describe the [`Layout`] of the type, which [`wireshark`] uses to generate a dissector for live traffic. Types with hand-written encodings mostly use [`Layout::Opaque`].
+*/
+mod layout;
+pub mod wireshark;
+
+use std::any::type_name;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{Error as IoError, ErrorKind::UnexpectedEof, Read, Result as Res, Write};
+use std::mem::size_of;
+use std::net::Ipv4Addr;
+use std::ops::Range;
+
+use endio::{Deserialize, LE};
+use endio_bit::{BEBitReader, BEBitWriter};
+
+pub use self::layout::*;
+
+/// A field of a dissected message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+	/// Name of the field, or its index for tuple fields and list elements.
+	pub name: String,
+	/// Type of the field, as returned by [`std::any::type_name`].
+	pub type_name: &'static str,
+	/// The value as formatted by `Debug`, for fields that aren't broken down further. For enums, the name of the variant.
+	pub value: Option<String>,
+	/// Position of the field in the encoded message, in bits.
+	pub span: Range<u64>,
+	/// Subfields in the order they are encoded.
+	pub children: Vec<Field>,
+}
+
+impl Field {
+	/// The bytes containing the field, including the bytes only partially belonging to it.
+	pub fn byte_span(&self) -> Range<u64> {
+		self.span.start / 8..(self.span.end + 7) / 8
+	}
+
+	/// The subfield named `name`, if any.
+	pub fn get(&self, name: &str) -> Option<&Field> {
+		self.children.iter().find(|x| x.name == name)
+	}
+
+	fn fmt_indented(&self, f: &mut Formatter, indent: usize) -> std::fmt::Result {
+		write!(f, "{:indent$}", "", indent = indent * 2)?;
+		if !self.name.is_empty() {
+			write!(f, "{}: ", self.name)?;
+		}
+		write!(f, "{}", short_type_name(self.type_name))?;
+		if let Some(value) = &self.value {
+			write!(f, " = {}", value)?;
+		}
+		writeln!(f, " [{}.{}..{}.{}]", self.span.start / 8, self.span.start % 8, self.span.end / 8, self.span.end % 8)?;
+		for child in &self.children {
+			child.fmt_indented(f, indent + 1)?;
+		}
+		Ok(())
+	}
+}
+
+/// Prints the tree of fields, one per line, with spans as `byte.bit`.
+impl Display for Field {
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		self.fmt_indented(f, 0)
+	}
+}
+
+/// `type_name` without the module paths.
+fn short_type_name(name: &str) -> String {
+	let mut short = String::new();
+	let mut path = String::new();
+	for c in name.chars() {
+		if c.is_alphanumeric() || c == '_' || c == ':' {
+			path.push(c);
+		} else {
+			short.push_str(path.rsplit("::").next().unwrap());
+			path.clear();
+			short.push(c);
+		}
+	}
+	short.push_str(path.rsplit("::").next().unwrap());
+	short
+}
+
+/// Types that can be broken down into fields, see the [module documentation](self).
+pub trait Dissect {
+	/// Records the fields of `self` with `dissector`, in the order they are encoded.
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()>;
+
+	/// The layout of all values of the type.
+	fn layout() -> Layout
+	where
+		Self: Sized,
+	{
+		Layout::Opaque
+	}
+}
+
+/// Builds the tree of fields, keeping track of the position in the encoded message.
+pub struct Dissector {
+	pos: u64,
+	/// The data the message was decoded from, if known.
+	source: Option<Vec<u8>>,
+	value: Option<String>,
+	fields: Vec<Field>,
+}
+
+impl Dissector {
+	/// The current position in the encoded message, in bits.
+	pub fn position(&self) -> u64 {
+		self.pos
+	}
+
+	/// Adds a field named `name` containing `value`.
+	pub fn field<T: Dissect + ?Sized>(&mut self, name: &str, value: &T) -> Res<()> {
+		self.node(name, type_name::<T>(), |dissector| value.dissect(dissector))
+	}
+
+	/// Adds a field named `name`, with the subfields and value recorded by `f`.
+	pub fn node(&mut self, name: &str, type_name: &'static str, f: impl FnOnce(&mut Self) -> Res<()>) -> Res<()> {
+		let start = self.pos;
+		let fields = std::mem::take(&mut self.fields);
+		let value = self.value.take();
+		let res = f(self);
+		let children = std::mem::replace(&mut self.fields, fields);
+		let own_value = std::mem::replace(&mut self.value, value);
+		res?;
+		self.fields.push(Field { name: name.into(), type_name, value: own_value, span: start..self.pos, children });
+		Ok(())
+	}
+
+	/// Adds a single bit field named `name`.
+	pub fn bit(&mut self, name: &str, value: bool) -> Res<()> {
+		self.node(name, "bool", |dissector| {
+			dissector.leaf(&value, 1);
+			Ok(())
+		})
+	}
+
+	/// Adds a field named `name` for an optional value of a replica bit stream, which is prefixed with a bit indicating whether the value is present.
+	pub fn flagged<T: Dissect>(&mut self, name: &str, value: &Option<T>) -> Res<()> {
+		self.node(name, type_name::<Option<T>>(), |dissector| {
+			dissector.bit("is_some", value.is_some())?;
+			if let Some(x) = value {
+				dissector.field("value", x)?;
+			}
+			Ok(())
+		})
+	}
+
+	/// Sets the value of the current field to `value`, which takes up `bits` bits.
+	pub fn leaf<T: Debug + ?Sized>(&mut self, value: &T, bits: u64) {
+		self.value = Some(format!("{:?}", value));
+		self.pos += bits;
+	}
+
+	/// Sets the value of the current field to `value`, with a size determined by encoding it with `ser`.
+	pub fn encoded<T: Debug + ?Sized>(&mut self, value: &T, ser: impl FnOnce(&mut BEBitWriter<Vec<u8>>) -> Res<()>) -> Res<()> {
+		let bits = encoded_len(ser)?;
+		self.leaf(value, bits);
+		Ok(())
+	}
+
+	/**
+		Sets the value of the current field to `value`, with a size determined by decoding it again with `de` from the data the message was decoded from.
+
+		If that data isn't known, the size is determined by encoding the value with `ser` instead, as with [`encoded`](Self::encoded).
+	*/
+	pub fn decoded<T: Debug + ?Sized>(&mut self, value: &T, ser: impl FnOnce(&mut BEBitWriter<Vec<u8>>) -> Res<()>, de: impl FnOnce(&mut BEBitReader<SourceReader>) -> Res<()>) -> Res<()> {
+		let bits = match &self.source {
+			Some(source) => decoded_len(source, self.pos, de)?,
+			None => encoded_len(ser)?,
+		};
+		self.leaf(value, bits);
+		Ok(())
+	}
+
+	/// Sets the value of the current field to the variant name `name`.
+	pub fn variant(&mut self, name: &str) {
+		self.value = Some(name.into());
+	}
+
+	/// Skips `bits` bits of padding.
+	pub fn skip(&mut self, bits: u64) {
+		self.pos += bits;
+	}
+
+	/// Skips to the next byte boundary, as at the end of bit streams.
+	pub fn align(&mut self) {
+		self.pos = (self.pos + 7) / 8 * 8;
+	}
+}
+
+/// The number of bits written by `ser`.
+fn encoded_len(ser: impl FnOnce(&mut BEBitWriter<Vec<u8>>) -> Res<()>) -> Res<u64> {
+	let mut writer = BEBitWriter::new(vec![]);
+	ser(&mut writer)?;
+	// marks the end, since flushing pads to the next byte
+	writer.write_bit(true)?;
+	writer.flush()?;
+	let bytes = writer.get_ref();
+	let last = bytes[bytes.len() - 1];
+	Ok(bytes.len() as u64 * 8 - last.trailing_zeros() as u64 - 1)
+}
+
+/// Reads the data a message was decoded from, for [`Dissector::decoded`], counting the bytes read.
+pub struct SourceReader<'a> {
+	data: &'a [u8],
+	read: usize,
+}
+
+impl Read for SourceReader<'_> {
+	fn read(&mut self, buf: &mut [u8]) -> Res<usize> {
+		let n = (&self.data[self.read..]).read(buf)?;
+		self.read += n;
+		Ok(n)
+	}
+}
+
+/// The number of bits read by `de` from `source`, starting at bit `start`.
+fn decoded_len(source: &[u8], start: u64, de: impl FnOnce(&mut BEBitReader<SourceReader>) -> Res<()>) -> Res<u64> {
+	let first = (start / 8) as usize;
+	let shift = start % 8;
+	if first > source.len() {
+		return Err(IoError::new(UnexpectedEof, "field starts after the end of the data"));
+	}
+	// the data from `start` on, shifted to start at a byte boundary
+	let mut data: Vec<u8> = (first..source.len())
+		.map(|i| {
+			let next = source.get(i + 1).copied().unwrap_or(0) as u16;
+			(((source[i] as u16) << 8 | next) >> (8 - shift)) as u8
+		})
+		.collect();
+	let available = data.len() as u64 * 8 - shift;
+	// lets the bits left in the last byte be counted below
+	data.push(0);
+	let mut reader = BEBitReader::new(SourceReader { data: &data, read: 0 });
+	de(&mut reader)?;
+	let read = reader.get_ref().read;
+	// the bit reader only reads the next byte once the current one is used up
+	let mut left = 0;
+	loop {
+		reader.read_bit()?;
+		if reader.get_ref().read > read {
+			break;
+		}
+		left += 1;
+	}
+	let bits = read as u64 * 8 - left;
+	if bits > available {
+		return Err(IoError::new(UnexpectedEof, "field extends past the end of the data"));
+	}
+	Ok(bits)
+}
+
+/// The `type_name` of the type of `value`, for types that are tedious to spell out.
+pub(crate) fn type_name_of<T: ?Sized>(_value: &T) -> &'static str {
+	type_name::<T>()
+}
+
+/// Breaks `value` down into a tree of fields, with `value` at the root.
+pub fn dissect<T: Dissect + ?Sized>(value: &T) -> Res<Field> {
+	dissect_with_source(value, None)
+}
+
+/**
+	Decodes a `T` from `data` like [`from_slice`](crate::from_slice), and breaks it down into a tree of fields.
+
+	Unlike [`dissect`], values without a fixed size are measured in `data`, see the [module documentation](self).
+*/
+pub fn dissect_slice<'a, T: Dissect + Deserialize<LE, &'a [u8]>>(data: &'a [u8]) -> Result<Field, crate::Error> {
+	let value: T = crate::from_slice(data)?;
+	Ok(dissect_with_source(&value, Some(data.to_vec()))?)
+}
+
+fn dissect_with_source<T: Dissect + ?Sized>(value: &T, source: Option<Vec<u8>>) -> Res<Field> {
+	let mut dissector = Dissector { pos: 0, source, value: None, fields: vec![] };
+	dissector.field("", value)?;
+	Ok(dissector.fields.pop().unwrap())
+}
+
+macro_rules! impl_dissect_primitive {
+	($($ty:ty => $prim:ident),*) => {
+		$(
+			impl Dissect for $ty {
+				fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+					dissector.leaf(self, size_of::<$ty>() as u64 * 8);
+					Ok(())
+				}
+
+				fn layout() -> Layout {
+					Layout::Value(Primitive::$prim)
+				}
+			}
+		)*
+	};
+}
+
+impl_dissect_primitive!(bool => Bool, u8 => U8, u16 => U16, u32 => U32, u64 => U64, i8 => I8, i16 => I16, i32 => I32, i64 => I64, f32 => F32, f64 => F64);
+
+impl Dissect for Ipv4Addr {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.leaf(self, 32);
+		Ok(())
+	}
+
+	fn layout() -> Layout {
+		Layout::Value(Primitive::Ipv4)
+	}
+}
+
+impl<T: Dissect, const N: usize> Dissect for [T; N] {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		for (i, x) in self.iter().enumerate() {
+			dissector.field(&i.to_string(), x)?;
+		}
+		Ok(())
+	}
+
+	fn layout() -> Layout {
+		Layout::Array { len: N as u64, element: Box::new(Layout::of::<T>()) }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use endio::LERead;
+
+	use crate::raknet::client::replica::{ChildInfo, ParentChildInfo};
+	use crate::world::amf3::Amf3;
+	use super::{decoded_len, dissect, dissect_slice};
+
+	#[test]
+	fn test_spans() {
+		let info = ParentChildInfo { parent_info: None, child_info: Some(ChildInfo { child_ids: vec![1, 2].into() }) };
+		let field = dissect(&info).unwrap();
+		assert_eq!(field.span, 0..146);
+		assert_eq!(field.byte_span(), 0..19);
+		let child_info = field.get("child_info").unwrap();
+		assert_eq!(child_info.span, 1..146);
+		assert_eq!(child_info.get("is_some").unwrap().value.as_deref(), Some("true"));
+		let child_ids = child_info.get("value").unwrap().get("child_ids").unwrap();
+		assert_eq!(child_ids.get("length").unwrap().span, 2..18);
+		assert_eq!(child_ids.get("1").unwrap().span, 82..146);
+		assert_eq!(child_ids.get("1").unwrap().value.as_deref(), Some("2"));
+	}
+
+	#[test]
+	fn test_display() {
+		let info = ParentChildInfo { parent_info: None, child_info: None };
+		let field = dissect(&info).unwrap();
+		assert_eq!(field.to_string(), "ParentChildInfo [0.0..0.2]\n  parent_info: Option<ParentInfo> [0.0..0.1]\n    is_some: bool = false [0.0..0.1]\n  child_info: Option<ChildInfo> [0.1..0.2]\n    is_some: bool = false [0.1..0.2]\n");
+	}
+	#[test]
+	fn test_decoded_len() {
+		let mut value = 0u8;
+		let len = decoded_len(&[0x0f, 0xf0], 4, |reader| {
+			value = LERead::read(reader)?;
+			Ok(())
+		});
+		assert_eq!(len.unwrap(), 8);
+		assert_eq!(value, 0xff);
+		assert_eq!(decoded_len(&[0x80], 0, |reader| reader.read_bit().map(drop)).unwrap(), 1);
+		assert!(decoded_len(&[0x0f], 4, |reader| LERead::read::<u8>(reader).map(drop)).is_err());
+	}
+
+	#[test]
+	fn test_source_spans() {
+		// the second string would be written as a reference to the first when encoded again
+		let data = b"\x09\x05\x01\x06\x03a\x06\x03a";
+		assert_eq!(dissect_slice::<Amf3>(data).unwrap().span, 0..72);
+		assert_eq!(dissect(&crate::from_slice::<Amf3>(data).unwrap()).unwrap().span, 0..64);
+	}
+}
@@ -0,0 +1,190 @@
+-- Interpreter for the tables above.
+-- F holds the fields, with F[1] for undecoded data, T the structs and enums, ROOT the layout of a message, and IDS the RakNet message IDs to register for.
+-- Positions are in bits, with bit 0 being the most significant bit of the first byte.
+
+lu.fields = F
+
+-- raised when reaching data that can't be dissected
+local STOP = {}
+
+local SIZES = {bool = 1, u8 = 1, u16 = 2, u32 = 4, u64 = 8, i8 = 1, i16 = 2, i32 = 4, i64 = 8, f32 = 4, f64 = 8, ipv4 = 4}
+local FORMATS = {u8 = "<I1", u16 = "<I2", u32 = "<I4", u64 = "<E", i8 = "<i1", i16 = "<i2", i32 = "<i4", i64 = "<e", f32 = "<f", f64 = "<d"}
+
+local function check(ctx, bits)
+	if ctx.pos + bits > ctx.tvb:len() * 8 then
+		error("message is shorter than expected", 0)
+	end
+end
+
+local function read_bit(ctx)
+	check(ctx, 1)
+	local pos = ctx.pos
+	ctx.pos = pos + 1
+	return ctx.tvb(math.floor(pos / 8), 1):bitfield(pos % 8, 1) == 1
+end
+
+local function read_bytes(ctx, len)
+	check(ctx, len * 8)
+	local tvb, pos = ctx.tvb, ctx.pos
+	ctx.pos = pos + len * 8
+	if pos % 8 == 0 then
+		return tvb:raw(math.floor(pos / 8), len)
+	end
+	local bytes = {}
+	for i = 0, len - 1 do
+		bytes[i + 1] = string.char(tvb(math.floor(pos / 8) + i, 2):bitfield(pos % 8, 8))
+	end
+	return table.concat(bytes)
+end
+
+local function read_value(ctx, t)
+	local raw = read_bytes(ctx, SIZES[t])
+	if t == "bool" then
+		return raw:byte() ~= 0
+	elseif t == "ipv4" then
+		return Address.ip(string.format("%d.%d.%d.%d", raw:byte(1, 4)))
+	end
+	return (Struct.unpack(FORMATS[t], raw))
+end
+
+local function read_number(ctx, t)
+	local value = read_value(ctx, t)
+	if type(value) ~= "number" then
+		value = value:tonumber()
+	end
+	return value
+end
+
+-- ASCII or UCS-2 to UTF-8, up to the null terminator
+local function decode(raw, wide)
+	local chars = {}
+	local step = wide and 2 or 1
+	for i = 1, #raw - step + 1, step do
+		local c = raw:byte(i)
+		if wide then
+			c = c + raw:byte(i + 1) * 256
+		end
+		if c == 0 then
+			break
+		elseif c < 0x80 then
+			chars[#chars + 1] = string.char(c)
+		elseif c < 0x800 then
+			chars[#chars + 1] = string.char(0xc0 + math.floor(c / 0x40), 0x80 + c % 0x40)
+		else
+			chars[#chars + 1] = string.char(0xe0 + math.floor(c / 0x1000), 0x80 + math.floor(c / 0x40) % 0x40, 0x80 + c % 0x40)
+		end
+	end
+	return table.concat(chars)
+end
+
+local function hex(raw)
+	return (raw:gsub(".", function(c) return string.format("%02x", c:byte()) end))
+end
+
+-- adds the value of `f` read from `start` up to the current position
+local function add(ctx, tree, f, start, value)
+	local first = math.floor(start / 8)
+	return tree:add(f, ctx.tvb(first, math.floor((ctx.pos + 7) / 8) - first), value)
+end
+
+-- calls `body` with a subtree labeled `label`, which spans the data read by `body`
+local function subtree(ctx, tree, label, body)
+	local first = math.floor(ctx.pos / 8)
+	local item = tree:add(ctx.tvb(first, ctx.tvb:len() - first), label)
+	body(item)
+	item:set_len(math.floor((ctx.pos + 7) / 8) - first)
+end
+
+local dissect
+
+local function dissect_elements(ctx, tree, element, len)
+	for i = 1, len do
+		dissect(ctx, tree, element, "[" .. (i - 1) .. "]")
+	end
+end
+
+function dissect(ctx, tree, layout, name)
+	local k = layout.k
+	local start = ctx.pos
+	if k == "type" then
+		local ty = T[layout.id]
+		if name == "" then
+			dissect(ctx, tree, ty, name)
+		else
+			subtree(ctx, tree, name .. ": " .. ty.name, function(item) dissect(ctx, item, ty, "") end)
+		end
+	elseif k == "struct" then
+		for _, field in ipairs(layout.fields) do
+			dissect(ctx, tree, field[2], field[1])
+		end
+	elseif k == "enum" then
+		local discriminant = read_number(ctx, layout.t)
+		add(ctx, tree, layout.f, start, discriminant)
+		local variant = layout.variants[discriminant]
+		if variant == nil then
+			error("unknown discriminant " .. discriminant, 0)
+		end
+		table.insert(ctx.info, variant.name)
+		dissect(ctx, tree, variant.layout, variant.name)
+	elseif k == "value" then
+		add(ctx, tree, layout.f, start, read_value(ctx, layout.t))
+	elseif k == "bit" then
+		add(ctx, tree, layout.f, start, read_bit(ctx))
+	elseif k == "fixedstr" then
+		local raw = read_bytes(ctx, layout.len * (layout.wide and 2 or 1))
+		add(ctx, tree, layout.f, start, decode(raw, layout.wide))
+	elseif k == "varstr" then
+		local len = read_number(ctx, layout.t)
+		add(ctx, tree, layout.lf, start, len)
+		local str_start = ctx.pos
+		local raw = read_bytes(ctx, len * (layout.wide and 2 or 1))
+		add(ctx, tree, layout.f, str_start, decode(raw, layout.wide))
+	elseif k == "list" then
+		subtree(ctx, tree, name, function(item)
+			local len = read_number(ctx, layout.t)
+			add(ctx, item, layout.lf, start, len)
+			dissect_elements(ctx, item, layout.element, len)
+		end)
+	elseif k == "array" then
+		subtree(ctx, tree, name, function(item) dissect_elements(ctx, item, layout.element, layout.len) end)
+	elseif k == "bytes" then
+		local raw = read_bytes(ctx, layout.len)
+		add(ctx, tree, layout.f, start, ByteArray.new(hex(raw)))
+	elseif k == "padding" then
+		check(ctx, layout.bits)
+		ctx.pos = start + layout.bits
+	elseif k == "flagged" then
+		local is_set = read_bit(ctx)
+		add(ctx, tree, layout.f, start, is_set)
+		if is_set then
+			dissect(ctx, tree, layout.value, name)
+		end
+	elseif k == "align" then
+		ctx.pos = math.floor((start + 7) / 8) * 8
+	elseif k == "opaque" then
+		local first = math.floor(start / 8)
+		tree:add(F[1], ctx.tvb(first, ctx.tvb:len() - first))
+		error(STOP)
+	end
+end
+
+function lu.dissector(tvb, pinfo, tree)
+	pinfo.cols.protocol = "LU"
+	local ctx = {tvb = tvb, pos = 0, info = {}}
+	local root = tree:add(lu, tvb())
+	local ok, err = pcall(dissect, ctx, root, ROOT, "")
+	pinfo.cols.info = table.concat(ctx.info, " / ")
+	if not ok and err ~= STOP then
+		root:add_expert_info(PI_MALFORMED, PI_ERROR, tostring(err))
+	elseif ok and math.floor((ctx.pos + 7) / 8) < tvb:len() then
+		root:add_expert_info(PI_MALFORMED, PI_WARN, (tvb:len() - math.floor((ctx.pos + 7) / 8)) .. " bytes left over")
+	end
+	return tvb:len()
+end
+
+local ok, raknet = pcall(DissectorTable.get, "raknet.packet_id")
+if ok and raknet ~= nil then
+	for _, id in ipairs(IDS) do
+		raknet:add(id, lu)
+	end
+end
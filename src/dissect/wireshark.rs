@@ -0,0 +1,343 @@
+/*!
+	Generation of a Wireshark dissector from the message types.
+
+	[`lua_dissector`] generates a Lua plugin for Wireshark from the [`Layout`] of [`Message`], so that the dissector follows the type definitions of this crate. It covers the hierarchy of RakNet message ID, [`ServiceId`](crate::common::ServiceId), message ID and game message ID, with padding, bit-packed `bool`s, the flag bits of optional values and the default flag bits of game message parameters. The variants along the way are shown in the info column.
+
+	The `wireshark_dissector` example writes the plugin to a file:
+
+	```text
+	cargo run --example wireshark_dissector -- lu.lua
+	```
+
+	Copy `lu.lua` to Wireshark's personal Lua plugins folder, listed under Help > About Wireshark > Folders.
+
+	### Notes
+	The dissector handles the message, starting with the RakNet message ID, but not the RakNet framing around it. It registers itself for the message IDs in Wireshark's `raknet.packet_id` dissector table if available, and can be called by name (`lu`) from a dissector for the framing.
+
+	Parts of messages that are described as [`Layout::Opaque`], such as replica components whose presence depends on the object, are shown as undecoded bytes, and dissection of the message stops there.
+*/
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::unified::Message;
+use super::{short_type_name, Dissect, Layout, Primitive, TypeRef, Variant};
+
+/// The part of the plugin that interprets the generated tables.
+const RUNTIME: &str = include_str!("wireshark.lua");
+
+/// Generates the Lua source of a Wireshark plugin dissecting [`Message`]s.
+pub fn lua_dissector() -> String {
+	let mut gen = Generator::default();
+	gen.field("lu.undecoded", "undecoded", "bytes", "");
+	let root = gen.layout(&Layout::of::<Message>(), "", "lu");
+	let mut ids = vec![];
+	if let Layout::Enum { variants, .. } = Message::layout() {
+		ids.extend(variants.iter().map(|x| x.discriminant.to_string()));
+	}
+
+	let mut lua = String::new();
+	lua.push_str("-- Dissector for LEGO Universe messages, generated by lu_packets from its type definitions.\n");
+	lua.push_str("-- Regenerate it instead of editing it, see the documentation of lu_packets::dissect::wireshark.\n\n");
+	lua.push_str("local lu = Proto(\"lu\", \"LEGO Universe\")\n\n");
+	lua.push_str("local F = {}\n");
+	for (i, field) in gen.fields.iter().enumerate() {
+		writeln!(lua, "F[{}] = {}", i + 1, field).unwrap();
+	}
+	lua.push_str("\nlocal T = {}\n");
+	for (i, ty) in gen.types.iter().enumerate() {
+		writeln!(lua, "T[{}] = {}", i + 1, ty).unwrap();
+	}
+	writeln!(lua, "\nlocal ROOT = {}", root).unwrap();
+	writeln!(lua, "local IDS = {{{}}}\n", ids.join(", ")).unwrap();
+	lua.push_str(RUNTIME);
+	lua
+}
+
+#[derive(Default)]
+struct Generator {
+	/// `ProtoField` constructors, referenced as `F[i]`.
+	fields: Vec<String>,
+	abbrevs: HashSet<String>,
+	/// Struct and enum tables, referenced by their index in `T`.
+	types: Vec<String>,
+	type_ids: HashMap<&'static str, usize>,
+}
+
+impl Generator {
+	/// Adds a `ProtoField`, and returns the Lua expression referencing it.
+	fn field(&mut self, abbrev: &str, name: &str, ty: &str, args: &str) -> String {
+		let mut abbrev = abbrev.to_string();
+		if self.abbrevs.contains(&abbrev) {
+			let mut i = 2;
+			while self.abbrevs.contains(&format!("{}_{}", abbrev, i)) {
+				i += 1;
+			}
+			abbrev = format!("{}_{}", abbrev, i);
+		}
+		self.fields.push(format!("ProtoField.{}({:?}, {:?}{})", ty, abbrev, name, args));
+		self.abbrevs.insert(abbrev);
+		format!("F[{}]", self.fields.len())
+	}
+
+	/// Generates the Lua table describing `layout`, for a field named `name` with the filter name `abbrev`.
+	fn layout(&mut self, layout: &Layout, name: &str, abbrev: &str) -> String {
+		match layout {
+			Layout::Value(x) => {
+				let (ty, args) = proto_field_type(*x);
+				let f = self.field(abbrev, name, ty, args);
+				format!("{{k = \"value\", t = \"{}\", f = {}}}", primitive(*x), f)
+			}
+			Layout::Bit => {
+				let f = self.field(abbrev, name, "bool", "");
+				format!("{{k = \"bit\", f = {}}}", f)
+			}
+			Layout::FixedStr { len, wide } => {
+				let f = self.field(abbrev, name, "string", "");
+				format!("{{k = \"fixedstr\", len = {}, wide = {}, f = {}}}", len, wide, f)
+			}
+			Layout::VarStr { length, wide } => {
+				let (ty, args) = proto_field_type(*length);
+				let lf = self.field(&format!("{}.length", abbrev), "length", ty, args);
+				let f = self.field(abbrev, name, "string", "");
+				format!("{{k = \"varstr\", t = \"{}\", wide = {}, lf = {}, f = {}}}", primitive(*length), wide, lf, f)
+			}
+			Layout::List { length, element } => {
+				let (ty, args) = proto_field_type(*length);
+				let lf = self.field(&format!("{}.length", abbrev), "length", ty, args);
+				let element = self.element(element, abbrev);
+				format!("{{k = \"list\", t = \"{}\", lf = {}, element = {}}}", primitive(*length), lf, element)
+			}
+			Layout::Array { len, element } => {
+				if let Layout::Value(Primitive::U8) = resolve(element) {
+					let f = self.field(abbrev, name, "bytes", "");
+					return format!("{{k = \"bytes\", len = {}, f = {}}}", len, f);
+				}
+				let element = self.element(element, abbrev);
+				format!("{{k = \"array\", len = {}, element = {}}}", len, element)
+			}
+			Layout::Struct(fields) => {
+				let fields: Vec<_> = fields.iter().map(|(name, layout)| self.struct_field(name, layout, &field_abbrev(abbrev, name))).collect();
+				format!("{{k = \"struct\", fields = {{{}}}}}", fields.join(", "))
+			}
+			Layout::Padding(bits) => format!("{{k = \"padding\", bits = {}}}", bits),
+			Layout::Flagged { flag, value } => {
+				let f = self.field(&format!("{}.{}", abbrev, flag), flag, "bool", "");
+				let value = self.layout(value, name, abbrev);
+				format!("{{k = \"flagged\", f = {}, value = {}}}", f, value)
+			}
+			Layout::Enum { discriminant, variants } => {
+				let names: Vec<_> = variants.iter().map(|x| format!("[{}] = {:?}", x.discriminant, x.name)).collect();
+				let (ty, args) = proto_field_type(*discriminant);
+				let f = self.field(&format!("{}.discriminant", abbrev), "discriminant", ty, &format!("{}, {{{}}}", args, names.join(", ")));
+				let arms: Vec<_> = variants.iter().map(|x| self.variant(x, abbrev)).collect();
+				format!("{{k = \"enum\", t = \"{}\", f = {}, variants = {{{}}}}}", primitive(*discriminant), f, arms.join(", "))
+			}
+			Layout::Align => "{k = \"align\"}".into(),
+			Layout::Type(x) => self.type_ref(x, name, abbrev),
+			Layout::Opaque => "{k = \"opaque\"}".into(),
+		}
+	}
+
+	fn struct_field(&mut self, name: &str, layout: &Layout, abbrev: &str) -> String {
+		format!("{{{:?}, {}}}", name, self.layout(layout, name, abbrev))
+	}
+
+	fn variant(&mut self, variant: &Variant, abbrev: &str) -> String {
+		let abbrev = format!("{}.{}", abbrev, snake_case(variant.name));
+		let layout = match &variant.layout {
+			Layout::Struct(fields) => {
+				// the content of a variant wrapping a single type is shown under the name of the variant
+				let wraps_one = fields.iter().filter(|(name, _)| !name.is_empty()).count() == 1;
+				let fields: Vec<_> = fields.iter().map(|(name, layout)| if wraps_one && *name == "0" { self.struct_field(variant.name, layout, &abbrev) } else { self.struct_field(name, layout, &field_abbrev(&abbrev, name)) }).collect();
+				format!("{{k = \"struct\", fields = {{{}}}}}", fields.join(", "))
+			}
+			layout => self.layout(layout, variant.name, &abbrev),
+		};
+		format!("[{}] = {{name = {:?}, layout = {}}}", variant.discriminant, variant.name, layout)
+	}
+
+	fn element(&mut self, element: &Layout, abbrev: &str) -> String {
+		self.layout(element, "element", &format!("{}.element", abbrev))
+	}
+
+	/// Structs and enums are defined once and referenced by ID, other types are inlined.
+	fn type_ref(&mut self, ty: &TypeRef, name: &str, abbrev: &str) -> String {
+		if let Some(id) = self.type_ids.get(ty.name) {
+			return format!("{{k = \"type\", id = {}}}", id);
+		}
+		let layout = (ty.layout)();
+		match layout {
+			Layout::Struct(_) | Layout::Enum { .. } => {}
+			_ => return self.layout(&layout, name, abbrev),
+		}
+		// reserve the id first, for types containing themselves
+		self.types.push(String::new());
+		let id = self.types.len();
+		self.type_ids.insert(ty.name, id);
+		let table = self.layout(&layout, "", &type_abbrev(ty.name));
+		// the table starts with `{`
+		self.types[id - 1] = format!("{{name = {:?}, {}", short_type_name(ty.name), &table[1..]);
+		format!("{{k = \"type\", id = {}}}", id)
+	}
+}
+
+/// The layout behind any type references.
+fn resolve(layout: &Layout) -> Layout {
+	match layout {
+		Layout::Type(x) => resolve(&(x.layout)()),
+		x => x.clone(),
+	}
+}
+
+fn field_abbrev(abbrev: &str, name: &str) -> String {
+	if name.is_empty() {
+		abbrev.into()
+	} else {
+		format!("{}.{}", abbrev, name)
+	}
+}
+
+/// The `ProtoField` constructor and its extra arguments for `primitive`.
+fn proto_field_type(primitive: Primitive) -> (&'static str, &'static str) {
+	match primitive {
+		Primitive::Bool => ("bool", ""),
+		Primitive::U8 => ("uint8", ", base.DEC"),
+		Primitive::U16 => ("uint16", ", base.DEC"),
+		Primitive::U32 => ("uint32", ", base.DEC"),
+		Primitive::U64 => ("uint64", ", base.DEC"),
+		Primitive::I8 => ("int8", ", base.DEC"),
+		Primitive::I16 => ("int16", ", base.DEC"),
+		Primitive::I32 => ("int32", ", base.DEC"),
+		Primitive::I64 => ("int64", ", base.DEC"),
+		Primitive::F32 => ("float", ""),
+		Primitive::F64 => ("double", ""),
+		Primitive::Ipv4 => ("ipv4", ""),
+	}
+}
+
+/// The name of `primitive` in the runtime.
+fn primitive(primitive: Primitive) -> &'static str {
+	match primitive {
+		Primitive::Bool => "bool",
+		Primitive::U8 => "u8",
+		Primitive::U16 => "u16",
+		Primitive::U32 => "u32",
+		Primitive::U64 => "u64",
+		Primitive::I8 => "i8",
+		Primitive::I16 => "i16",
+		Primitive::I32 => "i32",
+		Primitive::I64 => "i64",
+		Primitive::F32 => "f32",
+		Primitive::F64 => "f64",
+		Primitive::Ipv4 => "ipv4",
+	}
+}
+
+/// The filter name prefix for the fields of a type, from its path in this crate.
+fn type_abbrev(type_name: &str) -> String {
+	let path = type_name.split('<').next().unwrap();
+	let mut abbrev = String::from("lu");
+	for segment in path.split("::").skip(1) {
+		abbrev.push('.');
+		abbrev.push_str(&snake_case(segment));
+	}
+	abbrev
+}
+
+fn snake_case(name: &str) -> String {
+	let chars: Vec<_> = name.chars().collect();
+	let mut snake = String::new();
+	for (i, c) in chars.iter().enumerate() {
+		if c.is_uppercase() && i > 0 {
+			let prev = chars[i - 1];
+			let next_is_lower = chars.get(i + 1).map_or(false, |x| x.is_lowercase());
+			if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+				snake.push('_');
+			}
+		}
+		snake.extend(c.to_lowercase());
+	}
+	snake
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::{lua_dissector, snake_case, type_abbrev};
+
+	/// `lua` with the contents of strings and comments removed, checking that the brackets in the rest are balanced.
+	fn code(lua: &str) -> String {
+		let mut code = String::new();
+		let mut open = vec![];
+		let mut chars = lua.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'"' => {
+					while let Some(c) = chars.next() {
+						match c {
+							'\\' => {
+								chars.next();
+							}
+							'"' => break,
+							_ => {}
+						}
+					}
+					code.push_str("\"\"");
+				}
+				'-' if chars.peek() == Some(&'-') => {
+					chars.find(|&c| c == '\n');
+					code.push('\n');
+				}
+				'{' | '(' | '[' => {
+					open.push(c);
+					code.push(c);
+				}
+				'}' | ')' | ']' => {
+					let expected = match c {
+						'}' => '{',
+						')' => '(',
+						_ => '[',
+					};
+					assert_eq!(open.pop(), Some(expected), "unbalanced {:?} after {:?}", c, &code[code.len().saturating_sub(100)..]);
+					code.push(c);
+				}
+				_ => code.push(c),
+			}
+		}
+		assert!(open.is_empty(), "unclosed {:?}", open);
+		code
+	}
+
+	/// The numbers following `prefix`, where it isn't part of a longer name.
+	fn numbers_after(code: &str, prefix: &str) -> HashSet<u64> {
+		code.match_indices(prefix).filter(|(i, _)| !code[..*i].ends_with(|c: char| c.is_alphanumeric() || c == '_')).filter_map(|(i, _)| code[i + prefix.len()..].split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()).collect()
+	}
+
+	#[test]
+	fn test_names() {
+		assert_eq!(snake_case("UserMessage"), "user_message");
+		assert_eq!(snake_case("LuWString33"), "lu_w_string33");
+		assert_eq!(snake_case("MftfEcho"), "mftf_echo");
+		assert_eq!(type_abbrev("lu_packets::world::server::ClientValidation"), "lu.world.server.client_validation");
+	}
+
+	#[test]
+	fn test_lua_dissector() {
+		let lua = lua_dissector();
+		assert!(lua.contains("ProtoField.uint8(\"lu.unified.message.discriminant\", \"discriminant\", base.DEC, {[0] = \"InternalPing\""));
+		assert!(lua.contains("ProtoField.string(\"lu.world.server.client_validation.username\", \"username\")"));
+		assert!(lua.contains("ProtoField.bool(\"lu.raknet.client.replica.replica_scope_change.in_scope\", \"in_scope\")"));
+		assert!(lua.contains("local IDS = {0, 3, 4, 14, 17, 19, 36, 37, 38, 39, 41, 83}"));
+
+		let code = code(&lua);
+		let line_starts = |prefix: &str| -> HashSet<u64> { code.lines().filter(|x| x.starts_with(prefix)).flat_map(|x| numbers_after(x, prefix)).collect() };
+		let fields = line_starts("F[");
+		let types = line_starts("T[");
+		assert!(fields.contains(&1) && types.contains(&1));
+		for (prefix, defined) in &[("F[", &fields), ("T[", &types), ("id = ", &types)] {
+			let undefined: Vec<_> = numbers_after(&code, prefix).difference(defined).copied().collect();
+			assert!(undefined.is_empty(), "{}...] referenced but not defined: {:?}", prefix, undefined);
+		}
+	}
+}
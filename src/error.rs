@@ -0,0 +1,260 @@
+//! Errors produced while decoding and encoding messages.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+
+/**
+	An error produced while decoding or encoding a message.
+
+	Since (de-)serialization goes through [`endio`], whose traits return [`std::io::Result`], this error travels wrapped in an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData). Convert the `io::Error` back with [`From`] to inspect it:
+
+	```
+	# use lu_packets::Error;
+	# use lu_packets::world::amf3::Amf3;
+	use endio::LERead;
+
+	let err = LERead::read::<Amf3>(&mut &b"\x11"[..]).unwrap_err();
+	assert!(matches!(Error::from(err), Error::UnknownDiscriminant { type_name: "Amf3", value: 17 }));
+	```
+
+	Errors produced by this crate's own decoding code are typed, while errors from the underlying reader are passed through as [`Error::Io`].
+
+	### Context
+
+	While an error propagates out of nested types, enum variants, fields of types deserialized by this crate's derive macros and bit streams add frames to its path, resulting in an [`Error::Context`] with a path like `Message::UserMessage > UserMessage::World > WorldMessage::SubjectGameMessage > GameMessage::StartSkill > bitstream`. Bit streams additionally record the bit position at which decoding stopped, and decoding with [`from_slice`](crate::from_slice) records the byte offset. Use [`path`](Self::path), [`byte_offset`](Self::byte_offset), [`bit_offset`](Self::bit_offset) and [`root`](Self::root) to inspect errors regardless of whether they carry context.
+*/
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+	/// An enum discriminant that doesn't correspond to any variant.
+	UnknownDiscriminant { type_name: &'static str, value: u64 },
+	/// A string that isn't valid in its encoding, or is missing its null terminator.
+	InvalidString { type_name: &'static str, reason: &'static str },
+	/// A length or length prefix that is out of range, either for its type or for the data it describes.
+	LengthOverflow { type_name: &'static str, length: u64 },
+	/// A length, size or nesting depth exceeding the [`DecodeLimits`](crate::DecodeLimits) in effect, with `limit` naming the field of the limit.
+	LimitExceeded { type_name: &'static str, limit: &'static str, value: u64 },
+	/// Data left over after a message was fully decoded.
+	TrailingData { remaining: usize },
+	/// An AMF3 reference from an object to itself or to an object containing it, which can't be represented.
+	UnsupportedAmf3Reference { index: u32 },
+	/// An AMF3 reference to an index not present in the reference table.
+	InvalidAmf3Reference { index: u32 },
+	/// A line of a [`LuNameValue`](crate::world::LuNameValue) in text form that couldn't be parsed, with its line number starting at 1.
+	InvalidLnv { line: usize, text: String, reason: &'static str },
+	/// A value of a [`LuNameValue`](crate::world::LuNameValue) that is missing or of the wrong type for the field it is converted to, see [`FromLnv`](crate::world::FromLnv).
+	LnvField { name: &'static str, reason: &'static str },
+	/// Data that violates the structure of a message in some other way.
+	Malformed { type_name: &'static str, reason: &'static str },
+	/// An error of the underlying reader or writer.
+	Io(io::Error),
+	/// Another error, annotated with where in the message it occurred.
+	Context {
+		/// Names of the types, variants and fields being decoded, outermost first.
+		path: Vec<&'static str>,
+		/// Number of bytes consumed from the input when the error occurred, if known.
+		byte_offset: Option<u64>,
+		/// Number of bits consumed from the innermost bit stream when the error occurred, if it occurred in one. Replica bit streams are read through a byte buffer, so for them this is rounded up to whole bytes.
+		bit_offset: Option<u64>,
+		source: Box<Error>,
+	},
+}
+
+impl Error {
+	/// The error without any context.
+	pub fn root(&self) -> &Self {
+		match self {
+			Self::Context { source, .. } => source,
+			x => x,
+		}
+	}
+
+	/// The path to where the error occurred, outermost first. Empty if unknown.
+	pub fn path(&self) -> &[&'static str] {
+		match self {
+			Self::Context { path, .. } => path,
+			_ => &[],
+		}
+	}
+
+	/// Number of bytes consumed from the input when the error occurred, if known.
+	pub fn byte_offset(&self) -> Option<u64> {
+		match self {
+			Self::Context { byte_offset, .. } => *byte_offset,
+			_ => None,
+		}
+	}
+
+	/// Number of bits consumed from the innermost bit stream when the error occurred, if it occurred in one.
+	pub fn bit_offset(&self) -> Option<u64> {
+		match self {
+			Self::Context { bit_offset, .. } => *bit_offset,
+			_ => None,
+		}
+	}
+
+	fn kind(&self) -> io::ErrorKind {
+		match self.root() {
+			Self::Io(err) => err.kind(),
+			_ => io::ErrorKind::InvalidData,
+		}
+	}
+
+	/// Adds a frame to the front of the path, wrapping the error in [`Error::Context`] if necessary.
+	fn push_frame(self, frame: &'static str) -> Self {
+		match self {
+			Self::Context { mut path, byte_offset, bit_offset, source } => {
+				path.insert(0, frame);
+				Self::Context { path, byte_offset, bit_offset, source }
+			}
+			x => Self::Context { path: vec![frame], byte_offset: None, bit_offset: None, source: Box::new(x) },
+		}
+	}
+
+	pub(crate) fn with_byte_offset(self, offset: u64) -> Self {
+		match self {
+			Self::Context { path, bit_offset, source, .. } => Self::Context { path, byte_offset: Some(offset), bit_offset, source },
+			x => Self::Context { path: vec![], byte_offset: Some(offset), bit_offset: None, source: Box::new(x) },
+		}
+	}
+
+	/// Records the position in a bit stream, unless the error already has one from a bit stream nested in it.
+	fn with_bit_offset(self, offset: u64) -> Self {
+		match self {
+			Self::Context { path, byte_offset, bit_offset, source } => Self::Context { path, byte_offset, bit_offset: bit_offset.or(Some(offset)), source },
+			x => Self::Context { path: vec![], byte_offset: None, bit_offset: Some(offset), source: Box::new(x) },
+		}
+	}
+}
+
+/// Adds `frame` to the path of an error propagating out of a nested type.
+pub(crate) fn push_frame(err: io::Error, frame: &'static str) -> io::Error {
+	Error::from(err).push_frame(frame).into()
+}
+
+/// Records `offset` as the position in the bit stream an error occurred in.
+pub(crate) fn with_bit_offset(err: io::Error, offset: u64) -> io::Error {
+	Error::from(err).with_bit_offset(offset).into()
+}
+
+/// Runs `f`, adding `frame` to the path of any error it returns.
+pub(crate) fn frame<T>(frame: &'static str, f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+	f().map_err(|err| push_frame(err, frame))
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Self::UnknownDiscriminant { type_name, value } => write!(f, "invalid discriminant value for {}: {}", type_name, value),
+			Self::InvalidString { type_name, reason } => write!(f, "invalid {}: {}", type_name, reason),
+			Self::LengthOverflow { type_name, length } => write!(f, "length out of range for {}: {}", type_name, length),
+			Self::LimitExceeded { type_name, limit, value } => write!(f, "{} of {} exceeded: {}", limit, type_name, value),
+			Self::TrailingData { remaining } => write!(f, "{} bytes of trailing data", remaining),
+			Self::UnsupportedAmf3Reference { index } => write!(f, "unsupported AMF3 reference: {}", index),
+			Self::InvalidAmf3Reference { index } => write!(f, "invalid AMF3 reference index: {}", index),
+			Self::InvalidLnv { line, text, reason } => write!(f, "invalid LNV on line {} ({:?}): {}", line, text, reason),
+			Self::LnvField { name, reason } => write!(f, "LNV value {:?} {}", name, reason),
+			Self::Malformed { type_name, reason } => write!(f, "malformed {}: {}", type_name, reason),
+			Self::Io(err) => err.fmt(f),
+			Self::Context { path, byte_offset, bit_offset, source } => {
+				write!(f, "{}", path.join(" > "))?;
+				let mut separator = if path.is_empty() { "" } else { " " };
+				if let Some(offset) = byte_offset {
+					write!(f, "{}at byte {}", separator, offset)?;
+					separator = ", ";
+				}
+				if let Some(offset) = bit_offset {
+					write!(f, "{}at bit {} of the bit stream", separator, offset)?;
+				}
+				write!(f, ": {}", source)
+			}
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Io(err) => Some(err),
+			Self::Context { source, .. } => Some(source),
+			_ => None,
+		}
+	}
+}
+
+/// Wraps the error in an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData), or unwraps [`Error::Io`]. Errors with context keep the kind of their root.
+impl From<Error> for io::Error {
+	fn from(err: Error) -> Self {
+		match err {
+			Error::Io(err) => err,
+			err => io::Error::new(err.kind(), err),
+		}
+	}
+}
+
+/// Extracts an [`Error`] wrapped by [`From<Error> for io::Error`](#impl-From<Error>-for-Error), or wraps other errors in [`Error::Io`].
+impl From<io::Error> for Error {
+	fn from(err: io::Error) -> Self {
+		if err.get_ref().map_or(false, |inner| inner.is::<Self>()) {
+			if let Some(Ok(inner)) = err.into_inner().map(|inner| inner.downcast::<Self>()) {
+				return *inner;
+			}
+			unreachable!();
+		}
+		Self::Io(err)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+
+	use super::{push_frame, with_bit_offset, Error};
+
+	#[test]
+	fn test_context() {
+		let err = push_frame(Error::UnknownDiscriminant { type_name: "Foo", value: 3 }.into(), "field");
+		let err = Error::from(push_frame(err, "GameMessage::Foo")).with_byte_offset(5);
+		assert_eq!(err.path(), ["GameMessage::Foo", "field"]);
+		assert_eq!(err.byte_offset(), Some(5));
+		assert!(matches!(err.root(), Error::UnknownDiscriminant { type_name: "Foo", value: 3 }));
+		assert_eq!(err.to_string(), "GameMessage::Foo > field at byte 5: invalid discriminant value for Foo: 3");
+	}
+
+	#[test]
+	fn test_bit_offset() {
+		let inner = with_bit_offset(push_frame(Error::UnknownDiscriminant { type_name: "Foo", value: 3 }.into(), "inner"), 3);
+		let err = Error::from(with_bit_offset(push_frame(inner, "outer"), 20)).with_byte_offset(5);
+		assert_eq!(err.path(), ["outer", "inner"]);
+		assert_eq!(err.bit_offset(), Some(3));
+		assert_eq!(err.to_string(), "outer > inner at byte 5, at bit 3 of the bit stream: invalid discriminant value for Foo: 3");
+	}
+
+	#[test]
+	fn test_context_keeps_kind() {
+		let err = push_frame(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"), "field");
+		assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+		assert!(matches!(Error::from(err).root(), Error::Io(_)));
+	}
+
+	#[test]
+	fn test_derived_enum() {
+		let err = crate::from_slice::<crate::common::ServiceId>(b"\x09\x00").unwrap_err();
+		assert!(matches!(err.root(), Error::UnknownDiscriminant { type_name: "ServiceId", value: 9 }));
+	}
+
+	#[test]
+	fn test_message_path() {
+		let data = b"\x53\x04\x00\x05\x00\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\xff\xff";
+		let err = crate::from_slice::<crate::world::server::Message>(data).unwrap_err();
+		assert_eq!(err.path(), ["Message::UserMessage", "LuMessage::World", "WorldMessage::SubjectGameMessage"]);
+		assert_eq!(err.byte_offset(), Some(18));
+		assert!(matches!(err.root(), Error::UnknownDiscriminant { type_name: "GameMessage", value: 0xffff }));
+	}
+
+	#[test]
+	fn test_from_slice_offset() {
+		let err = crate::from_slice::<crate::world::amf3::Amf3>(b"\x09\x03\x01\x11").unwrap_err();
+		assert_eq!(err.byte_offset(), Some(4));
+		assert!(matches!(err.root(), Error::UnknownDiscriminant { type_name: "Amf3", value: 17 }));
+	}
+}
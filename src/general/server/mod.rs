@@ -1,10 +1,11 @@
 //! Server-received general messages.
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::VariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, VariantTests};
 
 use crate::common::ServiceId;
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[post_disc_padding = 1]
 #[repr(u32)]
 pub enum GeneralMessage {
@@ -28,7 +29,8 @@ pub enum GeneralMessage {
 	### Notes
 	This packet should not be seen as proof that the client's network version is actually what they report it to be. The client can provide any value, and malicious clients can deviate from the protocol in any way they like. Therefore, proper length and value checking is still required for packet parsing, and care should be taken that your server does not crash on invalid input. If you're using the parsing functionality of this library, this will be taken care of for you.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 33]
 pub struct Handshake {
 	/// The network protocol version of the client. For unmodified live clients, this is `171022`. This was relevant mainly back when LU was actively updated. If you intend to make modifications to the protocol for your server project, you should change this to a different value.
@@ -1,8 +1,8 @@
 /*!
 	Documentation and (de-)serialization support for LU's network protocol.
+
+	With the `serde` feature, all messages implement `serde`'s traits as well, for a textual representation such as JSON that can be converted back into messages. Strings are represented as plain strings, [`Amf3`](crate::world::amf3::Amf3) as described in `world::amf3::json`, and replica components as a map with the name of their kind as the only key.
 */
-#![feature(specialization)]
-#![allow(incomplete_features)]
 
 /**
 	Creates an [`Amf3::Array`](crate::world::amf3::Amf3::Array) containing the arguments.
@@ -49,29 +49,29 @@
 */
 #[macro_export]
 macro_rules! amf3 {
-	{} => { $crate::world::amf3::Amf3::Array($crate::world::amf3::Amf3Array::new()) };
+	{} => { $crate::world::amf3::Amf3::from($crate::world::amf3::Amf3Array::new()) };
 	($($name:literal:$value:expr),+ $(,)?) => {
 		{
 			let mut array = $crate::world::amf3::Amf3Array::new();
-			$(array.map.insert(::std::convert::TryInto::try_into($name).unwrap(), ::std::convert::TryInto::try_into($value).unwrap());)*
-			$crate::world::amf3::Amf3::Array(array)
+			$(array.insert(::std::convert::TryInto::try_into($name).unwrap(), ::std::convert::TryInto::try_into($value).unwrap());)*
+			$crate::world::amf3::Amf3::from(array)
 		}
 	};
 	($value:expr; $n:expr) => {
 		{
 			let converted = ::std::convert::TryInto::try_into($value).unwrap();
-			let mut array = $crate::world::amf3::Amf3Array {
-				map: ::std::collections::HashMap::new(),
+			let array = $crate::world::amf3::Amf3Array {
+				map: vec![],
 				vec: vec![converted; $n],
 			};
-			$crate::world::amf3::Amf3::Array(array)
+			$crate::world::amf3::Amf3::from(array)
 		}
 	};
 	($($value:expr),+ $(,)?) => {
 		{
 			let mut array = $crate::world::amf3::Amf3Array::new();
 			$(array.vec.push(::std::convert::TryInto::try_into($value).unwrap());)*
-			$crate::world::amf3::Amf3::Array(array)
+			$crate::world::amf3::Amf3::from(array)
 		}
 	};
 }
@@ -133,6 +133,9 @@ macro_rules! lu {
 	};
 }
 
+// lets derives for consumers of this crate, such as `FromLnv`, be used inside it as well
+extern crate self as lu_packets;
+
 pub mod raknet;
 pub mod auth;
 pub mod chat;
@@ -140,3 +143,50 @@ pub mod common;
 pub mod general;
 pub mod world;
 pub mod unified;
+pub mod dissect;
+#[cfg(feature = "capture")]
+pub mod capture;
+
+mod error;
+mod limits;
+
+use std::io::Result as Res;
+
+use endio::{Deserialize, LE, LERead};
+
+pub use crate::error::Error;
+pub use crate::limits::DecodeLimits;
+
+/**
+	Decodes a value from a byte slice, requiring the whole slice to be consumed.
+
+	Leftover bytes result in [`Error::TrailingData`]. Errors record the byte offset at which decoding stopped, see [`Error::byte_offset`].
+*/
+pub fn from_slice<'a, T: Deserialize<LE, &'a [u8]>>(mut data: &'a [u8]) -> Result<T, Error> {
+	let len = data.len();
+	let val = LERead::read(&mut data).map_err(|err| Error::from(err).with_byte_offset((len - data.len()) as u64))?;
+	if !data.is_empty() {
+		return Err(Error::TrailingData { remaining: data.len() });
+	}
+	Ok(val)
+}
+
+/**
+	Decoding that borrows from the input instead of allocating, for messages received often enough that allocations matter.
+
+	Implemented by borrowed counterparts of some messages, such as [`WorldMessageRef`](crate::world::server::WorldMessageRef), and by messages that don't allocate to begin with.
+*/
+pub trait DecodeBorrowed<'a>: Sized {
+	/// Decodes a value from the front of `data`, advancing it past the value.
+	fn decode_borrowed(data: &mut &'a [u8]) -> Res<Self>;
+}
+
+/// Like [`from_slice`], but borrows from `data` instead of allocating, see [`DecodeBorrowed`].
+pub fn decode_borrowed<'a, T: DecodeBorrowed<'a>>(mut data: &'a [u8]) -> Result<T, Error> {
+	let len = data.len();
+	let val = T::decode_borrowed(&mut data).map_err(|err| Error::from(err).with_byte_offset((len - data.len()) as u64))?;
+	if !data.is_empty() {
+		return Err(Error::TrailingData { remaining: data.len() });
+	}
+	Ok(val)
+}
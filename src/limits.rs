@@ -0,0 +1,131 @@
+//! Limits on the resources used for decoding untrusted data.
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::io::{Read, Result as Res};
+
+use crate::Error;
+
+/// Capacity reserved up front at most, larger values grow as data actually arrives.
+const MAX_PREALLOC: usize = 4096;
+
+thread_local! {
+	static LIMITS: Cell<DecodeLimits> = Cell::new(DecodeLimits::DEFAULT);
+}
+
+/**
+	Limits on lengths, sizes and nesting read from the wire.
+
+	Since decoding goes through [`endio`]'s traits, the limits can't be passed as an argument. Instead, they apply to all decoding on the current thread, see [`scope`](Self::scope). Without a scope, [`DecodeLimits::DEFAULT`] applies.
+
+	Independently of the limits, lengths from the wire are never used to reserve more than a few kilobytes up front, so memory use grows with the amount of data actually received.
+
+	```
+	use lu_packets::{DecodeLimits, Error};
+	use lu_packets::world::amf3::Amf3;
+
+	let limits = DecodeLimits { max_string_len: 2, ..DecodeLimits::DEFAULT };
+	let err = limits.scope(|| lu_packets::from_slice::<Amf3>(b"\x06\x07abc")).unwrap_err();
+	assert!(matches!(err.root(), Error::LimitExceeded { limit: "max_string_len", value: 3, .. }));
+	```
+*/
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecodeLimits {
+	/// Maximum number of characters of a string, or bytes of a byte array.
+	pub max_string_len: usize,
+	/// Maximum number of elements of a length-prefixed list.
+	pub max_vec_len: usize,
+	/// Maximum size in bytes of a [`LuNameValue`](crate::world::LuNameValue) after decompression.
+	pub max_lnv_inflated_len: usize,
+	/// Maximum nesting of arrays and objects in [`Amf3`](crate::world::amf3::Amf3).
+	pub max_amf3_depth: usize,
+	/// Maximum nesting of behaviors in the data of [skills](crate::world::skill), which also guards against cycles in the behavior data.
+	pub max_skill_depth: usize,
+}
+
+impl DecodeLimits {
+	/// Limits generous enough for all legitimate traffic.
+	pub const DEFAULT: Self = Self { max_string_len: 1 << 20, max_vec_len: 1 << 20, max_lnv_inflated_len: 1 << 24, max_amf3_depth: 64, max_skill_depth: 64 };
+
+	/// No limits, for trusted data such as captures. Cyclic behavior data then overflows the stack when decoding skills.
+	pub const UNLIMITED: Self = Self { max_string_len: usize::MAX, max_vec_len: usize::MAX, max_lnv_inflated_len: usize::MAX, max_amf3_depth: usize::MAX, max_skill_depth: usize::MAX };
+
+	/// The limits currently in effect on this thread.
+	pub fn current() -> Self {
+		LIMITS.with(Cell::get)
+	}
+
+	/// Runs `f` with these limits in effect on this thread, restoring the previous limits afterwards, even if `f` panics.
+	pub fn scope<T>(self, f: impl FnOnce() -> T) -> T {
+		struct Restore(DecodeLimits);
+
+		impl Drop for Restore {
+			fn drop(&mut self) {
+				LIMITS.with(|x| x.set(self.0));
+			}
+		}
+
+		let _restore = Restore(LIMITS.with(|x| x.replace(self)));
+		f()
+	}
+}
+
+impl Default for DecodeLimits {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+/// Checks `len` against the limit selected by `limit`, named `name` in errors.
+pub(crate) fn check(type_name: &'static str, name: &'static str, limit: fn(&DecodeLimits) -> usize, len: u64) -> Res<usize> {
+	match usize::try_from(len) {
+		Ok(x) if x <= limit(&DecodeLimits::current()) => Ok(x),
+		_ => Err(Error::LimitExceeded { type_name, limit: name, value: len }.into()),
+	}
+}
+
+pub(crate) fn check_string(type_name: &'static str, len: u64) -> Res<usize> {
+	check(type_name, "max_string_len", |x| x.max_string_len, len)
+}
+
+pub(crate) fn check_vec(type_name: &'static str, len: u64) -> Res<usize> {
+	check(type_name, "max_vec_len", |x| x.max_vec_len, len)
+}
+
+/// A `Vec` with capacity for `len` elements, or less if `len` is large.
+pub(crate) fn vec_with_capacity<T>(len: usize) -> Vec<T> {
+	Vec::with_capacity(len.min(MAX_PREALLOC))
+}
+
+/// Reads exactly `len` bytes, allocating only as much as is actually read.
+pub(crate) fn read_bytes<R: Read>(reader: &mut R, len: usize) -> Res<Vec<u8>> {
+	let mut vec = vec_with_capacity(len);
+	reader.take(len as u64).read_to_end(&mut vec)?;
+	if vec.len() != len {
+		return Err(std::io::ErrorKind::UnexpectedEof.into());
+	}
+	Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{check_string, read_bytes, DecodeLimits};
+
+	#[test]
+	fn test_scope() {
+		let limits = DecodeLimits { max_string_len: 3, ..DecodeLimits::DEFAULT };
+		limits.scope(|| {
+			assert_eq!(DecodeLimits::current(), limits);
+			assert!(check_string("Foo", 3).is_ok());
+			assert!(check_string("Foo", 4).is_err());
+			DecodeLimits::UNLIMITED.scope(|| assert!(check_string("Foo", 4).is_ok()));
+			assert_eq!(DecodeLimits::current(), limits);
+		});
+		assert_eq!(DecodeLimits::current(), DecodeLimits::DEFAULT);
+	}
+
+	#[test]
+	fn test_read_bytes() {
+		assert_eq!(read_bytes(&mut &b"abc"[..], 2).unwrap(), b"ab");
+		assert_eq!(read_bytes(&mut &b"abc"[..], usize::MAX).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+	}
+}
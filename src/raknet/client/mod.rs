@@ -2,12 +2,13 @@
 pub mod replica;
 
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::ReplicaVariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, ReplicaVariantTests};
 
 use super::SystemAddress;
-use replica::{ReplicaConstruction, ReplicaSerialization};
+use replica::{ReplicaConstruction, ReplicaDestruction, ReplicaScopeChange, ReplicaSerialization};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, ReplicaVariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, ReplicaVariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[test_params(crate::world::client::LuMessage)]
 #[non_exhaustive]
 #[repr(u8)]
@@ -16,7 +17,10 @@ pub enum Message<U> {
 	ConnectionRequestAccepted(ConnectionRequestAccepted) = 14,
 	DisconnectionNotification = 19,
 	ReplicaConstruction(ReplicaConstruction) = 36,
+	ReplicaDestruction(ReplicaDestruction) = 37,
+	ReplicaScopeChange(ReplicaScopeChange) = 38,
 	ReplicaSerialization(ReplicaSerialization) = 39,
+	ReplicaDownloadComplete = 41,
 	UserMessage(U) = 83,
 }
 
@@ -38,18 +42,32 @@ impl<U> From<ReplicaConstruction> for Message<U> {
 	}
 }
 
+impl<U> From<ReplicaDestruction> for Message<U> {
+	fn from(msg: ReplicaDestruction) -> Self {
+		Message::ReplicaDestruction(msg)
+	}
+}
+
+impl<U> From<ReplicaScopeChange> for Message<U> {
+	fn from(msg: ReplicaScopeChange) -> Self {
+		Message::ReplicaScopeChange(msg)
+	}
+}
+
 impl<U> From<ReplicaSerialization> for Message<U> {
 	fn from(msg: ReplicaSerialization) -> Self {
 		Message::ReplicaSerialization(msg)
 	}
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ConnectedPong {
 	pub ping_send_time: u32,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ConnectionRequestAccepted {
 	pub peer_addr: SystemAddress,
 	#[padding = 2]
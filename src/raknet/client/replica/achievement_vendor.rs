@@ -4,10 +4,11 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::vendor::VendorInfo;
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AchievementVendorConstruction {
 	pub vendor_info: Option<VendorInfo>,
 }
@@ -26,6 +27,20 @@ impl ComponentSerialization for AchievementVendorSerialization {
 	}
 }
 
+impl ApplySerialization<AchievementVendorSerialization> for AchievementVendorConstruction {
+	fn apply_serialization(&mut self, serialization: AchievementVendorSerialization) {
+		update_flagged(&mut self.vendor_info, serialization.vendor_info);
+	}
+}
+
+impl ComponentDiff for AchievementVendorConstruction {
+	type Serialization = AchievementVendorSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		AchievementVendorSerialization { vendor_info: flag_changed(&previous.vendor_info, &current.vendor_info) }
+	}
+}
+
 pub struct AchievementVendorProtocol;
 
 impl ComponentProtocol for AchievementVendorProtocol {
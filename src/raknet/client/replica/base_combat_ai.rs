@@ -1,13 +1,14 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::common::ObjId;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum AiCombatState {
 	Idle,
@@ -17,13 +18,15 @@ pub enum AiCombatState {
 	Dead,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CombatAiInfo {
 	pub current_combat_state: AiCombatState,
 	pub current_target: ObjId,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BaseCombatAiConstruction {
 	pub combat_ai_info: Option<CombatAiInfo>,
 }
@@ -42,6 +45,20 @@ impl ComponentSerialization for BaseCombatAiSerialization {
 	}
 }
 
+impl ApplySerialization<BaseCombatAiSerialization> for BaseCombatAiConstruction {
+	fn apply_serialization(&mut self, serialization: BaseCombatAiSerialization) {
+		update_flagged(&mut self.combat_ai_info, serialization.combat_ai_info);
+	}
+}
+
+impl ComponentDiff for BaseCombatAiConstruction {
+	type Serialization = BaseCombatAiSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		BaseCombatAiSerialization { combat_ai_info: flag_changed(&previous.combat_ai_info, &current.combat_ai_info) }
+	}
+}
+
 pub struct BaseCombatAiProtocol;
 
 impl ComponentProtocol for BaseCombatAiProtocol {
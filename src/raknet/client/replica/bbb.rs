@@ -5,9 +5,10 @@ use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::ObjId;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BbbConstruction {
 	pub metadata_source_item: Option<ObjId>,
 }
@@ -26,6 +27,20 @@ impl ComponentSerialization for BbbSerialization {
 	}
 }
 
+impl ApplySerialization<BbbSerialization> for BbbConstruction {
+	fn apply_serialization(&mut self, serialization: BbbSerialization) {
+		update_flagged(&mut self.metadata_source_item, serialization.metadata_source_item);
+	}
+}
+
+impl ComponentDiff for BbbConstruction {
+	type Serialization = BbbSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		BbbSerialization { metadata_source_item: flag_changed(&previous.metadata_source_item, &current.metadata_source_item) }
+	}
+}
+
 pub struct BbbProtocol;
 
 impl ComponentProtocol for BbbProtocol {
@@ -4,9 +4,10 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BouncerConstruction {
 	pub bounce_on_collision: Option<bool>,
 }
@@ -25,6 +26,20 @@ impl ComponentSerialization for BouncerSerialization {
 	}
 }
 
+impl ApplySerialization<BouncerSerialization> for BouncerConstruction {
+	fn apply_serialization(&mut self, serialization: BouncerSerialization) {
+		update_flagged(&mut self.bounce_on_collision, serialization.bounce_on_collision);
+	}
+}
+
+impl ComponentDiff for BouncerConstruction {
+	type Serialization = BouncerSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		BouncerSerialization { bounce_on_collision: flag_changed(&previous.bounce_on_collision, &current.bounce_on_collision) }
+	}
+}
+
 pub struct BouncerProtocol;
 
 impl ComponentProtocol for BouncerProtocol {
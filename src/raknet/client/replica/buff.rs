@@ -4,11 +4,13 @@ use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use crate::common::{LVec, ObjId};
-use super::{ReplicaD, ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::common::{LVec, LVecElement, ObjId};
+use crate::dissect::Dissector;
+use super::{ReplicaD, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
 // so close to being able to do serialization automatically...if not for the irregularity with `added_by_teammate`...
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuffInfo {
 	pub buff_id: u32,
 	pub time_left: Option<u32>,
@@ -25,6 +27,8 @@ pub struct BuffInfo {
 	pub ref_count: i32,
 }
 
+impl LVecElement for BuffInfo {}
+
 impl<R: Read> Deserialize<LE, BEBitReader<R>> for BuffInfo {
 	fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self> {
 		let buff_id = LERead::read(reader)?;
@@ -66,13 +70,36 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a BuffInfo {
 	}
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl crate::dissect::Dissect for BuffInfo {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("buff_id", &self.buff_id)?;
+		dissector.flagged("time_left", &self.time_left)?;
+		dissector.bit("cancel_on_death", self.cancel_on_death)?;
+		dissector.bit("cancel_on_zone", self.cancel_on_zone)?;
+		dissector.bit("cancel_on_damaged", self.cancel_on_damaged)?;
+		dissector.bit("cancel_on_remove_buff", self.cancel_on_remove_buff)?;
+		dissector.bit("cancel_on_ui", self.cancel_on_ui)?;
+		dissector.bit("cancel_on_logout", self.cancel_on_logout)?;
+		dissector.bit("cancel_on_unequip", self.cancel_on_unequip)?;
+		dissector.bit("cancel_on_damage_absorb_ran_out", self.cancel_on_damage_absorb_ran_out)?;
+		dissector.bit("has_added_by_teammate", self.added_by_teammate.is_some())?;
+		dissector.bit("apply_on_teammates", self.apply_on_teammates)?;
+		if let Some(x) = &self.added_by_teammate {
+			dissector.field("added_by_teammate", x)?;
+		}
+		dissector.field("ref_count", &self.ref_count)
+	}
+}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuffConstruction {
 	pub buffs: Option<LVec<u32, BuffInfo>>,
 	pub immunities: Option<LVec<u32, BuffInfo>>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuffSerialization {}
 
 impl ComponentConstruction for BuffConstruction {
@@ -87,6 +114,14 @@ impl ComponentSerialization for BuffSerialization {
 	}
 }
 
+impl ComponentDiff for BuffConstruction {
+	type Serialization = BuffSerialization;
+
+	fn diff(_previous: &Self, _current: &Self) -> Self::Serialization {
+		BuffSerialization {}
+	}
+}
+
 pub struct BuffProtocol;
 
 impl ComponentProtocol for BuffProtocol {
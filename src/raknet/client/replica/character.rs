@@ -1,13 +1,16 @@
-use std::io::{Error, ErrorKind::InvalidData, Read, Result as Res, Write};
+use std::io::{Read, Result as Res, Write};
 
 use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
+use crate::Error;
 use crate::common::{LuVarWString, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::dissect::Dissector;
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum TransitionState {
 	None,
 	Arrive { last_custom_build_parts: LuVarWString<u16> },
@@ -21,7 +24,7 @@ impl<R: Read> Deserialize<LE, BEBitReader<R>> for TransitionState {
 			0 => TransitionState::None,
 			1 => TransitionState::Arrive { last_custom_build_parts: LERead::read(reader)? },
 			2 => TransitionState::Leave,
-			_ => return Err(Error::new(InvalidData, "invalid discriminant for TransitionState")),
+			_ => return Err(Error::UnknownDiscriminant { type_name: "TransitionState", value: disc as u64 }.into()),
 		})
 	}
 }
@@ -39,7 +42,31 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a TransitionState {
 	}
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+impl crate::dissect::Dissect for TransitionState {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		let disc: u8 = match self {
+			TransitionState::None => 0,
+			TransitionState::Arrive { .. } => 1,
+			TransitionState::Leave => 2,
+		};
+		dissector.node("discriminant", "u8", |dissector| {
+			dissector.leaf(&disc, 2);
+			Ok(())
+		})?;
+		match self {
+			TransitionState::None => dissector.variant("None"),
+			TransitionState::Arrive { last_custom_build_parts } => {
+				dissector.variant("Arrive");
+				dissector.field("last_custom_build_parts", last_custom_build_parts)?;
+			}
+			TransitionState::Leave => dissector.variant("Leave"),
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct GmPvpInfo {
 	pub pvp_enabled: bool,
 	pub is_gm: bool,
@@ -48,7 +75,8 @@ pub struct GmPvpInfo {
 	pub editor_level: u8,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum GameActivity {
 	None,
@@ -59,7 +87,8 @@ pub enum GameActivity {
 	PetTaming,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 4] // country code, unused
 pub struct SocialInfo {
 	pub guild_id: ObjId,
@@ -67,7 +96,8 @@ pub struct SocialInfo {
 	pub is_lego_club_member: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterConstruction {
 	pub claim_code_1: Option<u64>,
 	pub claim_code_2: Option<u64>,
@@ -122,7 +152,8 @@ pub struct CharacterConstruction {
 	pub social_info: Option<SocialInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterSerialization {
 	pub gm_pvp_info: Option<GmPvpInfo>,
 	pub current_activity: Option<GameActivity>,
@@ -141,6 +172,22 @@ impl ComponentSerialization for CharacterSerialization {
 	}
 }
 
+impl ApplySerialization<CharacterSerialization> for CharacterConstruction {
+	fn apply_serialization(&mut self, serialization: CharacterSerialization) {
+		update_flagged(&mut self.gm_pvp_info, serialization.gm_pvp_info);
+		update_flagged(&mut self.current_activity, serialization.current_activity);
+		update_flagged(&mut self.social_info, serialization.social_info);
+	}
+}
+
+impl ComponentDiff for CharacterConstruction {
+	type Serialization = CharacterSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		CharacterSerialization { gm_pvp_info: flag_changed(&previous.gm_pvp_info, &current.gm_pvp_info), current_activity: flag_changed(&previous.current_activity, &current.current_activity), social_info: flag_changed(&previous.social_info, &current.social_info) }
+	}
+}
+
 pub struct CharacterProtocol;
 
 impl ComponentProtocol for CharacterProtocol {
@@ -4,9 +4,10 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CollectibleConstruction {
 	pub collectible_id: u16,
 }
@@ -25,6 +26,20 @@ impl ComponentSerialization for CollectibleSerialization {
 	}
 }
 
+impl ApplySerialization<CollectibleSerialization> for CollectibleConstruction {
+	fn apply_serialization(&mut self, serialization: CollectibleSerialization) {
+		self.collectible_id = serialization.collectible_id;
+	}
+}
+
+impl ComponentDiff for CollectibleConstruction {
+	type Serialization = CollectibleSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		CollectibleSerialization { collectible_id: current.collectible_id }
+	}
+}
+
 pub struct CollectibleProtocol;
 
 impl ComponentProtocol for CollectibleProtocol {
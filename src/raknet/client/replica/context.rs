@@ -0,0 +1,267 @@
+/*!
+	A [`ReplicaContext`] backed by the CDClient's component registry.
+
+	Which components a replica has, and in which order they are serialized, isn't part of the replica packets themselves. The client determines it from the `componentsregistry` table of the CDClient database, with a number of adjustments. [`CdClientContext`] reproduces this logic, getting the registry entries from a [`ComponentSource`].
+*/
+use std::collections::HashMap;
+use std::io::{Read, Result as Res};
+
+use endio_bit::BEBitReader;
+
+use crate::Error;
+use crate::world::{Lot, LnvValue, LuNameValue};
+use super::ReplicaContext;
+use super::registry::{AnyComponentConstruction, ComponentRegistry, ConstructionFn, DefaultComponents, SerializationFn};
+
+/**
+	Provides the component IDs registered for a LOT.
+
+	Usually backed by the `componentsregistry` table of the CDClient, but any source works, for example the provided implementation on `HashMap<Lot, Vec<u32>>`.
+*/
+pub trait ComponentSource {
+	/// The `component_type`s of the `componentsregistry` rows with `id` equal to `lot`, in any order. Empty if the LOT is unknown.
+	fn components(&mut self, lot: Lot) -> Vec<u32>;
+}
+
+impl ComponentSource for HashMap<Lot, Vec<u32>> {
+	fn components(&mut self, lot: Lot) -> Vec<u32> {
+		self.get(&lot).cloned().unwrap_or_default()
+	}
+}
+
+impl<S: ComponentSource + ?Sized> ComponentSource for &mut S {
+	fn components(&mut self, lot: Lot) -> Vec<u32> {
+		(**self).components(lot)
+	}
+}
+
+/**
+	Determines the components of a replica, in serialization order, from its registry entries and config.
+
+	This applies the adjustments the client makes:
+
+	- `componentWhitelist` in the config restricts components to a fixed set.
+	- Objects with model behaviors (42) get a physics component depending on `modelType`.
+	- Components are sorted by their [order](ComponentRegistry::order) in `components`.
+	- Some components imply others: 44 for 2, 110, 109 and 106 for 4, 98 for 7, and 7 for 23 and 48.
+	- Pets (26) don't have item (11) or model behavior (42) components.
+*/
+pub fn resolve_components<C: ComponentRegistry>(registry: &[u32], config: &Option<LuNameValue>, components: &C) -> Vec<u32> {
+	let mut comps = registry.to_vec();
+	apply_whitelist(&mut comps, config);
+	apply_config_overrides(&mut comps, config);
+
+	comps.sort_by_key(|&x| components.order(x).unwrap_or(usize::MAX));
+	comps.dedup();
+
+	let mut final_comps = vec![];
+	for comp in comps {
+		match comp {
+			2 => final_comps.push(44),
+			4 => final_comps.extend_from_slice(&[110, 109, 106]),
+			7 => final_comps.push(98),
+			23 | 48 => {
+				if !final_comps.contains(&7) {
+					final_comps.push(7);
+				}
+			}
+			_ => {}
+		}
+		final_comps.push(comp);
+	}
+	if final_comps.contains(&26) {
+		final_comps.retain(|&x| x != 11 && x != 42);
+	}
+	final_comps
+}
+
+fn config_value<'a>(config: &'a Option<LuNameValue>, key: &str) -> Option<&'a LnvValue> {
+	config.as_ref()?.get(&crate::lu!(key))
+}
+
+fn apply_whitelist(comps: &mut Vec<u32>, config: &Option<LuNameValue>) {
+	if let Some(LnvValue::I32(1)) = config_value(config, "componentWhitelist") {
+		comps.retain(|x| matches!(x, 1 | 2 | 3 | 7 | 10 | 11 | 24 | 42));
+	}
+}
+
+fn apply_config_overrides(comps: &mut Vec<u32>, config: &Option<LuNameValue>) {
+	if !comps.contains(&42) {
+		return;
+	}
+	let phys_index = comps.iter().position(|&x| x == 1 || x == 3);
+	if config_value(config, "modelBehaviors").is_some() {
+		if let Some(LnvValue::I32(model_type)) = config_value(config, "modelType") {
+			let new_phys = if *model_type == 0 { 1 } else { 3 };
+			match phys_index {
+				Some(i) => comps[i] = new_phys,
+				None => comps.push(new_phys),
+			}
+			return;
+		}
+	}
+	if phys_index.is_none() {
+		comps.push(3);
+	}
+}
+
+fn unknown_construction<R: Read>(_reader: &mut BEBitReader<R>) -> Res<AnyComponentConstruction> {
+	Err(Error::Malformed { type_name: "ReplicaConstruction", reason: "unknown component ID" }.into())
+}
+
+/**
+	Tracks the components of constructed replicas, so that their serializations can be decoded.
+
+	Since the context needs to outlive individual packets, it doesn't read data itself. Use [`reader`](Self::reader) to get a [`ContextReader`] for decoding a packet.
+
+	Example:
+
+	```
+	# use std::collections::HashMap;
+	# use lu_packets::raknet::client::replica::context::CdClientContext;
+	# use lu_packets::world::Lot;
+	use endio::LERead;
+	use lu_packets::unified::Message;
+
+	let mut registry: HashMap<Lot, Vec<u32>> = HashMap::new();
+	registry.insert(1, vec![1, 2, 4, 7, 9, 17]);
+	let mut context = CdClientContext::new(registry);
+	# let packet: &[u8] = &[];
+	let mut reader = context.reader(packet);
+	let message: std::io::Result<Message> = reader.read();
+	```
+*/
+pub struct CdClientContext<S, C = DefaultComponents> {
+	source: S,
+	components: C,
+	comps: HashMap<u16, Vec<u32>>,
+}
+
+impl<S: ComponentSource> CdClientContext<S> {
+	pub fn new(source: S) -> Self {
+		Self::with_registry(source, DefaultComponents)
+	}
+}
+
+impl<S: ComponentSource, C: ComponentRegistry> CdClientContext<S, C> {
+	/// Creates a context using a custom [`ComponentRegistry`], for protocol extensions.
+	pub fn with_registry(source: S, components: C) -> Self {
+		Self { source, components, comps: HashMap::new() }
+	}
+
+	pub fn source(&self) -> &S {
+		&self.source
+	}
+
+	pub fn source_mut(&mut self) -> &mut S {
+		&mut self.source
+	}
+
+	/// The components of a constructed replica, in serialization order.
+	pub fn components(&self, network_id: u16) -> Option<&[u32]> {
+		self.comps.get(&network_id).map(|x| &x[..])
+	}
+
+	/// Forgets the components of a replica, for when it's destroyed.
+	pub fn remove(&mut self, network_id: u16) -> Option<Vec<u32>> {
+		self.comps.remove(&network_id)
+	}
+
+	/// Forgets all replicas, for when switching worlds.
+	pub fn clear(&mut self) {
+		self.comps.clear();
+	}
+
+	/// Wraps `inner` in a reader that uses this context for replica packets.
+	pub fn reader<R: Read>(&mut self, inner: R) -> ContextReader<'_, S, R, C> {
+		ContextReader { context: self, inner, unknown_network_id: None }
+	}
+}
+
+/// A reader using a [`CdClientContext`] to decode replica packets.
+pub struct ContextReader<'a, S, R, C = DefaultComponents> {
+	context: &'a mut CdClientContext<S, C>,
+	inner: R,
+	unknown_network_id: Option<u16>,
+}
+
+impl<S, R, C> ContextReader<'_, S, R, C> {
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+
+	/**
+		The network ID of the last serialization of a replica not constructed through the context.
+
+		The components of such a serialization can't be known, so they aren't decoded, and the rest of the packet is left unread.
+	*/
+	pub fn unknown_network_id(&self) -> Option<u16> {
+		self.unknown_network_id
+	}
+}
+
+impl<S, R: Read, C> Read for ContextReader<'_, S, R, C> {
+	fn read(&mut self, buf: &mut [u8]) -> Res<usize> {
+		self.inner.read(buf)
+	}
+}
+
+impl<S: ComponentSource, R, C: ComponentRegistry> ReplicaContext for ContextReader<'_, S, R, C> {
+	fn get_comp_constructions<RR: Read>(&mut self, network_id: u16, lot: Lot, config: &Option<LuNameValue>) -> Vec<ConstructionFn<RR>> {
+		let context = &mut *self.context;
+		let registry = context.source.components(lot);
+		let comps = resolve_components(&registry, config, &context.components);
+		let constrs = comps
+			.iter()
+			.filter_map(|&x| {
+				if !context.components.is_known(x) {
+					return Some(unknown_construction as ConstructionFn<RR>);
+				}
+				context.components.construction(x)
+			})
+			.collect();
+		self.context.comps.insert(network_id, comps);
+		constrs
+	}
+
+	fn get_comp_serializations<RR: Read>(&mut self, network_id: u16) -> Vec<SerializationFn<RR>> {
+		match self.context.comps.get(&network_id) {
+			Some(comps) => comps.iter().filter_map(|&x| self.context.components.serialization(x)).collect(),
+			None => {
+				self.unknown_network_id = Some(network_id);
+				vec![]
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_components() {
+		assert_eq!(resolve_components(&[4, 7, 2, 1, 17, 9], &None, &DefaultComponents), vec![1, 98, 7, 110, 109, 106, 4, 17, 9, 44, 2]);
+		assert_eq!(resolve_components(&[26, 11, 42, 7], &None, &DefaultComponents), vec![3, 98, 7, 26]);
+		let config = crate::lnv! { "componentWhitelist": 1i32, };
+		assert_eq!(resolve_components(&[1, 5, 7], &Some(config), &DefaultComponents), vec![1, 98, 7]);
+	}
+
+	#[test]
+	fn test_serializations_follow_constructions() {
+		let mut registry: HashMap<Lot, Vec<u32>> = HashMap::new();
+		registry.insert(1, vec![1, 2]);
+		let mut context = CdClientContext::new(registry);
+		let mut reader = context.reader(&b""[..]);
+		assert_eq!(reader.get_comp_constructions::<&[u8]>(5, 1, &None).len(), 2);
+		assert_eq!(reader.get_comp_serializations::<&[u8]>(5).len(), 1);
+		assert_eq!(reader.unknown_network_id(), None);
+		assert!(reader.get_comp_serializations::<&[u8]>(6).is_empty());
+		assert_eq!(reader.unknown_network_id(), Some(6));
+		assert_eq!(context.components(5), Some(&[1, 44, 2][..]));
+	}
+}
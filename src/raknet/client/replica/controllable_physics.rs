@@ -2,20 +2,22 @@ use std::io::{Result as Res};
 
 use endio::{Deserialize, Serialize};
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, ReplicaSerde};
 
 use crate::common::ObjId;
 use crate::world::{Vector3, Quaternion};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct JetpackInfo {
 	pub effect_id: i32, // todo: id
 	pub is_flying: bool,
 	pub bypass_checks: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StunImmunityInfo {
 	// todo: type
 	pub immune_to_stun_move: i32,
@@ -27,31 +29,36 @@ pub struct StunImmunityInfo {
 	pub immune_to_stun_interact: i32,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CheatInfo {
 	pub gravity_scale: f32,
 	pub run_multiplier: f32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MagnetAndFlyingUpdate {
 	pub loot_pickup_radius: f32,
 	pub is_flying: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BubbleInfo {
 	pub bubble_type: i32,
 	pub special_animation: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BubbleUpdateInfo {
 	/// If option is not set, the bubble is removed.
 	pub bubble_info: Option<BubbleInfo>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FrameStats {
 	pub position: Vector3,
 	pub rotation: Quaternion,
@@ -66,7 +73,8 @@ pub struct FrameStats {
 	pub local_space_info: Option<LocalSpaceInfo>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LocalSpaceInfo {
 	pub object_id: ObjId,
 	pub position: Vector3,
@@ -75,7 +83,8 @@ pub struct LocalSpaceInfo {
 	pub linear_velocity: Option<Vector3>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ControllablePhysicsConstruction {
 	pub jetpack_info: Option<JetpackInfo>,
 	pub stun_immunity_info: Option<StunImmunityInfo>,
@@ -85,13 +94,15 @@ pub struct ControllablePhysicsConstruction {
 	pub frame_stats: Option<FrameStats>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FrameStatsTeleportInfo {
 	pub frame_stats: FrameStats,
 	pub is_teleporting: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ControllablePhysicsSerialization {
 	pub cheat_info: Option<CheatInfo>,
 	pub magnet_and_flying_update: Option<MagnetAndFlyingUpdate>,
@@ -111,6 +122,26 @@ impl ComponentSerialization for ControllablePhysicsSerialization {
 	}
 }
 
+impl ApplySerialization<ControllablePhysicsSerialization> for ControllablePhysicsConstruction {
+	fn apply_serialization(&mut self, serialization: ControllablePhysicsSerialization) {
+		update_flagged(&mut self.cheat_info, serialization.cheat_info);
+		update_flagged(&mut self.magnet_and_flying_update, serialization.magnet_and_flying_update);
+		update_flagged(&mut self.bubble_update_info, serialization.bubble_update_info);
+		if let Some(info) = serialization.frame_stats_teleport_info {
+			self.frame_stats = Some(info.frame_stats);
+		}
+	}
+}
+
+impl ComponentDiff for ControllablePhysicsConstruction {
+	type Serialization = ControllablePhysicsSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		let frame_stats = flag_changed(&previous.frame_stats, &current.frame_stats);
+		ControllablePhysicsSerialization { cheat_info: flag_changed(&previous.cheat_info, &current.cheat_info), magnet_and_flying_update: flag_changed(&previous.magnet_and_flying_update, &current.magnet_and_flying_update), bubble_update_info: flag_changed(&previous.bubble_update_info, &current.bubble_update_info), frame_stats_teleport_info: frame_stats.map(|frame_stats| FrameStatsTeleportInfo { frame_stats, is_teleporting: false }) }
+	}
+}
+
 pub struct ControllablePhysicsProtocol;
 
 impl ComponentProtocol for ControllablePhysicsProtocol {
@@ -2,12 +2,14 @@ use std::io::{Read, Result as Res, Write};
 
 use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, ReplicaSerde};
 
 use crate::common::LVec;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::dissect::Dissector;
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StatusImmunityInfo {
 	pub immune_to_basic_attack: u32,
 	pub immune_to_damage_over_time: u32,
@@ -20,14 +22,16 @@ pub struct StatusImmunityInfo {
 	pub immune_to_pull_to_point: u32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SmashableInfo {
 	pub is_module_assembly: bool,
 	pub explode_factor: Option<f32>,
 }
 
 // so close to being able to do serialization automatically...if not for the irregularity with `smashable_info`...
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StatsInfo {
 	pub cur_health: u32,
 	pub max_health: f32,
@@ -121,14 +125,42 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a StatsInfo {
 	}
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl crate::dissect::Dissect for StatsInfo {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("cur_health", &self.cur_health)?;
+		dissector.field("max_health", &self.max_health)?;
+		dissector.field("cur_armor", &self.cur_armor)?;
+		dissector.field("max_armor", &self.max_armor)?;
+		dissector.field("cur_imag", &self.cur_imag)?;
+		dissector.field("max_imag", &self.max_imag)?;
+		dissector.field("damage_absorption_points", &self.damage_absorption_points)?;
+		dissector.bit("immunity", self.immunity)?;
+		dissector.bit("is_gm_immune", self.is_gm_immune)?;
+		dissector.bit("is_shielded", self.is_shielded)?;
+		dissector.field("actual_max_health", &self.actual_max_health)?;
+		dissector.field("actual_max_armor", &self.actual_max_armor)?;
+		dissector.field("actual_max_imag", &self.actual_max_imag)?;
+		dissector.field("factions", &self.factions)?;
+		dissector.bit("is_smashable", self.smashable_info.is_some())?;
+		dissector.bit("is_dead", self.is_dead)?;
+		dissector.bit("is_smashed", self.is_smashed)?;
+		if let Some(x) = &self.smashable_info {
+			dissector.field("smashable_info", x)?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DestroyableConstruction {
 	pub status_immunity_info: Option<StatusImmunityInfo>,
 	pub stats_info: Option<StatsInfo>,
 	pub is_on_a_threat_list: Option<bool>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SerializationStatsInfo {
 	pub cur_health: u32,
 	pub max_health: f32,
@@ -147,7 +179,8 @@ pub struct SerializationStatsInfo {
 	pub is_smashable: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DestroyableSerialization {
 	pub serialization_stats_info: Option<SerializationStatsInfo>,
 	pub is_on_a_threat_list: Option<bool>,
@@ -165,6 +198,34 @@ impl ComponentSerialization for DestroyableSerialization {
 	}
 }
 
+impl ApplySerialization<DestroyableSerialization> for DestroyableConstruction {
+	fn apply_serialization(&mut self, serialization: DestroyableSerialization) {
+		if let Some(info) = serialization.serialization_stats_info {
+			let (is_dead, is_smashed, smashable_info) = match self.stats_info.take() {
+				Some(x) => (x.is_dead, x.is_smashed, x.smashable_info),
+				None => (false, false, None),
+			};
+			let smashable_info = match (info.is_smashable, smashable_info) {
+				(false, _) => None,
+				(true, Some(x)) => Some(x),
+				// the serialization only says whether the object is smashable, not how
+				(true, None) => Some(SmashableInfo { is_module_assembly: false, explode_factor: None }),
+			};
+			self.stats_info = Some(StatsInfo { cur_health: info.cur_health, max_health: info.max_health, cur_armor: info.cur_armor, max_armor: info.max_armor, cur_imag: info.cur_imag, max_imag: info.max_imag, damage_absorption_points: info.damage_absorption_points, immunity: info.immunity, is_gm_immune: info.is_gm_immune, is_shielded: info.is_shielded, actual_max_health: info.actual_max_health, actual_max_armor: info.actual_max_armor, actual_max_imag: info.actual_max_imag, factions: info.factions, is_dead, is_smashed, smashable_info });
+		}
+		update_flagged(&mut self.is_on_a_threat_list, serialization.is_on_a_threat_list);
+	}
+}
+
+impl ComponentDiff for DestroyableConstruction {
+	type Serialization = DestroyableSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		let serialization_stats_info = flag_changed(&previous.stats_info, &current.stats_info).map(|x| SerializationStatsInfo { cur_health: x.cur_health, max_health: x.max_health, cur_armor: x.cur_armor, max_armor: x.max_armor, cur_imag: x.cur_imag, max_imag: x.max_imag, damage_absorption_points: x.damage_absorption_points, immunity: x.immunity, is_gm_immune: x.is_gm_immune, is_shielded: x.is_shielded, actual_max_health: x.actual_max_health, actual_max_armor: x.actual_max_armor, actual_max_imag: x.actual_max_imag, factions: x.factions, is_smashable: x.smashable_info.is_some() });
+		DestroyableSerialization { serialization_stats_info, is_on_a_threat_list: flag_changed(&previous.is_on_a_threat_list, &current.is_on_a_threat_list) }
+	}
+}
+
 pub struct DestroyableProtocol;
 
 impl ComponentProtocol for DestroyableProtocol {
@@ -4,17 +4,19 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::vendor::VendorInfo;
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DonationVendorInfo {
 	pub percent_complete: f32,
 	pub total_donated: u32,
 	pub total_remaining: u32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DonationVendorConstruction {
 	pub vendor_info: Option<VendorInfo>,
 	pub donation_vendor_info: Option<DonationVendorInfo>,
@@ -34,6 +36,21 @@ impl ComponentSerialization for DonationVendorSerialization {
 	}
 }
 
+impl ApplySerialization<DonationVendorSerialization> for DonationVendorConstruction {
+	fn apply_serialization(&mut self, serialization: DonationVendorSerialization) {
+		update_flagged(&mut self.vendor_info, serialization.vendor_info);
+		update_flagged(&mut self.donation_vendor_info, serialization.donation_vendor_info);
+	}
+}
+
+impl ComponentDiff for DonationVendorConstruction {
+	type Serialization = DonationVendorSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		DonationVendorSerialization { vendor_info: flag_changed(&previous.vendor_info, &current.vendor_info), donation_vendor_info: flag_changed(&previous.donation_vendor_info, &current.donation_vendor_info) }
+	}
+}
+
 pub struct DonationVendorProtocol;
 
 impl ComponentProtocol for DonationVendorProtocol {
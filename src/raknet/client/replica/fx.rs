@@ -4,10 +4,11 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use crate::common::{LuVarString, LuVarWString, LVec, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::common::{LuVarString, LuVarWString, LVec, LVecElement, ObjId};
+use super::{ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EffectInfo {
 	pub effect_name: LuVarString<u8>,
 	pub effect_id: u32, // todo: type
@@ -16,12 +17,16 @@ pub struct EffectInfo {
 	pub secondary: ObjId,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for EffectInfo {}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FxConstruction {
 	pub active_effects: LVec<u32, EffectInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FxSerialization {}
 
 impl ComponentConstruction for FxConstruction {
@@ -36,6 +41,14 @@ impl ComponentSerialization for FxSerialization {
 	}
 }
 
+impl ComponentDiff for FxConstruction {
+	type Serialization = FxSerialization;
+
+	fn diff(_previous: &Self, _current: &Self) -> Self::Serialization {
+		FxSerialization {}
+	}
+}
+
 pub struct FxProtocol;
 
 impl ComponentProtocol for FxProtocol {
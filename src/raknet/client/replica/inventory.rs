@@ -4,12 +4,13 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use crate::common::{LVec, ObjId};
+use crate::common::{LVec, LVecElement, ObjId};
 use crate::world::{LuNameValue, Lot, Quaternion, Vector3};
 use crate::world::gm::InventoryType;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EquippedItemInfo {
 	pub id: ObjId,
 	pub lot: Lot,
@@ -21,14 +22,20 @@ pub struct EquippedItemInfo {
 	pub is_bound: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for EquippedItemInfo {}
+
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EquippedModelTransform {
 	pub model_id: ObjId,
 	pub equip_position: Vector3,
 	pub equip_rotation: Quaternion,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for EquippedModelTransform {}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct InventoryConstruction {
 	pub equipped_items: Option<LVec<u32, EquippedItemInfo>>,
 	pub equipped_model_transforms: Option<LVec<u32, EquippedModelTransform>>,
@@ -48,6 +55,21 @@ impl ComponentSerialization for InventorySerialization {
 	}
 }
 
+impl ApplySerialization<InventorySerialization> for InventoryConstruction {
+	fn apply_serialization(&mut self, serialization: InventorySerialization) {
+		update_flagged(&mut self.equipped_items, serialization.equipped_items);
+		update_flagged(&mut self.equipped_model_transforms, serialization.equipped_model_transforms);
+	}
+}
+
+impl ComponentDiff for InventoryConstruction {
+	type Serialization = InventorySerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		InventorySerialization { equipped_items: flag_changed(&previous.equipped_items, &current.equipped_items), equipped_model_transforms: flag_changed(&previous.equipped_model_transforms, &current.equipped_model_transforms) }
+	}
+}
+
 pub struct InventoryProtocol;
 
 impl ComponentProtocol for InventoryProtocol {
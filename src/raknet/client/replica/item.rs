@@ -1,13 +1,14 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::common::{LuVarWString, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum UgcModerationStatus {
 	NoStatus,
@@ -15,14 +16,16 @@ pub enum UgcModerationStatus {
 	Rejected,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ItemInfo {
 	pub ug_id: ObjId,
 	pub ug_moderation_status: UgcModerationStatus,
 	pub ug_description: Option<LuVarWString<u32>>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ItemConstruction {
 	pub item_info: Option<ItemInfo>,
 }
@@ -41,6 +44,20 @@ impl ComponentSerialization for ItemSerialization {
 	}
 }
 
+impl ApplySerialization<ItemSerialization> for ItemConstruction {
+	fn apply_serialization(&mut self, serialization: ItemSerialization) {
+		update_flagged(&mut self.item_info, serialization.item_info);
+	}
+}
+
+impl ComponentDiff for ItemConstruction {
+	type Serialization = ItemSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		ItemSerialization { item_info: flag_changed(&previous.item_info, &current.item_info) }
+	}
+}
+
 pub struct ItemProtocol;
 
 impl ComponentProtocol for ItemProtocol {
@@ -4,9 +4,10 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LevelProgressionConstruction {
 	pub current_level: Option<u32>,
 }
@@ -25,6 +26,20 @@ impl ComponentSerialization for LevelProgressionSerialization {
 	}
 }
 
+impl ApplySerialization<LevelProgressionSerialization> for LevelProgressionConstruction {
+	fn apply_serialization(&mut self, serialization: LevelProgressionSerialization) {
+		update_flagged(&mut self.current_level, serialization.current_level);
+	}
+}
+
+impl ComponentDiff for LevelProgressionConstruction {
+	type Serialization = LevelProgressionSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		LevelProgressionSerialization { current_level: flag_changed(&previous.current_level, &current.current_level) }
+	}
+}
+
 pub struct LevelProgressionProtocol;
 
 impl ComponentProtocol for LevelProgressionProtocol {
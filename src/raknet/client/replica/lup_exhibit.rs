@@ -5,9 +5,10 @@ use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::world::Lot;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LupExhibitConstruction {
 	pub exhibited_lot: Option<Lot>,
 }
@@ -26,6 +27,20 @@ impl ComponentSerialization for LupExhibitSerialization {
 	}
 }
 
+impl ApplySerialization<LupExhibitSerialization> for LupExhibitConstruction {
+	fn apply_serialization(&mut self, serialization: LupExhibitSerialization) {
+		update_flagged(&mut self.exhibited_lot, serialization.exhibited_lot);
+	}
+}
+
+impl ComponentDiff for LupExhibitConstruction {
+	type Serialization = LupExhibitSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		LupExhibitSerialization { exhibited_lot: flag_changed(&previous.exhibited_lot, &current.exhibited_lot) }
+	}
+}
+
 pub struct LupExhibitProtocol;
 
 impl ComponentProtocol for LupExhibitProtocol {
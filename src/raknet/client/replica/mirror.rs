@@ -0,0 +1,174 @@
+/*!
+	Client-side state of replicas.
+
+	A [`ReplicaMirror`] keeps the constructions of the replicas received from a server, and applies serializations onto them, so that it always holds the current state of every replica in view.
+*/
+use std::collections::BTreeMap;
+use std::io::Result as Res;
+
+use crate::Error;
+use crate::common::ObjId;
+use crate::world::Lot;
+use super::{ConstructionComponent, ReplicaConstruction, ReplicaSerialization, update_flagged};
+
+/**
+	The current state of replicas, by network ID.
+
+	Feed it the replica messages received from the server, decoded using a [`ReplicaContext`](super::ReplicaContext):
+
+	```
+	# use lu_packets::raknet::client::Message;
+	# use lu_packets::raknet::client::replica::destroyable::DestroyableConstruction;
+	# use lu_packets::raknet::client::replica::mirror::ReplicaMirror;
+	# fn handle(mirror: &mut ReplicaMirror, message: Message<()>) -> std::io::Result<()> {
+	match message {
+		Message::ReplicaConstruction(x) => { mirror.construct(x); }
+		Message::ReplicaSerialization(x) => mirror.serialize(x)?,
+		Message::ReplicaDestruction(x) => { mirror.destroy(x.network_id); }
+		_ => {}
+	}
+	for (replica, destroyable) in mirror.with_component::<DestroyableConstruction>() {
+		println!("{:?}: {:?}", replica.object_id, destroyable.stats_info);
+	}
+	# Ok(())
+	# }
+	```
+*/
+#[derive(Debug, Default)]
+pub struct ReplicaMirror {
+	replicas: BTreeMap<u16, ReplicaConstruction>,
+}
+
+impl ReplicaMirror {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a replica, returning the replica it replaces if its network ID was in use.
+	pub fn construct(&mut self, construction: ReplicaConstruction) -> Option<ReplicaConstruction> {
+		self.replicas.insert(construction.network_id, construction)
+	}
+
+	/**
+		Applies a serialization onto the state of its replica.
+
+		The components of the serialization are matched with the replica's components in order. Fails if the replica doesn't exist, or if a component of the serialization doesn't match any remaining component of the replica, in which case the replica may be partially updated.
+	*/
+	pub fn serialize(&mut self, serialization: ReplicaSerialization) -> Res<()> {
+		let replica = match self.replicas.get_mut(&serialization.network_id) {
+			Some(x) => x,
+			None => return Err(Error::Malformed { type_name: "ReplicaSerialization", reason: "unknown network ID" }.into()),
+		};
+		if let Some(info) = serialization.parent_child_info {
+			match &mut replica.parent_child_info {
+				Some(x) => {
+					update_flagged(&mut x.parent_info, info.parent_info);
+					update_flagged(&mut x.child_info, info.child_info);
+				}
+				None => replica.parent_child_info = Some(info),
+			}
+		}
+		let mut comps = replica.components.iter_mut();
+		'outer: for mut ser in serialization.components {
+			for comp in &mut comps {
+				match comp.apply_serialization(ser) {
+					Ok(()) => continue 'outer,
+					Err(x) => ser = x,
+				}
+			}
+			return Err(Error::Malformed { type_name: "ReplicaSerialization", reason: "component not present in construction" }.into());
+		}
+		Ok(())
+	}
+
+	/// Removes a replica, returning its last state.
+	pub fn destroy(&mut self, network_id: u16) -> Option<ReplicaConstruction> {
+		self.replicas.remove(&network_id)
+	}
+
+	/// Removes all replicas, for when switching worlds.
+	pub fn clear(&mut self) {
+		self.replicas.clear();
+	}
+
+	pub fn get(&self, network_id: u16) -> Option<&ReplicaConstruction> {
+		self.replicas.get(&network_id)
+	}
+
+	pub fn get_by_object_id(&self, object_id: ObjId) -> Option<&ReplicaConstruction> {
+		self.replicas.values().find(|x| x.object_id == object_id)
+	}
+
+	pub fn iter_by_lot(&self, lot: Lot) -> impl Iterator<Item = &ReplicaConstruction> {
+		self.replicas.values().filter(move |x| x.lot == lot)
+	}
+
+	/// All replicas having a component of type `T`, with that component.
+	pub fn with_component<T: ConstructionComponent>(&self) -> impl Iterator<Item = (&ReplicaConstruction, &T)> {
+		self.replicas.values().filter_map(|x| Some((x, x.component::<T>()?)))
+	}
+
+	/// All replicas, in order of network ID.
+	pub fn iter(&self) -> impl Iterator<Item = &ReplicaConstruction> {
+		self.replicas.values()
+	}
+
+	pub fn len(&self) -> usize {
+		self.replicas.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.replicas.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::lu;
+	use super::super::bouncer::BouncerConstruction;
+	use super::super::switch::SwitchConstruction;
+	use super::*;
+
+	fn construction(network_id: u16) -> ReplicaConstruction {
+		ReplicaConstruction { network_id, object_id: 70368744177662 + network_id as ObjId, lot: 13006, name: lu!(""), time_since_created_on_server: 0, config: None, is_trigger: false, spawner_id: None, spawner_node_id: None, scale: None, world_state: None, gm_level: None, parent_child_info: None, components: vec![BouncerConstruction { bounce_on_collision: Some(true) }.into(), SwitchConstruction { is_active: false }.into()] }
+	}
+
+	#[test]
+	fn test_serialize() {
+		let mut mirror = ReplicaMirror::new();
+		mirror.construct(construction(1));
+		mirror.construct(construction(2));
+		mirror.serialize(ReplicaSerialization { network_id: 1, parent_child_info: None, components: vec![SwitchConstruction { is_active: true }.into()] }).unwrap();
+		assert_eq!(mirror.get(1).unwrap().component::<SwitchConstruction>(), Some(&SwitchConstruction { is_active: true }));
+		assert_eq!(mirror.get(1).unwrap().component::<BouncerConstruction>(), Some(&BouncerConstruction { bounce_on_collision: Some(true) }));
+		mirror.serialize(ReplicaSerialization { network_id: 1, parent_child_info: None, components: vec![BouncerConstruction { bounce_on_collision: None }.into(), SwitchConstruction { is_active: false }.into()] }).unwrap();
+		assert_eq!(mirror.get(1).unwrap().component::<BouncerConstruction>(), Some(&BouncerConstruction { bounce_on_collision: Some(true) }));
+		assert_eq!(mirror.get(1), Some(&construction(1)));
+
+		assert!(mirror.serialize(ReplicaSerialization { network_id: 3, parent_child_info: None, components: vec![] }).is_err());
+		assert!(mirror.serialize(ReplicaSerialization { network_id: 2, parent_child_info: None, components: vec![SwitchConstruction { is_active: true }.into(), BouncerConstruction { bounce_on_collision: None }.into()] }).is_err());
+
+		assert_eq!(mirror.with_component::<SwitchConstruction>().count(), 2);
+		assert!(mirror.destroy(2).is_some());
+		assert_eq!(mirror.get_by_object_id(70368744177663).map(|x| x.network_id), Some(1));
+		assert_eq!(mirror.len(), 1);
+	}
+
+	#[test]
+	fn test_diff() {
+		let previous = construction(1);
+		let mut current = construction(1);
+		current.components = vec![BouncerConstruction { bounce_on_collision: Some(false) }.into(), SwitchConstruction { is_active: false }.into()];
+		let serialization = ReplicaSerialization::diff(&previous, &current).unwrap();
+		assert_eq!(serialization.components, vec![BouncerConstruction { bounce_on_collision: Some(false) }.into(), SwitchConstruction { is_active: false }.into()]);
+		assert_eq!(ReplicaSerialization::diff(&previous, &previous).unwrap().components[0], BouncerConstruction { bounce_on_collision: None }.into());
+
+		let mut mirror = ReplicaMirror::new();
+		mirror.construct(previous);
+		mirror.serialize(serialization).unwrap();
+		assert_eq!(mirror.get(1), Some(&current));
+
+		current.components.pop();
+		assert!(ReplicaSerialization::diff(&construction(1), &current).is_none());
+	}
+}
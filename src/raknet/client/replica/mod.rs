@@ -5,6 +5,7 @@ pub mod bouncer;
 pub mod buff;
 pub mod character;
 pub mod collectible;
+pub mod context;
 pub mod controllable_physics;
 pub mod donation_vendor;
 pub mod destroyable;
@@ -13,6 +14,7 @@ pub mod inventory;
 pub mod item;
 pub mod level_progression;
 pub mod lup_exhibit;
+pub mod mirror;
 pub mod mutable_model_behavior;
 pub mod module_assembly;
 pub mod moving_platform;
@@ -23,6 +25,7 @@ pub mod possessable;
 pub mod possession_control;
 pub mod quickbuild;
 pub mod racing_control;
+pub mod registry;
 pub mod rigid_body_phantom_physics;
 pub mod script;
 pub mod scripted_activity;
@@ -38,61 +41,81 @@ use std::io::{Read, Result as Res, Write};
 
 use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
-use lu_packets_derive::ReplicaSerde;
+use lu_packets_derive::{Dissect, ReplicaSerde};
 
+use crate::Error;
 use crate::common::{ObjId, LuVarWString, LVec};
+use crate::dissect::{Dissector, Layout};
 use crate::world::{Lot, LuNameValue};
+use self::registry::{ConstructionFn, SerializationFn};
 
-trait ReplicaD<R: Read>: Sized {
-	fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self>;
-}
+pub use self::registry::{AnyComponentConstruction, AnyComponentSerialization, ComponentKind, ComponentRegistry, ConstructionComponent, DefaultComponents, SerializationComponent};
 
-trait ReplicaS<W: Write> {
-	fn serialize(self, writer: &mut BEBitWriter<W>) -> Res<()>;
+/// Counts the bytes read through it, to locate errors in bit streams.
+struct Counting<'a, R> {
+	inner: &'a mut R,
+	count: u64,
 }
 
-impl<R: Read, T: Deserialize<LE, BEBitReader<R>>> ReplicaD<R> for T {
-	default fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self> {
-		Deserialize::deserialize(reader)
+impl<R: Read> Read for Counting<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> Res<usize> {
+		let len = self.inner.read(buf)?;
+		self.count += len as u64;
+		Ok(len)
 	}
 }
 
-impl<'a, W: Write, T> ReplicaS<W> for &'a T
-where
-	&'a T: Serialize<LE, BEBitWriter<W>>,
-{
-	default fn serialize(self, writer: &mut BEBitWriter<W>) -> Res<()> {
-		Serialize::serialize(self, writer)
-	}
+/// Runs `f` on a bit stream read from `reader`, recording the position in the stream reading stopped at in its errors.
+fn read_bits<'a, R: Read, T>(reader: &'a mut R, f: impl FnOnce(&mut BEBitReader<Counting<'a, R>>) -> Res<T>) -> Res<T> {
+	let mut bit_reader = BEBitReader::new(Counting { inner: reader, count: 0 });
+	f(&mut bit_reader).map_err(|err| {
+		let offset = bit_offset(&mut bit_reader);
+		crate::error::with_bit_offset(err, offset)
+	})
 }
 
-impl<R: Read> ReplicaD<R> for bool {
-	fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self> {
-		reader.read_bit()
+/**
+	The number of bits read from `bit_reader`.
+
+	The bit reader only exposes how many bytes it has taken from the stream, so the bits left in the current byte are found by reading them, up to the point where the next byte would be taken. This consumes the rest of the stream's current byte, and possibly the next one, so it's only used once reading has failed.
+*/
+fn bit_offset<R: Read>(bit_reader: &mut BEBitReader<Counting<'_, R>>) -> u64 {
+	let count = bit_reader.get_ref().count;
+	let mut rest = 0;
+	while rest < 8 && bit_reader.read_bit().is_ok() && bit_reader.get_ref().count == count {
+		rest += 1;
 	}
+	count * 8 - rest
 }
 
-impl<'a, W: Write> ReplicaS<W> for &'a bool {
-	fn serialize(self, writer: &mut BEBitWriter<W>) -> Res<()> {
-		writer.write_bit(*self)
-	}
+/**
+	Optional values in replica bit streams, which are prefixed with a bit indicating whether the value is present.
+
+	The `ReplicaSerde` derive handles these, as well as `bool`s, which are single bits, based on how the field types are written. Type aliases of them aren't recognized. This is for manual implementations.
+*/
+trait ReplicaD<R: Read>: Sized {
+	fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self>;
+}
+
+trait ReplicaS<W: Write> {
+	fn serialize(self, writer: &mut BEBitWriter<W>) -> Res<()>;
 }
 
-impl<R: Read, T: ReplicaD<R> + Deserialize<LE, BEBitReader<R>>> ReplicaD<R> for Option<T> {
+impl<R: Read, T: Deserialize<LE, BEBitReader<R>>> ReplicaD<R> for Option<T> {
 	fn deserialize(reader: &mut BEBitReader<R>) -> Res<Self> {
 		let bit = reader.read_bit()?;
-		Ok(if !bit { None } else { Some(ReplicaD::deserialize(reader)?) })
+		Ok(if !bit { None } else { Some(LERead::read(reader)?) })
 	}
 }
 
-impl<W: Write, T> ReplicaS<W> for &Option<T>
+impl<'a, W: Write, T> ReplicaS<W> for &'a Option<T>
 where
-	for<'a> &'a T: ReplicaS<W> + Serialize<LE, BEBitWriter<W>>,
+	&'a T: Serialize<LE, BEBitWriter<W>>,
 {
 	fn serialize(self, writer: &mut BEBitWriter<W>) -> Res<()> {
 		writer.write_bit(self.is_some())?;
 		if let Some(x) = self {
-			ReplicaS::serialize(x, writer)?;
+			LEWrite::write(writer, x)?;
 		}
 		Ok(())
 	}
@@ -111,29 +134,78 @@ pub trait ComponentProtocol {
 	type Serialization: ComponentSerialization;
 }
 
+/**
+	Construction data of a component that can be updated with the component's serialization data.
+
+	Most serializations consist of optional groups of data, which are only set if the data changed. Applying a serialization overwrites the groups that are set.
+*/
+pub trait ApplySerialization<S> {
+	fn apply_serialization(&mut self, serialization: S);
+}
+
+/// Overwrites `state` if `update` is set, as with the optional groups in serializations.
+fn update_flagged<T>(state: &mut Option<T>, update: Option<T>) {
+	if update.is_some() {
+		*state = update;
+	}
+}
+
+/**
+	Construction data of a component that can be compared with a previous state, to build a serialization containing only what changed.
+
+	Optional groups of the serialization are only set if they changed, other data is always included. Groups that changed to `None` can't be expressed in a serialization and are left unset as well. Applying the result to `previous` with [`ApplySerialization`] yields `current`, apart from data that isn't part of the serialization.
+*/
+pub trait ComponentDiff {
+	type Serialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization;
+}
+
+/// The value of an optional group in a serialization, set if it changed.
+fn flag_changed<T: Clone + PartialEq>(previous: &Option<T>, current: &Option<T>) -> Option<T> {
+	if previous != current {
+		current.clone()
+	} else {
+		None
+	}
+}
+
+/// Dissects the elements of a list in a replica bit stream, where each element is prefixed with a set bit, and the end is marked with an unset bit.
+fn dissect_bit_list<T: crate::dissect::Dissect>(list: &[T], dissector: &mut Dissector) -> Res<()> {
+	for (i, x) in list.iter().enumerate() {
+		dissector.bit("has_next", true)?;
+		dissector.field(&i.to_string(), x)?;
+	}
+	dissector.bit("has_next", false)
+}
+
 pub trait ReplicaContext {
-	fn get_comp_constructions<R: Read>(&mut self, network_id: u16, lot: Lot, config: &Option<LuNameValue>) -> Vec<fn(&mut BEBitReader<R>) -> Res<Box<dyn ComponentConstruction>>>;
-	fn get_comp_serializations<R: Read>(&mut self, network_id: u16) -> Vec<fn(&mut BEBitReader<R>) -> Res<Box<dyn ComponentSerialization>>>;
+	fn get_comp_constructions<R: Read>(&mut self, network_id: u16, lot: Lot, config: &Option<LuNameValue>) -> Vec<ConstructionFn<R>>;
+	fn get_comp_serializations<R: Read>(&mut self, network_id: u16) -> Vec<SerializationFn<R>>;
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ParentInfo {
 	pub parent_id: ObjId,
 	pub update_position_with_parent: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ChildInfo {
 	pub child_ids: LVec<u16, ObjId>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ParentChildInfo {
 	pub parent_info: Option<ParentInfo>,
 	pub child_info: Option<ChildInfo>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ReplicaConstruction {
 	pub network_id: u16,
 	pub object_id: ObjId,
@@ -148,38 +220,49 @@ pub struct ReplicaConstruction {
 	pub world_state: Option<u8>, // todo: type
 	pub gm_level: Option<u8>,    // todo: type
 	pub parent_child_info: Option<ParentChildInfo>,
-	pub components: Vec<Box<dyn ComponentConstruction>>,
+	pub components: Vec<AnyComponentConstruction>,
 }
 
-impl PartialEq<ReplicaConstruction> for ReplicaConstruction {
-	fn eq(&self, rhs: &ReplicaConstruction) -> bool {
-		// hacky but i don't know a better way
-		format!("{:?}", self) == format!("{:?}", rhs)
+impl ReplicaConstruction {
+	/// The data of the component of type `T`, if the replica has it.
+	pub fn component<T: ConstructionComponent>(&self) -> Option<&T> {
+		self.components.iter().find_map(AnyComponentConstruction::get)
+	}
+
+	/// The data of the component of type `T`, if the replica has it.
+	pub fn component_mut<T: ConstructionComponent>(&mut self) -> Option<&mut T> {
+		self.components.iter_mut().find_map(AnyComponentConstruction::get_mut)
 	}
 }
 
 impl<R: Read + ReplicaContext> Deserialize<LE, R> for ReplicaConstruction {
-	#[rustfmt::skip]
 	fn deserialize(reader: &mut R) -> Res<Self> {
-		let mut bit_reader = BEBitReader::new(reader);
-		let bit = bit_reader.read_bit()?;
-		assert_eq!(bit, true);
-		let network_id = LERead::read(&mut bit_reader)?;
-		let object_id  = LERead::read(&mut bit_reader)?;
-		let lot        = LERead::read(&mut bit_reader)?;
-		let name       = LERead::read(&mut bit_reader)?;
-		let time_since_created_on_server = LERead::read(&mut bit_reader)?;
-		let config = ReplicaD::deserialize(&mut bit_reader)?;
+		read_bits(reader, Self::deserialize_bits)
+	}
+}
+
+impl ReplicaConstruction {
+	#[rustfmt::skip]
+	fn deserialize_bits<R: Read + ReplicaContext>(bit_reader: &mut BEBitReader<Counting<'_, R>>) -> Res<Self> {
+		if !bit_reader.read_bit()? {
+			return Err(Error::Malformed { type_name: "ReplicaConstruction", reason: "leading bit not set" }.into());
+		}
+		let network_id = LERead::read(bit_reader)?;
+		let object_id  = LERead::read(bit_reader)?;
+		let lot        = LERead::read(bit_reader)?;
+		let name       = LERead::read(bit_reader)?;
+		let time_since_created_on_server = LERead::read(bit_reader)?;
+		let config = ReplicaD::deserialize(bit_reader)?;
 		let is_trigger = bit_reader.read_bit()?;
-		let spawner_id        = ReplicaD::deserialize(&mut bit_reader)?;
-		let spawner_node_id   = ReplicaD::deserialize(&mut bit_reader)?;
-		let scale             = ReplicaD::deserialize(&mut bit_reader)?;
-		let world_state       = ReplicaD::deserialize(&mut bit_reader)?;
-		let gm_level          = ReplicaD::deserialize(&mut bit_reader)?;
-		let parent_child_info = ReplicaD::deserialize(&mut bit_reader)?;
+		let spawner_id        = ReplicaD::deserialize(bit_reader)?;
+		let spawner_node_id   = ReplicaD::deserialize(bit_reader)?;
+		let scale             = ReplicaD::deserialize(bit_reader)?;
+		let world_state       = ReplicaD::deserialize(bit_reader)?;
+		let gm_level          = ReplicaD::deserialize(bit_reader)?;
+		let parent_child_info = ReplicaD::deserialize(bit_reader)?;
 		let mut components = vec![];
-		for new in unsafe {bit_reader.get_mut_unchecked()}.get_comp_constructions(network_id, lot, &config) {
-			components.push(new(&mut bit_reader)?);
+		for new in unsafe {bit_reader.get_mut_unchecked()}.inner.get_comp_constructions(network_id, lot, &config) {
+			components.push(new(bit_reader).map_err(|err| crate::error::push_frame(err, "components"))?);
 		}
 
 		Ok(Self {
@@ -228,32 +311,88 @@ impl<'a, W: Write> Serialize<LE, W> for &'a ReplicaConstruction {
 	}
 }
 
-#[derive(Debug)]
+impl crate::dissect::Dissect for ReplicaConstruction {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.bit("leading_bit", true)?;
+		dissector.field("network_id", &self.network_id)?;
+		dissector.field("object_id", &self.object_id)?;
+		dissector.field("lot", &self.lot)?;
+		dissector.field("name", &self.name)?;
+		dissector.field("time_since_created_on_server", &self.time_since_created_on_server)?;
+		dissector.flagged("config", &self.config)?;
+		dissector.bit("is_trigger", self.is_trigger)?;
+		dissector.flagged("spawner_id", &self.spawner_id)?;
+		dissector.flagged("spawner_node_id", &self.spawner_node_id)?;
+		dissector.flagged("scale", &self.scale)?;
+		dissector.flagged("world_state", &self.world_state)?;
+		dissector.flagged("gm_level", &self.gm_level)?;
+		dissector.flagged("parent_child_info", &self.parent_child_info)?;
+		dissect_components(&self.components, dissector)?;
+		dissector.align();
+		Ok(())
+	}
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ReplicaSerialization {
 	pub network_id: u16,
 	pub parent_child_info: Option<ParentChildInfo>,
-	pub components: Vec<Box<dyn ComponentSerialization>>,
+	pub components: Vec<AnyComponentSerialization>,
 }
 
-impl PartialEq<ReplicaSerialization> for ReplicaSerialization {
-	fn eq(&self, rhs: &ReplicaSerialization) -> bool {
-		// hacky but i don't know a better way
-		format!("{:?}", self) == format!("{:?}", rhs)
+impl ReplicaSerialization {
+	/**
+		Builds the serialization updating a replica from state `previous` to `current`, with only the data that changed, see [`ComponentDiff`].
+
+		Returns `None` if the components of the states don't match, or if there are custom components.
+	*/
+	pub fn diff(previous: &ReplicaConstruction, current: &ReplicaConstruction) -> Option<Self> {
+		if previous.components.len() != current.components.len() {
+			return None;
+		}
+		let parent_child_info = match (&previous.parent_child_info, &current.parent_child_info) {
+			(prev, Some(cur)) if prev.as_ref() != Some(cur) => {
+				let (parent_info, child_info) = match prev {
+					Some(x) => (&x.parent_info, &x.child_info),
+					None => (&None, &None),
+				};
+				Some(ParentChildInfo { parent_info: flag_changed(parent_info, &cur.parent_info), child_info: flag_changed(child_info, &cur.child_info) })
+			}
+			_ => None,
+		};
+		let mut components = vec![];
+		for (prev, cur) in previous.components.iter().zip(&current.components) {
+			if prev.kind()? != cur.kind()? {
+				return None;
+			}
+			components.extend(AnyComponentConstruction::diff(prev, cur));
+		}
+		Some(Self { network_id: current.network_id, parent_child_info, components })
+	}
+
+	/// The data of the component of type `T`, if the serialization has it.
+	pub fn component<T: SerializationComponent>(&self) -> Option<&T> {
+		self.components.iter().find_map(AnyComponentSerialization::get)
+	}
+
+	/// The data of the component of type `T`, if the serialization has it.
+	pub fn component_mut<T: SerializationComponent>(&mut self) -> Option<&mut T> {
+		self.components.iter_mut().find_map(AnyComponentSerialization::get_mut)
 	}
 }
 
 impl<R: Read + ReplicaContext> Deserialize<LE, R> for ReplicaSerialization {
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let network_id = LERead::read(reader)?;
-		let comp_desers = reader.get_comp_serializations(network_id);
-		let mut bit_reader = BEBitReader::new(reader);
-		let parent_child_info = ReplicaD::deserialize(&mut bit_reader)?;
-		let mut components = vec![];
-		for new in comp_desers {
-			components.push(new(&mut bit_reader)?);
-		}
-
-		Ok(Self { network_id, parent_child_info, components })
+		read_bits(reader, |bit_reader| {
+			let parent_child_info = ReplicaD::deserialize(bit_reader)?;
+			let mut components = vec![];
+			for new in unsafe { bit_reader.get_mut_unchecked() }.inner.get_comp_serializations(network_id) {
+				components.push(new(bit_reader).map_err(|err| crate::error::push_frame(err, "components"))?);
+			}
+			Ok(Self { network_id, parent_child_info, components })
+		})
 	}
 }
 
@@ -272,6 +411,71 @@ impl<'a, W: Write> Serialize<LE, W> for &'a ReplicaSerialization {
 	}
 }
 
+impl crate::dissect::Dissect for ReplicaSerialization {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("network_id", &self.network_id)?;
+		dissector.flagged("parent_child_info", &self.parent_child_info)?;
+		dissect_components(&self.components, dissector)?;
+		dissector.align();
+		Ok(())
+	}
+}
+
+/// Dissects the components of a replica, with the kind of each component as its value.
+fn dissect_components<T: crate::dissect::Dissect>(components: &[T], dissector: &mut Dissector) -> Res<()> {
+	dissector.node("components", std::any::type_name::<Vec<T>>(), |dissector| {
+		for (i, comp) in components.iter().enumerate() {
+			dissector.field(&i.to_string(), comp)?;
+		}
+		Ok(())
+	})
+}
+
+/// Removes a replica from the client's view. Its network ID may be reused afterwards.
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ReplicaDestruction {
+	pub network_id: u16,
+}
+
+/// Tells the client whether a replica is in its scope. Out of scope replicas aren't serialized to the client.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ReplicaScopeChange {
+	pub network_id: u16,
+	pub in_scope: bool,
+}
+
+impl<R: Read> Deserialize<LE, R> for ReplicaScopeChange {
+	fn deserialize(reader: &mut R) -> Res<Self> {
+		let network_id = LERead::read(reader)?;
+		let mut bit_reader = BEBitReader::new(reader);
+		let in_scope = bit_reader.read_bit()?;
+		Ok(Self { network_id, in_scope })
+	}
+}
+
+impl<'a, W: Write> Serialize<LE, W> for &'a ReplicaScopeChange {
+	fn serialize(self, writer: &mut W) -> Res<()> {
+		LEWrite::write(writer, self.network_id)?;
+		let mut bit_writer = BEBitWriter::new(writer);
+		bit_writer.write_bit(self.in_scope)?;
+		bit_writer.flush()
+	}
+}
+
+impl crate::dissect::Dissect for ReplicaScopeChange {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("network_id", &self.network_id)?;
+		dissector.bit("in_scope", self.in_scope)?;
+		dissector.align();
+		Ok(())
+	}
+	fn layout() -> Layout {
+		Layout::Struct(vec![("network_id", Layout::of::<u16>()), ("in_scope", Layout::Bit), ("", Layout::Align)])
+	}
+}
+
 #[cfg(test)]
 #[derive(Debug)]
 pub(super) struct DummyContext<'a> {
@@ -287,11 +491,31 @@ impl Read for DummyContext<'_> {
 
 #[cfg(test)]
 impl ReplicaContext for DummyContext<'_> {
-	fn get_comp_constructions<R: Read>(&mut self, _network_id: u16, _lot: Lot, _config: &Option<LuNameValue>) -> Vec<fn(&mut BEBitReader<R>) -> Res<Box<dyn ComponentConstruction>>> {
+	fn get_comp_constructions<R: Read>(&mut self, _network_id: u16, _lot: Lot, _config: &Option<LuNameValue>) -> Vec<ConstructionFn<R>> {
 		vec![]
 	}
 
-	fn get_comp_serializations<R: Read>(&mut self, _network_id: u16) -> Vec<fn(&mut BEBitReader<R>) -> Res<Box<dyn ComponentSerialization>>> {
+	fn get_comp_serializations<R: Read>(&mut self, _network_id: u16) -> Vec<SerializationFn<R>> {
 		vec![]
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use endio::LERead;
+
+	use crate::Error;
+	use super::{DummyContext, ReplicaConstruction};
+
+	#[test]
+	fn test_bit_offset() {
+		// leading bit not set
+		let mut data = &[0x00, 0x00][..];
+		let err = LERead::read::<ReplicaConstruction>(&mut DummyContext { inner: &mut data }).unwrap_err();
+		assert_eq!(Error::from(err).bit_offset(), Some(1));
+		// leading bit, followed by a truncated network ID, which runs up to the end of the stream
+		let mut data = &[0x80, 0x00][..];
+		let err = LERead::read::<ReplicaConstruction>(&mut DummyContext { inner: &mut data }).unwrap_err();
+		assert_eq!(Error::from(err).bit_offset(), Some(16));
+	}
+}
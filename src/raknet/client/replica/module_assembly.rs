@@ -5,16 +5,18 @@ use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::{LuVarWString, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModuleAssemblyInfo {
 	pub assembly_id: Option<ObjId>,
 	pub use_optional_parts: bool,
 	pub blob: LuVarWString<u16>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModuleAssemblyConstruction {
 	pub module_assembly_info: Option<ModuleAssemblyInfo>,
 }
@@ -25,7 +27,8 @@ impl ComponentConstruction for ModuleAssemblyConstruction {
 	}
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModuleAssemblySerialization {}
 
 impl ComponentSerialization for ModuleAssemblySerialization {
@@ -34,6 +37,14 @@ impl ComponentSerialization for ModuleAssemblySerialization {
 	}
 }
 
+impl ComponentDiff for ModuleAssemblyConstruction {
+	type Serialization = ModuleAssemblySerialization;
+
+	fn diff(_previous: &Self, _current: &Self) -> Self::Serialization {
+		ModuleAssemblySerialization {}
+	}
+}
+
 pub struct ModuleAssemblyProtocol;
 
 impl ComponentProtocol for ModuleAssemblyProtocol {
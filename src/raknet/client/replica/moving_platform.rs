@@ -1,15 +1,17 @@
 use std::io::{Read, Result as Res, Write};
 
-use endio::{Deserialize, LE, Serialize};
+use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::LuVarWString;
+use crate::dissect::Dissector;
 use crate::world::Vector3;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization, ReplicaD};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, ReplicaD, dissect_bit_list, flag_changed, update_flagged};
 use super::simple_physics::PositionRotationInfo;
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlatformMoverInfo {
 	/// todo: bitfield
 	pub state: u32,
@@ -26,7 +28,8 @@ pub struct PlatformMoverInfo {
 	pub move_time_elapsed: f32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlatformSimpleMoverExtraInfo {
 	/// todo: bitfield
 	pub state: u32,
@@ -34,27 +37,31 @@ pub struct PlatformSimpleMoverExtraInfo {
 	pub is_in_reverse: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlatformSimpleMoverInfo {
 	pub start_point_position_rotation_info: Option<Option<PositionRotationInfo>>,
 	pub extra_info: Option<PlatformSimpleMoverExtraInfo>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PlatformSubcomponentInfo {
 	Mover(Option<PlatformMoverInfo>) = 4,
 	SimpleMover(PlatformSimpleMoverInfo) = 5,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlatformPathInfo {
 	pub path_name: LuVarWString<u16>,
 	pub starting_waypoint: u32,
 	pub is_in_reverse: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MovingPlatformConstruction {
 	pub path_info: Option<PlatformPathInfo>,
 	pub subcomponent_infos: Option<Vec<PlatformSubcomponentInfo>>,
@@ -68,7 +75,7 @@ impl<R: Read> Deserialize<LE, BEBitReader<R>> for MovingPlatformConstruction {
 		let subcomponent_infos = if has_subcomponent_infos {
 			let mut infos = vec![];
 			while reader.read_bit()? {
-				let subcomp = ReplicaD::deserialize(reader)?;
+				let subcomp = LERead::read(reader)?;
 				infos.push(subcomp);
 			}
 			Some(infos)
@@ -86,7 +93,7 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a MovingPlatformConstruct
 			if !path_info.path_name.is_empty() {
 				writer.write_bit(true)?;
 				writer.write_bit(true)?;
-				crate::raknet::client::replica::ReplicaS::serialize(path_info, writer)?;
+				LEWrite::write(writer, path_info)?;
 			} else {
 				writer.write_bit(false)?;
 			}
@@ -96,7 +103,7 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a MovingPlatformConstruct
 		if let Some(subcomponent_infos) = &self.subcomponent_infos {
 			for sci in subcomponent_infos {
 				writer.write_bit(true)?;
-				crate::raknet::client::replica::ReplicaS::serialize(sci, writer)?;
+				LEWrite::write(writer, sci)?;
 			}
 			writer.write_bit(false)?;
 		}
@@ -104,6 +111,24 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a MovingPlatformConstruct
 	}
 }
 
+impl crate::dissect::Dissect for MovingPlatformConstruction {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.bit("has_subcomponent_infos", self.subcomponent_infos.is_some())?;
+		dissector.node("path_info", std::any::type_name::<Option<PlatformPathInfo>>(), |dissector| match &self.path_info {
+			Some(path_info) if !path_info.path_name.is_empty() => {
+				dissector.bit("flag", true)?;
+				dissector.bit("is_some", true)?;
+				dissector.field("value", path_info)
+			}
+			_ => dissector.bit("flag", false),
+		})?;
+		if let Some(subcomponent_infos) = &self.subcomponent_infos {
+			dissector.node("subcomponent_infos", std::any::type_name::<Vec<PlatformSubcomponentInfo>>(), |dissector| dissect_bit_list(subcomponent_infos, dissector))?;
+		}
+		Ok(())
+	}
+}
+
 impl ComponentConstruction for MovingPlatformConstruction {
 	fn ser(&self, writer: &mut BEBitWriter<Vec<u8>>) -> Res<()> {
 		self.serialize(writer)
@@ -118,6 +143,21 @@ impl ComponentSerialization for MovingPlatformSerialization {
 	}
 }
 
+impl ApplySerialization<MovingPlatformSerialization> for MovingPlatformConstruction {
+	fn apply_serialization(&mut self, serialization: MovingPlatformSerialization) {
+		update_flagged(&mut self.path_info, serialization.path_info);
+		update_flagged(&mut self.subcomponent_infos, serialization.subcomponent_infos);
+	}
+}
+
+impl ComponentDiff for MovingPlatformConstruction {
+	type Serialization = MovingPlatformSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		MovingPlatformSerialization { path_info: flag_changed(&previous.path_info, &current.path_info), subcomponent_infos: flag_changed(&previous.subcomponent_infos, &current.subcomponent_infos) }
+	}
+}
+
 pub struct MovingPlatformProtocol;
 
 impl ComponentProtocol for MovingPlatformProtocol {
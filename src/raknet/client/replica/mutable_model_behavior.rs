@@ -1,14 +1,15 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::common::ObjId;
 use crate::world::{Vector3, Quaternion};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(i32)]
 pub enum PhysicsBehaviorType {
 	/// todo: option
@@ -19,7 +20,8 @@ pub enum PhysicsBehaviorType {
 	Dynamic,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModelBehaviorInfo {
 	pub is_pickable: bool,
 	pub physics_behavior_type: PhysicsBehaviorType,
@@ -27,20 +29,23 @@ pub struct ModelBehaviorInfo {
 	pub original_rotation: Quaternion,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModelEditingInfo {
 	pub old_object_id: ObjId,
 	pub player_editing_model: ObjId,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MutableModelBehaviorConstructionInfo {
 	pub behavior_count: u32,
 	pub is_paused: bool,
 	pub model_editing_info: Option<ModelEditingInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MutableModelBehaviorConstruction {
 	pub model_behavior_info: Option<ModelBehaviorInfo>,
 	pub mutable_model_behavior_construction_info: Option<MutableModelBehaviorConstructionInfo>,
@@ -52,13 +57,15 @@ impl ComponentConstruction for MutableModelBehaviorConstruction {
 	}
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MutableModelBehaviorSerializationInfo {
 	pub behavior_count: u32,
 	pub is_paused: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MutableModelBehaviorSerialization {
 	pub model_behavior_info: Option<ModelBehaviorInfo>,
 	pub mutable_model_behavior_serialization_info: Option<MutableModelBehaviorSerializationInfo>,
@@ -70,6 +77,32 @@ impl ComponentSerialization for MutableModelBehaviorSerialization {
 	}
 }
 
+impl ApplySerialization<MutableModelBehaviorSerialization> for MutableModelBehaviorConstruction {
+	fn apply_serialization(&mut self, serialization: MutableModelBehaviorSerialization) {
+		update_flagged(&mut self.model_behavior_info, serialization.model_behavior_info);
+		if let Some(info) = serialization.mutable_model_behavior_serialization_info {
+			match &mut self.mutable_model_behavior_construction_info {
+				Some(x) => {
+					x.behavior_count = info.behavior_count;
+					x.is_paused = info.is_paused;
+				}
+				None => {
+					self.mutable_model_behavior_construction_info = Some(MutableModelBehaviorConstructionInfo { behavior_count: info.behavior_count, is_paused: info.is_paused, model_editing_info: None });
+				}
+			}
+		}
+	}
+}
+
+impl ComponentDiff for MutableModelBehaviorConstruction {
+	type Serialization = MutableModelBehaviorSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		let info = |x: &Self| x.mutable_model_behavior_construction_info.as_ref().map(|x| MutableModelBehaviorSerializationInfo { behavior_count: x.behavior_count, is_paused: x.is_paused });
+		MutableModelBehaviorSerialization { model_behavior_info: flag_changed(&previous.model_behavior_info, &current.model_behavior_info), mutable_model_behavior_serialization_info: flag_changed(&info(previous), &info(current)) }
+	}
+}
+
 pub struct MutableModelBehaviorProtocol;
 
 impl ComponentProtocol for MutableModelBehaviorProtocol {
@@ -1,14 +1,15 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::common::{LuVarWString, ObjId};
 use crate::world::gm::client::{PetAbilityType, PetModerationStatus};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum PossessionType {
 	NoPossession,
@@ -17,14 +18,16 @@ pub enum PossessionType {
 	NotAttachedNotVisible,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TamedPetInfo {
 	pub pet_name_moderation_status: PetModerationStatus,
 	pub pet_name: LuVarWString<u8>,
 	pub owner_name: LuVarWString<u8>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetConstructionInfo {
 	/// todo: bitflag
 	pub pet_state: u32,
@@ -34,7 +37,8 @@ pub struct PetConstructionInfo {
 	pub tamed_pet_info: Option<TamedPetInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetConstruction {
 	pub pet_construction_info: Option<PetConstructionInfo>,
 }
@@ -53,6 +57,20 @@ impl ComponentSerialization for PetSerialization {
 	}
 }
 
+impl ApplySerialization<PetSerialization> for PetConstruction {
+	fn apply_serialization(&mut self, serialization: PetSerialization) {
+		update_flagged(&mut self.pet_construction_info, serialization.pet_construction_info);
+	}
+}
+
+impl ComponentDiff for PetConstruction {
+	type Serialization = PetSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		PetSerialization { pet_construction_info: flag_changed(&previous.pet_construction_info, &current.pet_construction_info) }
+	}
+}
+
 pub struct PetProtocol;
 
 impl ComponentProtocol for PetProtocol {
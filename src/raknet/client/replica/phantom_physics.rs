@@ -1,14 +1,15 @@
 use std::io::{Result as Res};
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::world::Vector3;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::simple_physics::PositionRotationInfo;
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PhysicsEffectType {
 	Push,
@@ -18,13 +19,15 @@ pub enum PhysicsEffectType {
 	Friction,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DistanceInfo {
 	pub min_distance: f32,
 	pub max_distance: f32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PhysicsEffectInfo {
 	pub effect_type: PhysicsEffectType,
 	pub amount: f32,
@@ -32,12 +35,14 @@ pub struct PhysicsEffectInfo {
 	pub impulse_velocity: Option<Vector3>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivePhysicsEffectInfo {
 	pub active_physics_effect: Option<PhysicsEffectInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PhantomPhysicsConstruction {
 	pub position_rotation_info: Option<PositionRotationInfo>,
 	pub active_physics_effect_info: Option<ActivePhysicsEffectInfo>,
@@ -57,6 +62,21 @@ impl ComponentSerialization for PhantomPhysicsSerialization {
 	}
 }
 
+impl ApplySerialization<PhantomPhysicsSerialization> for PhantomPhysicsConstruction {
+	fn apply_serialization(&mut self, serialization: PhantomPhysicsSerialization) {
+		update_flagged(&mut self.position_rotation_info, serialization.position_rotation_info);
+		update_flagged(&mut self.active_physics_effect_info, serialization.active_physics_effect_info);
+	}
+}
+
+impl ComponentDiff for PhantomPhysicsConstruction {
+	type Serialization = PhantomPhysicsSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		PhantomPhysicsSerialization { position_rotation_info: flag_changed(&previous.position_rotation_info, &current.position_rotation_info), active_physics_effect_info: flag_changed(&previous.active_physics_effect_info, &current.active_physics_effect_info) }
+	}
+}
+
 pub struct PhantomPhysicsProtocol;
 
 impl ComponentProtocol for PhantomPhysicsProtocol {
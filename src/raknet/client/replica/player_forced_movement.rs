@@ -4,15 +4,17 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ForcedMovementInfo {
 	pub player_on_rail: bool,
 	pub show_billboard: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayerForcedMovementConstruction {
 	pub forced_movement_info: Option<ForcedMovementInfo>,
 }
@@ -31,6 +33,20 @@ impl ComponentSerialization for PlayerForcedMovementSerialization {
 	}
 }
 
+impl ApplySerialization<PlayerForcedMovementSerialization> for PlayerForcedMovementConstruction {
+	fn apply_serialization(&mut self, serialization: PlayerForcedMovementSerialization) {
+		update_flagged(&mut self.forced_movement_info, serialization.forced_movement_info);
+	}
+}
+
+impl ComponentDiff for PlayerForcedMovementConstruction {
+	type Serialization = PlayerForcedMovementSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		PlayerForcedMovementSerialization { forced_movement_info: flag_changed(&previous.forced_movement_info, &current.forced_movement_info) }
+	}
+}
+
 pub struct PlayerForcedMovementProtocol;
 
 impl ComponentProtocol for PlayerForcedMovementProtocol {
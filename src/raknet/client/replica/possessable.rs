@@ -5,16 +5,18 @@ use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::ObjId;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PossessableInfo {
 	pub possessor_id: Option<ObjId>,
 	pub animation_flag: Option<u32>,
 	pub immediate_depossess: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PossessableConstruction {
 	pub possessable_info: Option<PossessableInfo>,
 }
@@ -33,6 +35,20 @@ impl ComponentSerialization for PossessableSerialization {
 	}
 }
 
+impl ApplySerialization<PossessableSerialization> for PossessableConstruction {
+	fn apply_serialization(&mut self, serialization: PossessableSerialization) {
+		update_flagged(&mut self.possessable_info, serialization.possessable_info);
+	}
+}
+
+impl ComponentDiff for PossessableConstruction {
+	type Serialization = PossessableSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		PossessableSerialization { possessable_info: flag_changed(&previous.possessable_info, &current.possessable_info) }
+	}
+}
+
 pub struct PossessableProtocol;
 
 impl ComponentProtocol for PossessableProtocol {
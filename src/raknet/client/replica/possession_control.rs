@@ -1,13 +1,14 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::common::ObjId;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum PossessionType {
 	NoPossession,
@@ -16,13 +17,15 @@ pub enum PossessionType {
 	NotAttachedNotVisible,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PossessionInfo {
 	pub possessed_id: Option<ObjId>,
 	pub possession_type: PossessionType,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PossessionControlConstruction {
 	pub possession_info: Option<PossessionInfo>,
 }
@@ -41,6 +44,20 @@ impl ComponentSerialization for PossessionControlSerialization {
 	}
 }
 
+impl ApplySerialization<PossessionControlSerialization> for PossessionControlConstruction {
+	fn apply_serialization(&mut self, serialization: PossessionControlSerialization) {
+		update_flagged(&mut self.possession_info, serialization.possession_info);
+	}
+}
+
+impl ComponentDiff for PossessionControlConstruction {
+	type Serialization = PossessionControlSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		PossessionControlSerialization { possession_info: flag_changed(&previous.possession_info, &current.possession_info) }
+	}
+}
+
 pub struct PossessionControlProtocol;
 
 impl ComponentProtocol for PossessionControlProtocol {
@@ -7,10 +7,11 @@ use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 use crate::common::LVec;
 use crate::world::Vector3;
 use crate::world::gm::client::RebuildChallengeState;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::scripted_activity::ActivityUserInfo;
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct QuickbuildConstructionInfo {
 	pub current_state: RebuildChallengeState,
 	pub show_reset_effect: bool,
@@ -22,7 +23,8 @@ pub struct QuickbuildConstructionInfo {
 	pub reposition_player: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct QuickbuildConstruction {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 	pub quickbuild_construction_info: Option<QuickbuildConstructionInfo>,
@@ -34,7 +36,8 @@ impl ComponentConstruction for QuickbuildConstruction {
 	}
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct QuickbuildSerializationInfo {
 	pub current_state: RebuildChallengeState,
 	pub show_reset_effect: bool,
@@ -43,7 +46,8 @@ pub struct QuickbuildSerializationInfo {
 	pub total_incomplete_time: f32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct QuickbuildSerialization {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 	pub quickbuild_serialization_info: Option<QuickbuildSerializationInfo>,
@@ -55,6 +59,35 @@ impl ComponentSerialization for QuickbuildSerialization {
 	}
 }
 
+impl ApplySerialization<QuickbuildSerialization> for QuickbuildConstruction {
+	fn apply_serialization(&mut self, serialization: QuickbuildSerialization) {
+		update_flagged(&mut self.activity_user_infos, serialization.activity_user_infos);
+		if let Some(info) = serialization.quickbuild_serialization_info {
+			match &mut self.quickbuild_construction_info {
+				Some(x) => {
+					x.current_state = info.current_state;
+					x.show_reset_effect = info.show_reset_effect;
+					x.has_activator = info.has_activator;
+					x.duration_timer = info.duration_timer;
+					x.total_incomplete_time = info.total_incomplete_time;
+				}
+				None => {
+					self.quickbuild_construction_info = Some(QuickbuildConstructionInfo { current_state: info.current_state, show_reset_effect: info.show_reset_effect, has_activator: info.has_activator, duration_timer: info.duration_timer, total_incomplete_time: info.total_incomplete_time, unknown: None, activator_position: Vector3::ZERO, reposition_player: false });
+				}
+			}
+		}
+	}
+}
+
+impl ComponentDiff for QuickbuildConstruction {
+	type Serialization = QuickbuildSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		let info = |x: &Self| x.quickbuild_construction_info.as_ref().map(|x| QuickbuildSerializationInfo { current_state: x.current_state.clone(), show_reset_effect: x.show_reset_effect, has_activator: x.has_activator, duration_timer: x.duration_timer, total_incomplete_time: x.total_incomplete_time });
+		QuickbuildSerialization { activity_user_infos: flag_changed(&previous.activity_user_infos, &current.activity_user_infos), quickbuild_serialization_info: flag_changed(&info(previous), &info(current)) }
+	}
+}
+
 pub struct QuickbuildProtocol;
 
 impl ComponentProtocol for QuickbuildProtocol {
@@ -1,14 +1,16 @@
 use std::io::{Read, Result as Res, Write};
 
-use endio::{Deserialize, LE, Serialize};
+use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
 use endio_bit::{BEBitReader, BEBitWriter};
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::{LuVarWString, LVec, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization, ReplicaD};
+use crate::dissect::Dissector;
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, ReplicaD, dissect_bit_list, flag_changed, update_flagged};
 use super::scripted_activity::ActivityUserInfo;
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PreRacePlayerInfo {
 	pub player_id: ObjId,
 	pub vehicle_id: ObjId,
@@ -16,26 +18,30 @@ pub struct PreRacePlayerInfo {
 	pub is_ready: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PostRacePlayerInfo {
 	pub player_id: ObjId,
 	pub current_rank: u32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RaceInfo {
 	pub lap_count: u16,
 	pub path_name: LuVarWString<u16>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DuringRacePlayerInfo {
 	pub player_id: ObjId,
 	pub best_lap_time: f32,
 	pub race_time: f32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingControlConstruction {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 	pub expected_player_count: Option<u16>,
@@ -52,7 +58,7 @@ impl<R: Read> Deserialize<LE, BEBitReader<R>> for RacingControlConstruction {
 		let pre_race_player_infos = if reader.read_bit()? {
 			let mut infos = vec![];
 			while reader.read_bit()? {
-				let info = ReplicaD::deserialize(reader)?;
+				let info = LERead::read(reader)?;
 				infos.push(info);
 			}
 			Some(infos)
@@ -62,7 +68,7 @@ impl<R: Read> Deserialize<LE, BEBitReader<R>> for RacingControlConstruction {
 		let post_race_player_infos = if reader.read_bit()? {
 			let mut infos = vec![];
 			while reader.read_bit()? {
-				let info = ReplicaD::deserialize(reader)?;
+				let info = LERead::read(reader)?;
 				infos.push(info);
 			}
 			Some(infos)
@@ -73,7 +79,7 @@ impl<R: Read> Deserialize<LE, BEBitReader<R>> for RacingControlConstruction {
 		let during_race_player_infos = if reader.read_bit()? {
 			let mut infos = vec![];
 			while reader.read_bit()? {
-				let info = ReplicaD::deserialize(reader)?;
+				let info = LERead::read(reader)?;
 				infos.push(info);
 			}
 			Some(infos)
@@ -92,7 +98,7 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a RacingControlConstructi
 		if let Some(infos) = &self.pre_race_player_infos {
 			for info in infos {
 				writer.write_bit(true)?;
-				crate::raknet::client::replica::ReplicaS::serialize(info, writer)?;
+				LEWrite::write(writer, info)?;
 			}
 			writer.write_bit(false)?;
 		}
@@ -100,7 +106,7 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a RacingControlConstructi
 		if let Some(infos) = &self.post_race_player_infos {
 			for info in infos {
 				writer.write_bit(true)?;
-				crate::raknet::client::replica::ReplicaS::serialize(info, writer)?;
+				LEWrite::write(writer, info)?;
 			}
 			writer.write_bit(false)?;
 		}
@@ -109,7 +115,7 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a RacingControlConstructi
 		if let Some(infos) = &self.during_race_player_infos {
 			for info in infos {
 				writer.write_bit(true)?;
-				crate::raknet::client::replica::ReplicaS::serialize(info, writer)?;
+				LEWrite::write(writer, info)?;
 			}
 			writer.write_bit(false)?;
 		}
@@ -117,6 +123,27 @@ impl<'a, W: Write> Serialize<LE, BEBitWriter<W>> for &'a RacingControlConstructi
 	}
 }
 
+impl crate::dissect::Dissect for RacingControlConstruction {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.flagged("activity_user_infos", &self.activity_user_infos)?;
+		dissector.flagged("expected_player_count", &self.expected_player_count)?;
+		dissect_infos("pre_race_player_infos", &self.pre_race_player_infos, dissector)?;
+		dissect_infos("post_race_player_infos", &self.post_race_player_infos, dissector)?;
+		dissector.flagged("race_info", &self.race_info)?;
+		dissect_infos("during_race_player_infos", &self.during_race_player_infos, dissector)
+	}
+}
+
+fn dissect_infos<T: crate::dissect::Dissect>(name: &str, infos: &Option<Vec<T>>, dissector: &mut Dissector) -> Res<()> {
+	dissector.node(name, std::any::type_name::<Option<Vec<T>>>(), |dissector| {
+		dissector.bit("is_some", infos.is_some())?;
+		if let Some(infos) = infos {
+			dissector.node("value", std::any::type_name::<Vec<T>>(), |dissector| dissect_bit_list(infos, dissector))?;
+		}
+		Ok(())
+	})
+}
+
 impl ComponentConstruction for RacingControlConstruction {
 	fn ser(&self, writer: &mut BEBitWriter<Vec<u8>>) -> Res<()> {
 		self.serialize(writer)
@@ -131,6 +158,25 @@ impl ComponentSerialization for RacingControlSerialization {
 	}
 }
 
+impl ApplySerialization<RacingControlSerialization> for RacingControlConstruction {
+	fn apply_serialization(&mut self, serialization: RacingControlSerialization) {
+		update_flagged(&mut self.activity_user_infos, serialization.activity_user_infos);
+		update_flagged(&mut self.expected_player_count, serialization.expected_player_count);
+		update_flagged(&mut self.pre_race_player_infos, serialization.pre_race_player_infos);
+		update_flagged(&mut self.post_race_player_infos, serialization.post_race_player_infos);
+		update_flagged(&mut self.race_info, serialization.race_info);
+		update_flagged(&mut self.during_race_player_infos, serialization.during_race_player_infos);
+	}
+}
+
+impl ComponentDiff for RacingControlConstruction {
+	type Serialization = RacingControlSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		RacingControlSerialization { activity_user_infos: flag_changed(&previous.activity_user_infos, &current.activity_user_infos), expected_player_count: flag_changed(&previous.expected_player_count, &current.expected_player_count), pre_race_player_infos: flag_changed(&previous.pre_race_player_infos, &current.pre_race_player_infos), post_race_player_infos: flag_changed(&previous.post_race_player_infos, &current.post_race_player_infos), race_info: flag_changed(&previous.race_info, &current.race_info), during_race_player_infos: flag_changed(&previous.during_race_player_infos, &current.during_race_player_infos) }
+	}
+}
+
 pub struct RacingControlProtocol;
 
 impl ComponentProtocol for RacingControlProtocol {
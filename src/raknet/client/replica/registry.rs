@@ -0,0 +1,507 @@
+/*!
+	Mapping of CDClient component IDs to component types.
+
+	[`ComponentKind`] lists the components with network data known to this crate, and [`AnyComponentConstruction`] and [`AnyComponentSerialization`] hold their decoded data. A [`ComponentRegistry`] decides which deserializers to use for which component ID, and in which order components are serialized. [`DefaultComponents`] is the registry for the components of this crate, custom registries can extend it with components of protocol extensions.
+*/
+use std::io::{Read, Result as Res, Write};
+
+use endio::{Deserialize, LE};
+use endio_bit::{BEBitReader, BEBitWriter};
+
+use crate::dissect::{Dissect, Dissector};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
+use super::achievement_vendor::{AchievementVendorConstruction, AchievementVendorProtocol, AchievementVendorSerialization};
+use super::base_combat_ai::{BaseCombatAiConstruction, BaseCombatAiProtocol, BaseCombatAiSerialization};
+use super::bbb::{BbbConstruction, BbbProtocol, BbbSerialization};
+use super::bouncer::{BouncerConstruction, BouncerProtocol, BouncerSerialization};
+use super::buff::{BuffConstruction, BuffProtocol};
+use super::character::{CharacterConstruction, CharacterProtocol, CharacterSerialization};
+use super::collectible::{CollectibleConstruction, CollectibleProtocol, CollectibleSerialization};
+use super::controllable_physics::{ControllablePhysicsConstruction, ControllablePhysicsProtocol, ControllablePhysicsSerialization};
+use super::destroyable::{DestroyableConstruction, DestroyableProtocol, DestroyableSerialization};
+use super::donation_vendor::{DonationVendorConstruction, DonationVendorProtocol, DonationVendorSerialization};
+use super::fx::{FxConstruction, FxProtocol};
+use super::inventory::{InventoryConstruction, InventoryProtocol, InventorySerialization};
+use super::item::{ItemConstruction, ItemProtocol, ItemSerialization};
+use super::level_progression::{LevelProgressionConstruction, LevelProgressionProtocol, LevelProgressionSerialization};
+use super::lup_exhibit::{LupExhibitConstruction, LupExhibitProtocol, LupExhibitSerialization};
+use super::module_assembly::{ModuleAssemblyConstruction, ModuleAssemblyProtocol};
+use super::moving_platform::{MovingPlatformConstruction, MovingPlatformProtocol, MovingPlatformSerialization};
+use super::mutable_model_behavior::{MutableModelBehaviorConstruction, MutableModelBehaviorProtocol, MutableModelBehaviorSerialization};
+use super::pet::{PetConstruction, PetProtocol, PetSerialization};
+use super::phantom_physics::{PhantomPhysicsConstruction, PhantomPhysicsProtocol, PhantomPhysicsSerialization};
+use super::player_forced_movement::{PlayerForcedMovementConstruction, PlayerForcedMovementProtocol, PlayerForcedMovementSerialization};
+use super::possessable::{PossessableConstruction, PossessableProtocol, PossessableSerialization};
+use super::possession_control::{PossessionControlConstruction, PossessionControlProtocol, PossessionControlSerialization};
+use super::quickbuild::{QuickbuildConstruction, QuickbuildProtocol, QuickbuildSerialization};
+use super::racing_control::{RacingControlConstruction, RacingControlProtocol, RacingControlSerialization};
+use super::rigid_body_phantom_physics::{RigidBodyPhantomPhysicsConstruction, RigidBodyPhantomPhysicsProtocol, RigidBodyPhantomPhysicsSerialization};
+use super::script::{ScriptConstruction, ScriptProtocol};
+use super::scripted_activity::{ScriptedActivityConstruction, ScriptedActivityProtocol, ScriptedActivitySerialization};
+use super::shooting_gallery::{ShootingGalleryConstruction, ShootingGalleryProtocol, ShootingGallerySerialization};
+use super::simple_physics::{SimplePhysicsConstruction, SimplePhysicsProtocol, SimplePhysicsSerialization};
+use super::skill::{SkillConstruction, SkillProtocol};
+use super::switch::{SwitchConstruction, SwitchProtocol, SwitchSerialization};
+use super::vehicle_physics::{VehiclePhysicsConstruction, VehiclePhysicsProtocol, VehiclePhysicsSerialization};
+use super::vendor::{VendorConstruction, VendorProtocol, VendorSerialization};
+
+/// Deserializer for the construction data of a component.
+pub type ConstructionFn<R> = fn(&mut BEBitReader<R>) -> Res<AnyComponentConstruction>;
+/// Deserializer for the serialization data of a component.
+pub type SerializationFn<R> = fn(&mut BEBitReader<R>) -> Res<AnyComponentSerialization>;
+
+/// Order in which the client serializes components, by component ID. Components not listed come last.
+pub const COMPONENT_ORDER: [u32; 35] = [108, 61, 1, 30, 20, 3, 40, 98, 7, 110, 109, 106, 4, 26, 17, 5, 9, 60, 11, 48, 25, 16, 100, 102, 19, 39, 23, 75, 42, 6, 49, 2, 44, 71, 107];
+
+/// IDs of components that exist on the client, but don't have any network data.
+pub const NO_DATA_COMPONENTS: [u32; 23] = [2, 12, 24, 27, 31, 35, 36, 43, 45, 55, 56, 57, 64, 65, 67, 68, 73, 74, 78, 95, 104, 113, 114];
+
+/// Returns a deserializer for the construction of the component described by `P`.
+pub fn construction_fn<P: ComponentProtocol, R: Read>() -> ConstructionFn<R>
+where
+	P::Construction: Deserialize<LE, BEBitReader<R>> + Into<AnyComponentConstruction>,
+{
+	|reader| Ok(P::Construction::deserialize(reader)?.into())
+}
+
+/// Returns a deserializer for the serialization of the component described by `P`.
+pub fn serialization_fn<P: ComponentProtocol, R: Read>() -> SerializationFn<R>
+where
+	P::Serialization: Deserialize<LE, BEBitReader<R>> + Into<AnyComponentSerialization>,
+{
+	|reader| Ok(P::Serialization::deserialize(reader)?.into())
+}
+
+/// A component type that can be accessed in an [`AnyComponentConstruction`].
+pub trait ConstructionComponent: ComponentConstruction + Sized {
+	const KIND: ComponentKind;
+
+	fn from_any(any: &AnyComponentConstruction) -> Option<&Self>;
+	fn from_any_mut(any: &mut AnyComponentConstruction) -> Option<&mut Self>;
+}
+
+/// A component type that can be accessed in an [`AnyComponentSerialization`].
+pub trait SerializationComponent: ComponentSerialization + Sized {
+	const KIND: ComponentKind;
+
+	fn from_any(any: &AnyComponentSerialization) -> Option<&Self>;
+	fn from_any_mut(any: &mut AnyComponentSerialization) -> Option<&mut Self>;
+}
+
+/// The network representation of a custom component, used to compare them since they can't be compared directly.
+fn custom_data(ser: impl FnOnce(&mut BEBitWriter<Vec<u8>>) -> Res<()>) -> Option<Vec<u8>> {
+	let mut writer = BEBitWriter::new(vec![]);
+	ser(&mut writer).ok()?;
+	writer.flush().ok()?;
+	Some(writer.get_ref().clone())
+}
+
+macro_rules! component_kinds {
+	(@has_ser $ser:ident) => { true };
+	(@has_ser) => { false };
+	(@ser_fn $protocol:ident $ser:ident) => { Some(serialization_fn::<$protocol, R>()) };
+	(@ser_fn $protocol:ident) => { None };
+	($($name:ident = $id:literal => $protocol:ident, $constr:ident $(, $ser:ident)?;)*) => {
+		/**
+			A component with network data, with its CDClient component ID as discriminant.
+
+			Components without network data are listed in [`NO_DATA_COMPONENTS`].
+		*/
+		#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+		#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+		#[non_exhaustive]
+		#[repr(u32)]
+		pub enum ComponentKind {
+			$($name = $id,)*
+		}
+
+		impl ComponentKind {
+			/// All component kinds, in order of ID.
+			pub const ALL: &'static [Self] = &[$(Self::$name,)*];
+
+			pub fn from_id(id: u32) -> Option<Self> {
+				match id {
+					$($id => Some(Self::$name),)*
+					_ => None,
+				}
+			}
+
+			pub fn id(self) -> u32 {
+				self as u32
+			}
+
+			/// Whether the component has data in replica serializations, in addition to its construction data.
+			pub fn has_serialization(self) -> bool {
+				match self {
+					$(Self::$name => component_kinds!(@has_ser $($ser)?),)*
+				}
+			}
+
+			/// Position of the component in the serialization order, see [`COMPONENT_ORDER`].
+			pub fn order(self) -> Option<usize> {
+				COMPONENT_ORDER.iter().position(|&x| x == self.id())
+			}
+
+			pub fn construction_fn<R: Read>(self) -> ConstructionFn<R> {
+				match self {
+					$(Self::$name => construction_fn::<$protocol, R>(),)*
+				}
+			}
+
+			/// The deserializer for the component's serialization, or `None` if it doesn't have serialization data.
+			pub fn serialization_fn<R: Read>(self) -> Option<SerializationFn<R>> {
+				match self {
+					$(Self::$name => component_kinds!(@ser_fn $protocol $($ser)?),)*
+				}
+			}
+		}
+
+		/**
+			The construction data of a component of a replica.
+
+			Components of a custom [`ComponentRegistry`] are stored as [`Custom`](Self::Custom). Since they can't be compared directly, they are equal if their serialized data is equal.
+
+			With the `serde` feature, components are represented as a map with the name of their kind as the only key, such as `{"Bouncer": {"bounce_on_collision": true}}`. Custom components can't be represented and fail to serialize.
+		*/
+		#[derive(Debug)]
+		#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+		#[non_exhaustive]
+		pub enum AnyComponentConstruction {
+			$($name($constr),)*
+			#[cfg_attr(feature = "serde", serde(skip))]
+			Custom(Box<dyn ComponentConstruction>),
+		}
+
+		impl AnyComponentConstruction {
+			/// The kind of the component, or `None` for custom components.
+			pub fn kind(&self) -> Option<ComponentKind> {
+				match self {
+					$(Self::$name(_) => Some(ComponentKind::$name),)*
+					Self::Custom(_) => None,
+				}
+			}
+
+			/// The component data, if it's of type `T`.
+			pub fn get<T: ConstructionComponent>(&self) -> Option<&T> {
+				T::from_any(self)
+			}
+
+			/// The component data, if it's of type `T`.
+			pub fn get_mut<T: ConstructionComponent>(&mut self) -> Option<&mut T> {
+				T::from_any_mut(self)
+			}
+
+			/// The serialization updating a component from `previous` to `current`, see [`ComponentDiff`]. `None` if the components are of different kinds, custom, or don't have serialization data.
+			pub fn diff(previous: &Self, current: &Self) -> Option<AnyComponentSerialization> {
+				match (previous, current) {
+					$($((Self::$name(prev), Self::$name(cur)) => Some(<AnyComponentSerialization as From<$ser>>::from(<$constr as ComponentDiff>::diff(prev, cur))),)?)*
+					_ => None,
+				}
+			}
+
+			/// Updates the component with a serialization of the same kind, see [`ApplySerialization`]. Returns the serialization if it's of a different kind, or if the component is custom.
+			pub fn apply_serialization(&mut self, serialization: AnyComponentSerialization) -> Result<(), AnyComponentSerialization> {
+				match (self, serialization) {
+					$($((Self::$name(constr), AnyComponentSerialization::$name(ser)) => <$constr as ApplySerialization<$ser>>::apply_serialization(constr, ser),)?)*
+					(_, ser) => return Err(ser),
+				}
+				Ok(())
+			}
+		}
+
+		impl PartialEq for AnyComponentConstruction {
+			fn eq(&self, rhs: &Self) -> bool {
+				match (self, rhs) {
+					$((Self::$name(a), Self::$name(b)) => a == b,)*
+					(Self::Custom(a), Self::Custom(b)) => {
+						let data = custom_data(|w| a.ser(w));
+						data.is_some() && data == custom_data(|w| b.ser(w))
+					}
+					_ => false,
+				}
+			}
+		}
+
+		impl ComponentConstruction for AnyComponentConstruction {
+			fn ser(&self, writer: &mut BEBitWriter<Vec<u8>>) -> Res<()> {
+				match self {
+					$(Self::$name(x) => <$constr as ComponentConstruction>::ser(x, writer),)*
+					Self::Custom(x) => x.ser(writer),
+				}
+			}
+		}
+
+		/// Dissected with the kind of the component as value, and custom components as a single value.
+		impl Dissect for AnyComponentConstruction {
+			fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+				match self {
+					$(Self::$name(x) => {
+						dissector.variant(stringify!($name));
+						dissector.field("0", x)
+					})*
+					Self::Custom(x) => {
+						dissector.variant("Custom");
+						dissector.node("0", std::any::type_name::<Box<dyn ComponentConstruction>>(), |dissector| dissector.encoded(x, |writer| x.ser(writer)))
+					}
+				}
+			}
+		}
+
+		$(
+			impl From<$constr> for AnyComponentConstruction {
+				fn from(comp: $constr) -> Self {
+					Self::$name(comp)
+				}
+			}
+
+			impl ConstructionComponent for $constr {
+				const KIND: ComponentKind = ComponentKind::$name;
+
+				fn from_any(any: &AnyComponentConstruction) -> Option<&Self> {
+					match any {
+						AnyComponentConstruction::$name(x) => Some(x),
+						_ => None,
+					}
+				}
+
+				fn from_any_mut(any: &mut AnyComponentConstruction) -> Option<&mut Self> {
+					match any {
+						AnyComponentConstruction::$name(x) => Some(x),
+						_ => None,
+					}
+				}
+			}
+		)*
+
+		/**
+			The serialization data of a component of a replica.
+
+			Only components that [have serialization data](ComponentKind::has_serialization) have a variant. Components of a custom [`ComponentRegistry`] are stored as [`Custom`](Self::Custom), and compared and represented with `serde` like in [`AnyComponentConstruction`].
+		*/
+		#[derive(Debug)]
+		#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+		#[non_exhaustive]
+		pub enum AnyComponentSerialization {
+			$($($name($ser),)?)*
+			#[cfg_attr(feature = "serde", serde(skip))]
+			Custom(Box<dyn ComponentSerialization>),
+		}
+
+		impl AnyComponentSerialization {
+			/// The kind of the component, or `None` for custom components.
+			pub fn kind(&self) -> Option<ComponentKind> {
+				match self {
+					$($(Self::$name(_) => Some(<$ser as SerializationComponent>::KIND),)?)*
+					Self::Custom(_) => None,
+				}
+			}
+
+			/// The component data, if it's of type `T`.
+			pub fn get<T: SerializationComponent>(&self) -> Option<&T> {
+				T::from_any(self)
+			}
+
+			/// The component data, if it's of type `T`.
+			pub fn get_mut<T: SerializationComponent>(&mut self) -> Option<&mut T> {
+				T::from_any_mut(self)
+			}
+		}
+
+		impl PartialEq for AnyComponentSerialization {
+			fn eq(&self, rhs: &Self) -> bool {
+				match (self, rhs) {
+					$($((Self::$name(a), Self::$name(b)) => <$ser as PartialEq>::eq(a, b),)?)*
+					(Self::Custom(a), Self::Custom(b)) => {
+						let data = custom_data(|w| a.ser(w));
+						data.is_some() && data == custom_data(|w| b.ser(w))
+					}
+					_ => false,
+				}
+			}
+		}
+
+		impl ComponentSerialization for AnyComponentSerialization {
+			fn ser(&self, writer: &mut BEBitWriter<Vec<u8>>) -> Res<()> {
+				match self {
+					$($(Self::$name(x) => <$ser as ComponentSerialization>::ser(x, writer),)?)*
+					Self::Custom(x) => x.ser(writer),
+				}
+			}
+		}
+
+		/// Dissected like [`AnyComponentConstruction`].
+		impl Dissect for AnyComponentSerialization {
+			fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+				match self {
+					$($(Self::$name(x) => {
+						dissector.variant(stringify!($name));
+						dissector.field::<$ser>("0", x)
+					})?)*
+					Self::Custom(x) => {
+						dissector.variant("Custom");
+						dissector.node("0", std::any::type_name::<Box<dyn ComponentSerialization>>(), |dissector| dissector.encoded(x, |writer| x.ser(writer)))
+					}
+				}
+			}
+		}
+
+		$($(
+			impl From<$ser> for AnyComponentSerialization {
+				fn from(comp: $ser) -> Self {
+					Self::$name(comp)
+				}
+			}
+
+			impl SerializationComponent for $ser {
+				const KIND: ComponentKind = ComponentKind::$name;
+
+				fn from_any(any: &AnyComponentSerialization) -> Option<&Self> {
+					match any {
+						AnyComponentSerialization::$name(x) => Some(x),
+						_ => None,
+					}
+				}
+
+				fn from_any_mut(any: &mut AnyComponentSerialization) -> Option<&mut Self> {
+					match any {
+						AnyComponentSerialization::$name(x) => Some(x),
+						_ => None,
+					}
+				}
+			}
+		)?)*
+	};
+}
+
+component_kinds! {
+	ControllablePhysics     = 1   => ControllablePhysicsProtocol, ControllablePhysicsConstruction, ControllablePhysicsSerialization;
+	SimplePhysics           = 3   => SimplePhysicsProtocol, SimplePhysicsConstruction, SimplePhysicsSerialization;
+	Character               = 4   => CharacterProtocol, CharacterConstruction, CharacterSerialization;
+	Script                  = 5   => ScriptProtocol, ScriptConstruction;
+	Bouncer                 = 6   => BouncerProtocol, BouncerConstruction, BouncerSerialization;
+	Destroyable             = 7   => DestroyableProtocol, DestroyableConstruction, DestroyableSerialization;
+	Skill                   = 9   => SkillProtocol, SkillConstruction;
+	Item                    = 11  => ItemProtocol, ItemConstruction, ItemSerialization;
+	Vendor                  = 16  => VendorProtocol, VendorConstruction, VendorSerialization;
+	Inventory               = 17  => InventoryProtocol, InventoryConstruction, InventorySerialization;
+	ShootingGallery         = 19  => ShootingGalleryProtocol, ShootingGalleryConstruction, ShootingGallerySerialization;
+	RigidBodyPhantomPhysics = 20  => RigidBodyPhantomPhysicsProtocol, RigidBodyPhantomPhysicsConstruction, RigidBodyPhantomPhysicsSerialization;
+	Collectible             = 23  => CollectibleProtocol, CollectibleConstruction, CollectibleSerialization;
+	MovingPlatform          = 25  => MovingPlatformProtocol, MovingPlatformConstruction, MovingPlatformSerialization;
+	Pet                     = 26  => PetProtocol, PetConstruction, PetSerialization;
+	VehiclePhysics          = 30  => VehiclePhysicsProtocol, VehiclePhysicsConstruction, VehiclePhysicsSerialization;
+	ScriptedActivity        = 39  => ScriptedActivityProtocol, ScriptedActivityConstruction, ScriptedActivitySerialization;
+	PhantomPhysics          = 40  => PhantomPhysicsProtocol, PhantomPhysicsConstruction, PhantomPhysicsSerialization;
+	MutableModelBehavior    = 42  => MutableModelBehaviorProtocol, MutableModelBehaviorConstruction, MutableModelBehaviorSerialization;
+	Fx                      = 44  => FxProtocol, FxConstruction;
+	Quickbuild              = 48  => QuickbuildProtocol, QuickbuildConstruction, QuickbuildSerialization;
+	Switch                  = 49  => SwitchProtocol, SwitchConstruction, SwitchSerialization;
+	BaseCombatAi            = 60  => BaseCombatAiProtocol, BaseCombatAiConstruction, BaseCombatAiSerialization;
+	ModuleAssembly          = 61  => ModuleAssemblyProtocol, ModuleAssemblyConstruction;
+	RacingControl           = 71  => RacingControlProtocol, RacingControlConstruction, RacingControlSerialization;
+	LupExhibit              = 75  => LupExhibitProtocol, LupExhibitConstruction, LupExhibitSerialization;
+	Buff                    = 98  => BuffProtocol, BuffConstruction;
+	DonationVendor          = 100 => DonationVendorProtocol, DonationVendorConstruction, DonationVendorSerialization;
+	AchievementVendor       = 102 => AchievementVendorProtocol, AchievementVendorConstruction, AchievementVendorSerialization;
+	PlayerForcedMovement    = 106 => PlayerForcedMovementProtocol, PlayerForcedMovementConstruction, PlayerForcedMovementSerialization;
+	Bbb                     = 107 => BbbProtocol, BbbConstruction, BbbSerialization;
+	Possessable             = 108 => PossessableProtocol, PossessableConstruction, PossessableSerialization;
+	LevelProgression        = 109 => LevelProgressionProtocol, LevelProgressionConstruction, LevelProgressionSerialization;
+	PossessionControl       = 110 => PossessionControlProtocol, PossessionControlConstruction, PossessionControlSerialization;
+}
+
+/**
+	Decides how components are deserialized, by component ID.
+
+	All methods default to the behavior of [`DefaultComponents`], so custom registries only need to override the methods relevant for their components, and can fall back to [`DefaultComponents`] for other IDs.
+
+	Deserializers of custom components return their data as [`AnyComponentConstruction::Custom`] and [`AnyComponentSerialization::Custom`], so to use [`construction_fn`] and [`serialization_fn`], implement `From` for the custom types accordingly.
+
+	Example of a registry adding a custom component with ID 200:
+
+	```
+	use std::io::Read;
+	use lu_packets::raknet::client::replica::registry::{construction_fn, ComponentRegistry, ConstructionFn, DefaultComponents};
+	# use lu_packets::raknet::client::replica::fx::FxProtocol as MyProtocol;
+
+	struct MyComponents;
+
+	impl ComponentRegistry for MyComponents {
+		fn is_known(&self, id: u32) -> bool {
+			id == 200 || DefaultComponents.is_known(id)
+		}
+
+		fn construction<R: Read>(&self, id: u32) -> Option<ConstructionFn<R>> {
+			match id {
+				200 => Some(construction_fn::<MyProtocol, R>()),
+				_ => DefaultComponents.construction(id),
+			}
+		}
+	}
+	```
+*/
+pub trait ComponentRegistry {
+	/// Whether the component ID is known, regardless of whether the component has network data.
+	fn is_known(&self, id: u32) -> bool {
+		ComponentKind::from_id(id).is_some() || NO_DATA_COMPONENTS.contains(&id)
+	}
+
+	/// Position of the component in the serialization order. Components without a position come last.
+	fn order(&self, id: u32) -> Option<usize> {
+		COMPONENT_ORDER.iter().position(|&x| x == id)
+	}
+
+	/// The deserializer for the component's construction, or `None` if it doesn't have network data.
+	fn construction<R: Read>(&self, id: u32) -> Option<ConstructionFn<R>> {
+		ComponentKind::from_id(id).map(ComponentKind::construction_fn)
+	}
+
+	/// The deserializer for the component's serialization, or `None` if it doesn't have serialization data.
+	fn serialization<R: Read>(&self, id: u32) -> Option<SerializationFn<R>> {
+		ComponentKind::from_id(id).and_then(ComponentKind::serialization_fn)
+	}
+}
+
+/// The registry of the components of this crate.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultComponents;
+
+impl ComponentRegistry for DefaultComponents {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_kinds() {
+		for &kind in ComponentKind::ALL {
+			assert_eq!(ComponentKind::from_id(kind.id()), Some(kind));
+			assert!(kind.order().is_some());
+			assert!(!NO_DATA_COMPONENTS.contains(&kind.id()));
+		}
+		assert_eq!(ComponentKind::from_id(2), None);
+		assert!(DefaultComponents.is_known(2));
+		assert!(!DefaultComponents.is_known(1000));
+		assert!(DefaultComponents.construction::<&[u8]>(2).is_none());
+		assert!(DefaultComponents.serialization::<&[u8]>(98).is_none());
+		assert!(DefaultComponents.serialization::<&[u8]>(7).is_some());
+	}
+
+	#[test]
+	fn test_typed_access() {
+		use super::super::bouncer::BouncerConstruction;
+		use super::super::destroyable::DestroyableConstruction;
+
+		let mut comp = AnyComponentConstruction::from(BouncerConstruction { bounce_on_collision: Some(true) });
+		assert_eq!(comp.kind(), Some(ComponentKind::Bouncer));
+		assert!(comp.get::<DestroyableConstruction>().is_none());
+		comp.get_mut::<BouncerConstruction>().unwrap().bounce_on_collision = None;
+		assert_eq!(comp.get::<BouncerConstruction>(), Some(&BouncerConstruction { bounce_on_collision: None }));
+
+		let ser = AnyComponentSerialization::from(BouncerConstruction { bounce_on_collision: None });
+		assert_eq!(ser.kind(), Some(ComponentKind::Bouncer));
+		let custom = AnyComponentConstruction::Custom(Box::new(BouncerConstruction { bounce_on_collision: None }));
+		assert_eq!(custom, AnyComponentConstruction::Custom(Box::new(BouncerConstruction { bounce_on_collision: None })));
+		assert_ne!(custom, comp);
+	}
+}
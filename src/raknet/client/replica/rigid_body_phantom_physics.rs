@@ -4,10 +4,11 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::simple_physics::PositionRotationInfo;
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RigidBodyPhantomPhysicsConstruction {
 	pub position_rotation_info: Option<PositionRotationInfo>,
 }
@@ -26,6 +27,20 @@ impl ComponentSerialization for RigidBodyPhantomPhysicsSerialization {
 	}
 }
 
+impl ApplySerialization<RigidBodyPhantomPhysicsSerialization> for RigidBodyPhantomPhysicsConstruction {
+	fn apply_serialization(&mut self, serialization: RigidBodyPhantomPhysicsSerialization) {
+		update_flagged(&mut self.position_rotation_info, serialization.position_rotation_info);
+	}
+}
+
+impl ComponentDiff for RigidBodyPhantomPhysicsConstruction {
+	type Serialization = RigidBodyPhantomPhysicsSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		RigidBodyPhantomPhysicsSerialization { position_rotation_info: flag_changed(&previous.position_rotation_info, &current.position_rotation_info) }
+	}
+}
+
 pub struct RigidBodyPhantomPhysicsProtocol;
 
 impl ComponentProtocol for RigidBodyPhantomPhysicsProtocol {
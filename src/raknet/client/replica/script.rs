@@ -5,14 +5,16 @@ use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::world::LuNameValue;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ScriptConstruction {
 	pub network_vars: Option<LuNameValue>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ScriptSerialization {}
 
 impl ComponentConstruction for ScriptConstruction {
@@ -27,6 +29,14 @@ impl ComponentSerialization for ScriptSerialization {
 	}
 }
 
+impl ComponentDiff for ScriptConstruction {
+	type Serialization = ScriptSerialization;
+
+	fn diff(_previous: &Self, _current: &Self) -> Self::Serialization {
+		ScriptSerialization {}
+	}
+}
+
 pub struct ScriptProtocol;
 
 impl ComponentProtocol for ScriptProtocol {
@@ -4,10 +4,11 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use crate::common::{LVec, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::common::{LVec, LVecElement, ObjId};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivityUserInfo {
 	pub user_object_id: ObjId,
 	// todo[min_const_generics]
@@ -24,7 +25,10 @@ pub struct ActivityUserInfo {
 	pub activity_value_9: f32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for ActivityUserInfo {}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ScriptedActivityConstruction {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 }
@@ -43,6 +47,20 @@ impl ComponentSerialization for ScriptedActivitySerialization {
 	}
 }
 
+impl ApplySerialization<ScriptedActivitySerialization> for ScriptedActivityConstruction {
+	fn apply_serialization(&mut self, serialization: ScriptedActivitySerialization) {
+		update_flagged(&mut self.activity_user_infos, serialization.activity_user_infos);
+	}
+}
+
+impl ComponentDiff for ScriptedActivityConstruction {
+	type Serialization = ScriptedActivitySerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		ScriptedActivitySerialization { activity_user_infos: flag_changed(&previous.activity_user_infos, &current.activity_user_infos) }
+	}
+}
+
 pub struct ScriptedActivityProtocol;
 
 impl ComponentProtocol for ScriptedActivityProtocol {
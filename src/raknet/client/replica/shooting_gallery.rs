@@ -6,10 +6,11 @@ use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
 use crate::common::{LVec, ObjId};
 use crate::world::Vector3;
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::scripted_activity::ActivityUserInfo;
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShootingGalleryInfo {
 	pub velocity: f64,
 	pub cooldown: f64,
@@ -22,7 +23,8 @@ pub struct ShootingGalleryInfo {
 	pub camera_fov: f32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShootingGalleryConstruction {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 	pub camera_position: Vector3,
@@ -36,7 +38,8 @@ impl ComponentConstruction for ShootingGalleryConstruction {
 	}
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShootingGallerySerialization {
 	pub activity_user_infos: Option<LVec<u32, ActivityUserInfo>>,
 	pub shooting_gallery_info: Option<ShootingGalleryInfo>,
@@ -48,6 +51,21 @@ impl ComponentSerialization for ShootingGallerySerialization {
 	}
 }
 
+impl ApplySerialization<ShootingGallerySerialization> for ShootingGalleryConstruction {
+	fn apply_serialization(&mut self, serialization: ShootingGallerySerialization) {
+		update_flagged(&mut self.activity_user_infos, serialization.activity_user_infos);
+		update_flagged(&mut self.shooting_gallery_info, serialization.shooting_gallery_info);
+	}
+}
+
+impl ComponentDiff for ShootingGalleryConstruction {
+	type Serialization = ShootingGallerySerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		ShootingGallerySerialization { activity_user_infos: flag_changed(&previous.activity_user_infos, &current.activity_user_infos), shooting_gallery_info: flag_changed(&previous.shooting_gallery_info, &current.shooting_gallery_info) }
+	}
+}
+
 pub struct ShootingGalleryProtocol;
 
 impl ComponentProtocol for ShootingGalleryProtocol {
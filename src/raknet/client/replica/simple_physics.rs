@@ -1,13 +1,14 @@
 use std::io::Result as Res;
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::world::{Vector3, Quaternion};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum ClimbingProperty {
 	None,
@@ -16,13 +17,15 @@ pub enum ClimbingProperty {
 	ClimbWallStick,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VelocityInfo {
 	pub linear_velocity: Vector3,
 	pub angular_velocity: Vector3,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum MotionType {
 	Dynamic = 1,
@@ -33,13 +36,15 @@ pub enum MotionType {
 	ThinBoxInertia,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PositionRotationInfo {
 	pub position: Vector3,
 	pub rotation: Quaternion,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SimplePhysicsConstruction {
 	pub is_climbable: bool,
 	pub climbing_property: ClimbingProperty,
@@ -48,7 +53,8 @@ pub struct SimplePhysicsConstruction {
 	pub position_rotation_info: Option<PositionRotationInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SimplePhysicsSerialization {
 	pub velocity_info: Option<VelocityInfo>,
 	pub motion_type: Option<MotionType>,
@@ -67,6 +73,22 @@ impl ComponentSerialization for SimplePhysicsSerialization {
 	}
 }
 
+impl ApplySerialization<SimplePhysicsSerialization> for SimplePhysicsConstruction {
+	fn apply_serialization(&mut self, serialization: SimplePhysicsSerialization) {
+		update_flagged(&mut self.velocity_info, serialization.velocity_info);
+		update_flagged(&mut self.motion_type, serialization.motion_type);
+		update_flagged(&mut self.position_rotation_info, serialization.position_rotation_info);
+	}
+}
+
+impl ComponentDiff for SimplePhysicsConstruction {
+	type Serialization = SimplePhysicsSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		SimplePhysicsSerialization { velocity_info: flag_changed(&previous.velocity_info, &current.velocity_info), motion_type: flag_changed(&previous.motion_type, &current.motion_type), position_rotation_info: flag_changed(&previous.position_rotation_info, &current.position_rotation_info) }
+	}
+}
+
 pub struct SimplePhysicsProtocol;
 
 impl ComponentProtocol for SimplePhysicsProtocol {
@@ -4,10 +4,11 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use crate::common::{LVec, ObjId};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use crate::common::{LVec, LVecElement, ObjId};
+use super::{ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BehaviorInfo {
 	pub unknown_1: u32,
 	pub action: u32, // todo: type
@@ -22,7 +23,10 @@ pub struct BehaviorInfo {
 	pub imagination_cost: u32,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for BehaviorInfo {}
+
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SkillInfo {
 	pub unknown_1: u32,
 	pub skill_id: u32,    // todo: type
@@ -31,12 +35,16 @@ pub struct SkillInfo {
 	pub behaviors: LVec<u32, BehaviorInfo>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+impl LVecElement for SkillInfo {}
+
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SkillConstruction {
 	pub skills_in_progress: Option<LVec<u32, SkillInfo>>,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SkillSerialization {}
 
 impl ComponentConstruction for SkillConstruction {
@@ -51,6 +59,14 @@ impl ComponentSerialization for SkillSerialization {
 	}
 }
 
+impl ComponentDiff for SkillConstruction {
+	type Serialization = SkillSerialization;
+
+	fn diff(_previous: &Self, _current: &Self) -> Self::Serialization {
+		SkillSerialization {}
+	}
+}
+
 pub struct SkillProtocol;
 
 impl ComponentProtocol for SkillProtocol {
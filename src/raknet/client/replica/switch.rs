@@ -4,9 +4,10 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization};
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SwitchConstruction {
 	pub is_active: bool,
 }
@@ -25,6 +26,20 @@ impl ComponentSerialization for SwitchSerialization {
 	}
 }
 
+impl ApplySerialization<SwitchSerialization> for SwitchConstruction {
+	fn apply_serialization(&mut self, serialization: SwitchSerialization) {
+		self.is_active = serialization.is_active;
+	}
+}
+
+impl ComponentDiff for SwitchConstruction {
+	type Serialization = SwitchSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		SwitchSerialization { is_active: current.is_active }
+	}
+}
+
 pub struct SwitchProtocol;
 
 impl ComponentProtocol for SwitchProtocol {
@@ -1,7 +1,7 @@
 ScriptConstruction {
 	network_vars: Some(
 		lnv! {
-			"points": 100i64,
+			"points": 100u64,
 		},
 	),
 }
@@ -1,14 +1,15 @@
 use std::io::{Result as Res};
 
-use endio::{Deserialize, Serialize};
+use endio::Serialize;
 use endio_bit::BEBitWriter;
-use lu_packets_derive::{BitVariantTests, ReplicaSerde};
+use lu_packets_derive::{BitVariantTests, Dissect, EnumDeserialize, ReplicaSerde};
 
 use crate::world::{Vector3, Quaternion};
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 use super::controllable_physics::{LocalSpaceInfo};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum EndOfRaceBehaviorType {
 	DriveStraight,
@@ -21,7 +22,8 @@ pub enum EndOfRaceBehaviorType {
 	Jump,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RemoteInputInfo {
 	pub remote_input_x: f32,
 	pub remote_input_y: f32,
@@ -29,7 +31,8 @@ pub struct RemoteInputInfo {
 	pub is_modified: bool,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleFrameStats {
 	pub position: Vector3,
 	pub rotation: Quaternion,
@@ -42,7 +45,8 @@ pub struct VehicleFrameStats {
 	pub remote_input_ping: f32,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehiclePhysicsConstruction {
 	pub vehicle_frame_stats: Option<VehicleFrameStats>,
 	pub end_of_race_behavior_type: EndOfRaceBehaviorType,
@@ -50,13 +54,15 @@ pub struct VehiclePhysicsConstruction {
 	pub wheel_lock_extra_friction: Option<bool>,
 }
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleFrameStatsTeleportInfo {
 	pub vehicle_frame_stats: VehicleFrameStats,
 	pub is_teleporting: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehiclePhysicsSerialization {
 	pub vehicle_frame_stats_teleport_info: Option<VehicleFrameStatsTeleportInfo>,
 	pub wheel_lock_extra_friction: Option<bool>,
@@ -74,6 +80,24 @@ impl ComponentSerialization for VehiclePhysicsSerialization {
 	}
 }
 
+impl ApplySerialization<VehiclePhysicsSerialization> for VehiclePhysicsConstruction {
+	fn apply_serialization(&mut self, serialization: VehiclePhysicsSerialization) {
+		if let Some(info) = serialization.vehicle_frame_stats_teleport_info {
+			self.vehicle_frame_stats = Some(info.vehicle_frame_stats);
+		}
+		update_flagged(&mut self.wheel_lock_extra_friction, serialization.wheel_lock_extra_friction);
+	}
+}
+
+impl ComponentDiff for VehiclePhysicsConstruction {
+	type Serialization = VehiclePhysicsSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		let vehicle_frame_stats = flag_changed(&previous.vehicle_frame_stats, &current.vehicle_frame_stats);
+		VehiclePhysicsSerialization { vehicle_frame_stats_teleport_info: vehicle_frame_stats.map(|vehicle_frame_stats| VehicleFrameStatsTeleportInfo { vehicle_frame_stats, is_teleporting: false }), wheel_lock_extra_friction: flag_changed(&previous.wheel_lock_extra_friction, &current.wheel_lock_extra_friction) }
+	}
+}
+
 pub struct VehiclePhysicsProtocol;
 
 impl ComponentProtocol for VehiclePhysicsProtocol {
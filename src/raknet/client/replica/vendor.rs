@@ -4,15 +4,17 @@ use endio::Serialize;
 use endio_bit::BEBitWriter;
 use lu_packets_derive::{BitVariantTests, ReplicaSerde};
 
-use super::{ComponentConstruction, ComponentProtocol, ComponentSerialization};
+use super::{ApplySerialization, ComponentConstruction, ComponentDiff, ComponentProtocol, ComponentSerialization, flag_changed, update_flagged};
 
-#[derive(Debug, PartialEq, ReplicaSerde)]
+#[derive(Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VendorInfo {
 	pub has_standard_items: bool,
 	pub has_multicost_items: bool,
 }
 
-#[derive(BitVariantTests, Debug, PartialEq, ReplicaSerde)]
+#[derive(BitVariantTests, Clone, Debug, PartialEq, ReplicaSerde)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VendorConstruction {
 	pub vendor_info: Option<VendorInfo>,
 }
@@ -31,6 +33,20 @@ impl ComponentSerialization for VendorSerialization {
 	}
 }
 
+impl ApplySerialization<VendorSerialization> for VendorConstruction {
+	fn apply_serialization(&mut self, serialization: VendorSerialization) {
+		update_flagged(&mut self.vendor_info, serialization.vendor_info);
+	}
+}
+
+impl ComponentDiff for VendorConstruction {
+	type Serialization = VendorSerialization;
+
+	fn diff(previous: &Self, current: &Self) -> Self::Serialization {
+		VendorSerialization { vendor_info: flag_changed(&previous.vendor_info, &current.vendor_info) }
+	}
+}
+
 pub struct VendorProtocol;
 
 impl ComponentProtocol for VendorProtocol {
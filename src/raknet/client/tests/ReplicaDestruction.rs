@@ -0,0 +1,6 @@
+// todo: replace the bin with a captured packet, it is built from the layout raknet's ReplicaManager writes
+Message::ReplicaDestruction(
+	ReplicaDestruction {
+		network_id: 11,
+	}
+)
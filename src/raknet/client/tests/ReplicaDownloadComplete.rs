@@ -0,0 +1,2 @@
+// todo: replace the bin with a captured packet, it is built from the layout raknet's ReplicaManager writes
+Message::ReplicaDownloadComplete
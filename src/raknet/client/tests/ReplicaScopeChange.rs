@@ -0,0 +1,7 @@
+// todo: replace the bin with a captured packet, it is built from the layout raknet's ReplicaManager writes
+Message::ReplicaScopeChange(
+	ReplicaScopeChange {
+		network_id: 11,
+		in_scope: true,
+	}
+)
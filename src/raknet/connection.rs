@@ -0,0 +1,420 @@
+/*!
+	Connection handshake, pings and disconnection.
+
+	A [`Connection`] drives a [`ReliabilityLayer`] through the RakNet connection lifecycle: the unconnected open connection request and reply, [`ConnectionRequest`] with password check, [`ConnectionRequestAccepted`] and [`NewIncomingConnection`], then [`InternalPing`]/[`ConnectedPong`] latency measurement, timeout detection, and [`DisconnectionNotification`](crate::raknet::server::Message::DisconnectionNotification).
+
+	Like the transport, connections are sans-IO and take timestamps as arguments, so they can be driven deterministically. Payloads that aren't part of the connection management are passed through as [`Event::Payload`], to be decoded as [`raknet::server::Message`](crate::raknet::server::Message) or [`raknet::client::Message`](crate::raknet::client::Message).
+*/
+use std::collections::VecDeque;
+use std::io::Result as Res;
+
+use endio::{LERead, LEWrite};
+
+use super::client::{ConnectedPong, ConnectionRequestAccepted};
+use super::server::{ConnectionRequest, InternalPing, NewIncomingConnection};
+use super::transport::{Reliability, ReliabilityLayer, DEFAULT_MTU};
+use super::SystemAddress;
+
+const ID_INTERNAL_PING: u8 = 0;
+const ID_CONNECTED_PONG: u8 = 3;
+const ID_CONNECTION_REQUEST: u8 = 4;
+const ID_OPEN_CONNECTION_REQUEST: u8 = 9;
+const ID_OPEN_CONNECTION_REPLY: u8 = 10;
+const ID_CONNECTION_REQUEST_ACCEPTED: u8 = 14;
+const ID_NEW_INCOMING_CONNECTION: u8 = 17;
+const ID_DISCONNECTION_NOTIFICATION: u8 = 19;
+const ID_INVALID_PASSWORD: u8 = 23;
+
+/// Which side of the handshake a connection is on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+	Client,
+	Server,
+}
+
+/// Lifecycle state of a [`Connection`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum State {
+	/// Client: not yet started. Server: waiting for the open connection request.
+	Unconnected,
+	/// Client: open connection request sent, waiting for the reply.
+	OpenConnectionRequested,
+	/// Client: connection request sent, waiting for it to be accepted. Server: waiting for the connection request.
+	ConnectionRequested,
+	/// Server: connection request accepted, waiting for the new incoming connection confirmation.
+	Accepted,
+	Connected,
+	/// Disconnection notification sent, waiting for it to be acknowledged.
+	Disconnecting,
+	Disconnected,
+}
+
+/// Why a connection ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisconnectReason {
+	/// [`Connection::disconnect`] was called.
+	Local,
+	/// The peer sent a disconnection notification.
+	Remote,
+	/// Nothing was received from the peer for longer than the timeout.
+	Timeout,
+	/// The server rejected the password.
+	InvalidPassword,
+}
+
+/// Something that happened on a [`Connection`].
+#[derive(Debug, PartialEq)]
+pub enum Event {
+	/// The handshake completed.
+	Connected,
+	/// A payload for the application, starting with its RakNet message ID.
+	Payload(Vec<u8>),
+	/// A pong was received, with the measured round trip time in milliseconds.
+	Latency(u32),
+	/// The connection ended. No more events follow.
+	Disconnected(DisconnectReason),
+}
+
+/// Timing configuration of a [`Connection`], in milliseconds.
+#[derive(Clone, Debug)]
+pub struct ConnectionConfig {
+	/// Maximum transmission unit, at least [`MIN_MTU`](crate::raknet::transport::MIN_MTU).
+	pub mtu: usize,
+	/// Disconnect if nothing was received for this long.
+	pub timeout: u32,
+	/// Interval between pings sent by the client.
+	pub ping_interval: u32,
+	/// Interval between retries of the open connection request.
+	pub open_connection_retry_interval: u32,
+}
+
+impl Default for ConnectionConfig {
+	fn default() -> Self {
+		Self { mtu: DEFAULT_MTU, timeout: 10000, ping_interval: 5000, open_connection_retry_interval: 1000 }
+	}
+}
+
+/// A RakNet connection to a single peer.
+#[derive(Debug)]
+pub struct Connection {
+	role: Role,
+	state: State,
+	config: ConnectionConfig,
+	password: Box<[u8]>,
+	local_addr: SystemAddress,
+	peer_addr: SystemAddress,
+	layer: ReliabilityLayer,
+	unconnected_out: Vec<Vec<u8>>,
+	events: VecDeque<Event>,
+	disconnect_reason: DisconnectReason,
+	last_receive: u32,
+	last_send_attempt: u32,
+	latency: Option<u32>,
+}
+
+impl Connection {
+	fn new(role: Role, password: &[u8], local_addr: SystemAddress, peer_addr: SystemAddress, config: ConnectionConfig, now: u32) -> Self {
+		Self { role, state: State::Unconnected, layer: ReliabilityLayer::new(config.mtu), config, password: password.into(), local_addr, peer_addr, unconnected_out: vec![], events: VecDeque::new(), disconnect_reason: DisconnectReason::Local, last_receive: now, last_send_attempt: now, latency: None }
+	}
+
+	/// Creates the client side of a connection. Call [`connect`](Self::connect) to start the handshake.
+	pub fn client(password: &[u8], local_addr: SystemAddress, peer_addr: SystemAddress, config: ConnectionConfig, now: u32) -> Self {
+		Self::new(Role::Client, password, local_addr, peer_addr, config, now)
+	}
+
+	/// Creates the server side of a connection, for a peer that has sent its first datagram. The password is the one clients need to provide.
+	pub fn server(password: &[u8], local_addr: SystemAddress, peer_addr: SystemAddress, config: ConnectionConfig, now: u32) -> Self {
+		Self::new(Role::Server, password, local_addr, peer_addr, config, now)
+	}
+
+	pub fn role(&self) -> Role {
+		self.role
+	}
+
+	pub fn state(&self) -> State {
+		self.state
+	}
+
+	pub fn peer_addr(&self) -> &SystemAddress {
+		&self.peer_addr
+	}
+
+	/// Round trip time in milliseconds, measured with pings if available, otherwise with acknowledgements.
+	pub fn latency(&self) -> Option<u32> {
+		self.latency.or_else(|| self.layer.rtt())
+	}
+
+	/// Starts the handshake on the client side.
+	pub fn connect(&mut self, now: u32) {
+		if self.role == Role::Client && self.state == State::Unconnected {
+			self.state = State::OpenConnectionRequested;
+			self.last_send_attempt = now;
+			self.unconnected_out.push(vec![ID_OPEN_CONNECTION_REQUEST, 0]);
+		}
+	}
+
+	/// Notifies the peer and ends the connection once the notification has been acknowledged.
+	pub fn disconnect(&mut self, now: u32) {
+		match self.state {
+			State::Disconnecting | State::Disconnected => {}
+			State::Unconnected | State::OpenConnectionRequested => self.close(DisconnectReason::Local),
+			_ => {
+				self.layer.send(vec![ID_DISCONNECTION_NOTIFICATION], Reliability::ReliableOrdered, 0);
+				self.state = State::Disconnecting;
+				self.last_send_attempt = now;
+			}
+		}
+	}
+
+	/// Queues a payload starting with its RakNet message ID. Ignored unless connected.
+	pub fn send(&mut self, payload: Vec<u8>, reliability: Reliability, ordering_channel: u8) {
+		if self.state == State::Connected {
+			self.layer.send(payload, reliability, ordering_channel);
+		}
+	}
+
+	/// Serializes and queues a message, such as a [`raknet::client::Message`](crate::raknet::client::Message) on the server side.
+	pub fn send_message<M>(&mut self, message: &M, reliability: Reliability, ordering_channel: u8) -> Res<()>
+	where
+		for<'a> &'a M: endio::Serialize<endio::LE, Vec<u8>>,
+	{
+		let mut payload = vec![];
+		LEWrite::write(&mut payload, message)?;
+		self.send(payload, reliability, ordering_channel);
+		Ok(())
+	}
+
+	/// Returns the next event, if any.
+	pub fn poll_event(&mut self) -> Option<Event> {
+		self.events.pop_front()
+	}
+
+	fn close(&mut self, reason: DisconnectReason) {
+		if self.state != State::Disconnected {
+			self.state = State::Disconnected;
+			self.events.push_back(Event::Disconnected(reason));
+		}
+	}
+
+	fn send_control<M>(&mut self, id: u8, message: &M) -> Res<()>
+	where
+		for<'a> &'a M: endio::Serialize<endio::LE, Vec<u8>>,
+	{
+		let mut payload = vec![id];
+		LEWrite::write(&mut payload, message)?;
+		self.layer.send(payload, Reliability::ReliableOrdered, 0);
+		Ok(())
+	}
+
+	/// Processes a datagram received from the peer.
+	pub fn handle_datagram(&mut self, data: &[u8], now: u32) -> Res<()> {
+		if self.state == State::Disconnected {
+			return Ok(());
+		}
+		self.last_receive = now;
+		// unconnected messages are two bytes long, which is too short for a connected datagram
+		if data.len() == 2 {
+			match (self.role, self.state, data[0]) {
+				(Role::Server, State::Unconnected, ID_OPEN_CONNECTION_REQUEST) | (Role::Server, State::ConnectionRequested, ID_OPEN_CONNECTION_REQUEST) => {
+					self.unconnected_out.push(vec![ID_OPEN_CONNECTION_REPLY, 0]);
+					self.state = State::ConnectionRequested;
+				}
+				(Role::Client, State::OpenConnectionRequested, ID_OPEN_CONNECTION_REPLY) => {
+					let password = self.password.clone();
+					self.send_control(ID_CONNECTION_REQUEST, &ConnectionRequest { password })?;
+					self.state = State::ConnectionRequested;
+				}
+				_ => {}
+			}
+			return Ok(());
+		}
+		if self.state == State::Unconnected || self.state == State::OpenConnectionRequested {
+			return Ok(());
+		}
+		self.layer.handle_datagram(data, now)?;
+		while let Some(payload) = self.layer.receive() {
+			self.handle_payload(payload, now)?;
+		}
+		Ok(())
+	}
+
+	fn handle_payload(&mut self, payload: Vec<u8>, now: u32) -> Res<()> {
+		let id = match payload.first() {
+			Some(x) => *x,
+			None => return Ok(()),
+		};
+		let reader = &mut &payload[1..];
+		match (self.role, id) {
+			(_, ID_INTERNAL_PING) => {
+				let ping: InternalPing = LERead::read(reader)?;
+				self.send_control(ID_CONNECTED_PONG, &ConnectedPong { ping_send_time: ping.send_time })?;
+			}
+			(_, ID_CONNECTED_PONG) => {
+				let pong: ConnectedPong = LERead::read(reader)?;
+				let latency = now.wrapping_sub(pong.ping_send_time);
+				self.latency = Some(latency);
+				self.events.push_back(Event::Latency(latency));
+			}
+			(_, ID_DISCONNECTION_NOTIFICATION) => self.close(DisconnectReason::Remote),
+			(Role::Server, ID_CONNECTION_REQUEST) => {
+				if self.state != State::ConnectionRequested {
+					return Ok(());
+				}
+				let request: ConnectionRequest = LERead::read(reader)?;
+				if request.password != self.password {
+					self.layer.send(vec![ID_INVALID_PASSWORD], Reliability::ReliableOrdered, 0);
+					self.state = State::Disconnecting;
+					self.disconnect_reason = DisconnectReason::InvalidPassword;
+					return Ok(());
+				}
+				let accepted = ConnectionRequestAccepted { peer_addr: self.peer_addr.clone(), local_addr: self.local_addr.clone() };
+				self.send_control(ID_CONNECTION_REQUEST_ACCEPTED, &accepted)?;
+				self.state = State::Accepted;
+			}
+			(Role::Server, ID_NEW_INCOMING_CONNECTION) => {
+				if self.state != State::Accepted {
+					return Ok(());
+				}
+				let _: NewIncomingConnection = LERead::read(reader)?;
+				self.state = State::Connected;
+				self.events.push_back(Event::Connected);
+			}
+			(Role::Client, ID_CONNECTION_REQUEST_ACCEPTED) => {
+				if self.state != State::ConnectionRequested {
+					return Ok(());
+				}
+				let accepted: ConnectionRequestAccepted = LERead::read(reader)?;
+				let confirmation = NewIncomingConnection { peer_addr: self.peer_addr.clone(), local_addr: accepted.peer_addr };
+				self.send_control(ID_NEW_INCOMING_CONNECTION, &confirmation)?;
+				self.state = State::Connected;
+				self.events.push_back(Event::Connected);
+				self.send_ping(now)?;
+			}
+			(Role::Client, ID_INVALID_PASSWORD) => self.close(DisconnectReason::InvalidPassword),
+			_ => {
+				if self.state == State::Connected {
+					self.events.push_back(Event::Payload(payload));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn send_ping(&mut self, now: u32) -> Res<()> {
+		self.last_send_attempt = now;
+		let ping = InternalPing { send_time: now };
+		let mut payload = vec![ID_INTERNAL_PING];
+		LEWrite::write(&mut payload, &ping)?;
+		self.layer.send(payload, Reliability::Unreliable, 0);
+		Ok(())
+	}
+
+	/**
+		Advances timers and returns the datagrams that should be sent to the peer now.
+
+		Call this regularly, e.g. every few ten milliseconds, even if nothing was queued.
+	*/
+	pub fn poll_transmit(&mut self, now: u32) -> Res<Vec<Vec<u8>>> {
+		if self.state == State::Disconnected {
+			// final flush, so that the peer receives acknowledgements, e.g. for its disconnection notification
+			let datagrams = self.layer.poll_transmit(now)?;
+			self.layer = ReliabilityLayer::new(self.config.mtu);
+			return Ok(datagrams);
+		}
+		if now.wrapping_sub(self.last_receive) > self.config.timeout {
+			// don't wait for acknowledgement of the disconnection notification forever
+			let reason = if self.state == State::Disconnecting { self.disconnect_reason } else { DisconnectReason::Timeout };
+			self.close(reason);
+			return Ok(vec![]);
+		}
+		match self.state {
+			State::OpenConnectionRequested => {
+				if now.wrapping_sub(self.last_send_attempt) >= self.config.open_connection_retry_interval {
+					self.last_send_attempt = now;
+					self.unconnected_out.push(vec![ID_OPEN_CONNECTION_REQUEST, 0]);
+				}
+			}
+			State::Connected => {
+				if self.role == Role::Client && now.wrapping_sub(self.last_send_attempt) >= self.config.ping_interval {
+					self.send_ping(now)?;
+				}
+			}
+			_ => {}
+		}
+		let mut datagrams = std::mem::take(&mut self.unconnected_out);
+		datagrams.extend(self.layer.poll_transmit(now)?);
+		if self.state == State::Disconnecting && !self.layer.has_pending() {
+			self.close(self.disconnect_reason);
+		}
+		Ok(datagrams)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::net::Ipv4Addr;
+
+	use super::*;
+
+	fn addr(port: u16) -> SystemAddress {
+		SystemAddress { ip: Ipv4Addr::LOCALHOST, port }
+	}
+
+	fn transfer(from: &mut Connection, to: &mut Connection, now: u32) {
+		for datagram in from.poll_transmit(now).unwrap() {
+			to.handle_datagram(&datagram, now).unwrap();
+		}
+	}
+
+	fn handshake(client_password: &[u8]) -> (Connection, Connection) {
+		let mut client = Connection::client(client_password, addr(1), addr(2), ConnectionConfig::default(), 0);
+		let mut server = Connection::server(b"3.25 ND1", addr(2), addr(1), ConnectionConfig::default(), 0);
+		client.connect(0);
+		for now in 0..4 {
+			transfer(&mut client, &mut server, now * 10);
+			transfer(&mut server, &mut client, now * 10 + 5);
+		}
+		(client, server)
+	}
+
+	#[test]
+	fn test_handshake() {
+		let (mut client, mut server) = handshake(b"3.25 ND1");
+		assert_eq!(client.state(), State::Connected);
+		assert_eq!(server.state(), State::Connected);
+		assert_eq!(client.poll_event(), Some(Event::Connected));
+		assert_eq!(server.poll_event(), Some(Event::Connected));
+		assert_eq!(client.poll_event(), Some(Event::Latency(10)));
+
+		client.send(vec![0x53, 4, 0], Reliability::ReliableOrdered, 0);
+		transfer(&mut client, &mut server, 50);
+		assert_eq!(server.poll_event(), Some(Event::Payload(vec![0x53, 4, 0])));
+
+		client.disconnect(60);
+		transfer(&mut client, &mut server, 60);
+		assert_eq!(server.poll_event(), Some(Event::Disconnected(DisconnectReason::Remote)));
+		transfer(&mut server, &mut client, 70);
+		client.poll_transmit(80).unwrap();
+		assert_eq!(client.poll_event(), Some(Event::Disconnected(DisconnectReason::Local)));
+	}
+
+	#[test]
+	fn test_invalid_password() {
+		let (mut client, mut server) = handshake(b"wrong");
+		assert_eq!(client.poll_event(), Some(Event::Disconnected(DisconnectReason::InvalidPassword)));
+		assert_eq!(server.state(), State::Disconnected);
+		assert_eq!(server.poll_event(), Some(Event::Disconnected(DisconnectReason::InvalidPassword)));
+	}
+
+	#[test]
+	fn test_timeout() {
+		let (mut client, _) = handshake(b"3.25 ND1");
+		client.poll_transmit(10000).unwrap();
+		assert_eq!(client.state(), State::Connected);
+		client.poll_transmit(10100).unwrap();
+		assert_eq!(client.state(), State::Disconnected);
+		assert_eq!(client.poll_event(), Some(Event::Connected));
+		assert_eq!(client.poll_event(), Some(Event::Latency(10)));
+		assert_eq!(client.poll_event(), Some(Event::Disconnected(DisconnectReason::Timeout)));
+	}
+}
@@ -1,13 +1,18 @@
 //! Raknet messages.
 pub mod client;
+pub mod connection;
+pub mod replica_manager;
 pub mod server;
+pub mod transport;
 
 use std::net::Ipv4Addr;
 
 use endio::{Deserialize, Serialize};
+use lu_packets_derive::Dissect;
 
 /// A combination of Ipv4Addr and port. todo: just use SocketAddrV4
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Dissect, Eq, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SystemAddress {
 	pub ip: Ipv4Addr,
 	pub port: u16,
@@ -0,0 +1,349 @@
+/*!
+	Server-side replication of objects to clients.
+
+	A [`ReplicaManager`] keeps the current [`ReplicaConstruction`] of every replicated object, allocates their network IDs, and tracks which clients each object is in scope for. When an object comes into scope for a client, the client receives its construction, while it's in scope, serializations of the object are forwarded to it, and when it leaves scope or the object is destroyed, the client receives a [`ReplicaDestruction`].
+
+	Like the connection, the manager is sans-IO: outgoing messages are serialized and queued per client, and can be retrieved with [`poll_packet`](ReplicaManager::poll_packet) and sent with [`Connection::send`](super::connection::Connection::send), using [`Reliability::ReliableOrdered`](super::transport::Reliability::ReliableOrdered).
+*/
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::io::{Error, ErrorKind::Other, Result as Res};
+
+use endio::LEWrite;
+
+use super::client::replica::{ReplicaConstruction, ReplicaDestruction, ReplicaSerialization};
+
+const ID_REPLICA_CONSTRUCTION: u8 = 36;
+const ID_REPLICA_DESTRUCTION: u8 = 37;
+const ID_REPLICA_SERIALIZATION: u8 = 39;
+
+/// The kind of a [`Packet`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PacketKind {
+	Construction,
+	Serialization,
+	Destruction,
+}
+
+/// A replica message queued for a client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packet {
+	pub kind: PacketKind,
+	pub network_id: u16,
+	/// The serialized message, starting with its RakNet message ID.
+	pub data: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Object<K> {
+	construction: ReplicaConstruction,
+	/// Clients for which the scope differs from the default.
+	scope_overrides: HashMap<K, bool>,
+	/// Clients that have received the construction.
+	constructed_for: HashSet<K>,
+}
+
+/**
+	Replicates objects to clients.
+
+	`K` identifies clients, for example by their [`SystemAddress`](super::SystemAddress).
+
+	Example:
+
+	```
+	# use std::net::Ipv4Addr;
+	# use lu_packets::raknet::SystemAddress;
+	# use lu_packets::raknet::replica_manager::{PacketKind, ReplicaManager};
+	# fn construction() -> lu_packets::raknet::client::replica::ReplicaConstruction { unimplemented!() }
+	# fn run() -> std::io::Result<()> {
+	let client = SystemAddress { ip: Ipv4Addr::LOCALHOST, port: 1001 };
+	let mut manager = ReplicaManager::new();
+	// after the client has sent LevelLoadComplete
+	manager.add_client(client.clone())?;
+	let network_id = manager.construct(construction())?;
+	let packet = manager.poll_packet(&client).unwrap();
+	assert_eq!(packet.kind, PacketKind::Construction);
+	assert_eq!(packet.network_id, network_id);
+	# Ok(())
+	# }
+	```
+*/
+#[derive(Debug)]
+pub struct ReplicaManager<K> {
+	objects: BTreeMap<u16, Object<K>>,
+	clients: HashMap<K, VecDeque<Packet>>,
+	default_scope: bool,
+	next_network_id: u16,
+	free_network_ids: Vec<u16>,
+}
+
+impl<K: Clone + Eq + Hash> ReplicaManager<K> {
+	/// Creates a manager in which objects are in scope for all clients by default.
+	pub fn new() -> Self {
+		Self { objects: BTreeMap::new(), clients: HashMap::new(), default_scope: true, next_network_id: 1, free_network_ids: vec![] }
+	}
+
+	/// Whether objects are in scope for clients without an explicit [`set_scope`](Self::set_scope).
+	pub fn default_scope(&self) -> bool {
+		self.default_scope
+	}
+
+	/// Changes the default scope, constructing or destroying objects for clients as needed.
+	pub fn set_default_scope(&mut self, in_scope: bool) -> Res<()> {
+		self.default_scope = in_scope;
+		let network_ids: Vec<_> = self.objects.keys().copied().collect();
+		let clients: Vec<_> = self.clients.keys().cloned().collect();
+		for network_id in network_ids {
+			for client in &clients {
+				self.sync_scope(network_id, client)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Adds a client, queueing the constructions of all objects in scope for it.
+	pub fn add_client(&mut self, client: K) -> Res<()> {
+		if self.clients.contains_key(&client) {
+			return Ok(());
+		}
+		self.clients.insert(client.clone(), VecDeque::new());
+		let network_ids: Vec<_> = self.objects.keys().copied().collect();
+		for network_id in network_ids {
+			self.sync_scope(network_id, &client)?;
+		}
+		Ok(())
+	}
+
+	/// Removes a client, dropping its queued packets. Nothing is sent, since the client is usually disconnected or switching worlds.
+	pub fn remove_client(&mut self, client: &K) {
+		self.clients.remove(client);
+		for object in self.objects.values_mut() {
+			object.scope_overrides.remove(client);
+			object.constructed_for.remove(client);
+		}
+	}
+
+	pub fn clients(&self) -> impl Iterator<Item = &K> {
+		self.clients.keys()
+	}
+
+	/**
+		Adds an object, allocating a network ID for it, and queues its construction for all clients it's in scope for.
+
+		The `network_id` of the construction is overwritten with the allocated one, which is returned. Fails if all network IDs are in use.
+	*/
+	pub fn construct(&mut self, mut construction: ReplicaConstruction) -> Res<u16> {
+		let network_id = self.allocate_network_id()?;
+		construction.network_id = network_id;
+		self.objects.insert(network_id, Object { construction, scope_overrides: HashMap::new(), constructed_for: HashSet::new() });
+		let clients: Vec<_> = self.clients.keys().cloned().collect();
+		for client in &clients {
+			self.sync_scope(network_id, client)?;
+		}
+		Ok(network_id)
+	}
+
+	/**
+		Queues a serialization for all clients the object has been constructed for.
+
+		The stored construction isn't changed, update it with [`construction_mut`](Self::construction_mut) so that clients the object comes into scope for later receive the current state.
+	*/
+	pub fn serialize(&mut self, serialization: &ReplicaSerialization) -> Res<()> {
+		let object = match self.objects.get(&serialization.network_id) {
+			Some(x) => x,
+			None => return Err(Error::new(Other, "serialization of unknown network ID")),
+		};
+		let mut data = vec![ID_REPLICA_SERIALIZATION];
+		LEWrite::write(&mut data, serialization)?;
+		for client in &object.constructed_for {
+			if let Some(queue) = self.clients.get_mut(client) {
+				queue.push_back(Packet { kind: PacketKind::Serialization, network_id: serialization.network_id, data: data.clone() });
+			}
+		}
+		Ok(())
+	}
+
+	/**
+		Replaces the state of an object, queueing a serialization of only what changed for all clients it has been constructed for, see [`ReplicaSerialization::diff`].
+
+		Fails if the object doesn't exist, or if the components of the new state don't match the stored ones.
+	*/
+	pub fn update(&mut self, mut construction: ReplicaConstruction) -> Res<()> {
+		let object = match self.objects.get_mut(&construction.network_id) {
+			Some(x) => x,
+			None => return Err(Error::new(Other, "update of unknown network ID")),
+		};
+		construction.network_id = object.construction.network_id;
+		let serialization = match ReplicaSerialization::diff(&object.construction, &construction) {
+			Some(x) => x,
+			None => return Err(Error::new(Other, "components of update don't match construction")),
+		};
+		object.construction = construction;
+		self.serialize(&serialization)
+	}
+
+	/// Removes an object, queueing its destruction for all clients it has been constructed for. Its network ID is freed for reuse.
+	pub fn destroy(&mut self, network_id: u16) -> Option<ReplicaConstruction> {
+		let object = self.objects.remove(&network_id)?;
+		for client in &object.constructed_for {
+			self.queue_destruction(network_id, client);
+		}
+		self.free_network_ids.push(network_id);
+		Some(object.construction)
+	}
+
+	/// Puts an object in or out of scope for a client, constructing or destroying it for the client as needed.
+	pub fn set_scope(&mut self, network_id: u16, client: &K, in_scope: bool) -> Res<()> {
+		if let Some(object) = self.objects.get_mut(&network_id) {
+			object.scope_overrides.insert(client.clone(), in_scope);
+			self.sync_scope(network_id, client)?;
+		}
+		Ok(())
+	}
+
+	/// Whether an object is in scope for a client.
+	pub fn is_in_scope(&self, network_id: u16, client: &K) -> bool {
+		match self.objects.get(&network_id) {
+			Some(object) => self.clients.contains_key(client) && *object.scope_overrides.get(client).unwrap_or(&self.default_scope),
+			None => false,
+		}
+	}
+
+	pub fn construction(&self, network_id: u16) -> Option<&ReplicaConstruction> {
+		self.objects.get(&network_id).map(|x| &x.construction)
+	}
+
+	/// The stored construction of an object, to keep it up to date for clients the object comes into scope for later. The network ID must not be changed.
+	pub fn construction_mut(&mut self, network_id: u16) -> Option<&mut ReplicaConstruction> {
+		self.objects.get_mut(&network_id).map(|x| &mut x.construction)
+	}
+
+	/// The network IDs of all objects, in ascending order.
+	pub fn network_ids(&self) -> impl Iterator<Item = u16> + '_ {
+		self.objects.keys().copied()
+	}
+
+	/// Returns the next packet queued for the client, if any.
+	pub fn poll_packet(&mut self, client: &K) -> Option<Packet> {
+		self.clients.get_mut(client)?.pop_front()
+	}
+
+	fn allocate_network_id(&mut self) -> Res<u16> {
+		if let Some(network_id) = self.free_network_ids.pop() {
+			return Ok(network_id);
+		}
+		// 65535 is RakNet's unassigned network ID
+		if self.next_network_id == u16::MAX {
+			return Err(Error::new(Other, "all network IDs are in use"));
+		}
+		let network_id = self.next_network_id;
+		self.next_network_id += 1;
+		Ok(network_id)
+	}
+
+	/// Constructs or destroys the object for the client, if its scope changed.
+	fn sync_scope(&mut self, network_id: u16, client: &K) -> Res<()> {
+		let in_scope = self.is_in_scope(network_id, client);
+		let object = match self.objects.get_mut(&network_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+		let constructed = object.constructed_for.contains(client);
+		if in_scope && !constructed {
+			let mut data = vec![ID_REPLICA_CONSTRUCTION];
+			LEWrite::write(&mut data, &object.construction)?;
+			object.constructed_for.insert(client.clone());
+			if let Some(queue) = self.clients.get_mut(client) {
+				queue.push_back(Packet { kind: PacketKind::Construction, network_id, data });
+			}
+		} else if !in_scope && constructed {
+			object.constructed_for.remove(client);
+			self.queue_destruction(network_id, client);
+		}
+		Ok(())
+	}
+
+	fn queue_destruction(&mut self, network_id: u16, client: &K) {
+		if let Some(queue) = self.clients.get_mut(client) {
+			let mut data = vec![ID_REPLICA_DESTRUCTION];
+			LEWrite::write(&mut data, &ReplicaDestruction { network_id }).unwrap();
+			queue.push_back(Packet { kind: PacketKind::Destruction, network_id, data });
+		}
+	}
+}
+
+impl<K: Clone + Eq + Hash> Default for ReplicaManager<K> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::lu;
+	use super::*;
+
+	fn construction() -> ReplicaConstruction {
+		ReplicaConstruction { network_id: 0, object_id: 70368744177662, lot: 13006, name: lu!(""), time_since_created_on_server: 0, config: None, is_trigger: false, spawner_id: None, spawner_node_id: None, scale: None, world_state: None, gm_level: None, parent_child_info: None, components: vec![] }
+	}
+
+	fn kinds(manager: &mut ReplicaManager<u32>, client: u32) -> Vec<(PacketKind, u16)> {
+		std::iter::from_fn(|| manager.poll_packet(&client)).map(|x| (x.kind, x.network_id)).collect()
+	}
+
+	#[test]
+	fn test_scope() {
+		let mut manager = ReplicaManager::new();
+		manager.add_client(1).unwrap();
+		let a = manager.construct(construction()).unwrap();
+		manager.add_client(2).unwrap();
+		let b = manager.construct(construction()).unwrap();
+		assert_eq!(kinds(&mut manager, 1), [(PacketKind::Construction, a), (PacketKind::Construction, b)]);
+		assert_eq!(kinds(&mut manager, 2), [(PacketKind::Construction, a), (PacketKind::Construction, b)]);
+
+		manager.set_scope(a, &2, false).unwrap();
+		manager.serialize(&ReplicaSerialization { network_id: a, parent_child_info: None, components: vec![] }).unwrap();
+		assert_eq!(kinds(&mut manager, 1), [(PacketKind::Serialization, a)]);
+		assert_eq!(kinds(&mut manager, 2), [(PacketKind::Destruction, a)]);
+
+		manager.set_default_scope(false).unwrap();
+		manager.set_scope(a, &2, true).unwrap();
+		assert_eq!(kinds(&mut manager, 1), [(PacketKind::Destruction, a), (PacketKind::Destruction, b)]);
+		assert_eq!(kinds(&mut manager, 2), [(PacketKind::Destruction, b), (PacketKind::Construction, a)]);
+	}
+
+	#[test]
+	fn test_destroy() {
+		let mut manager = ReplicaManager::new();
+		manager.add_client(1).unwrap();
+		let a = manager.construct(construction()).unwrap();
+		assert_eq!(manager.poll_packet(&1).unwrap().data[0], ID_REPLICA_CONSTRUCTION);
+		assert!(manager.destroy(a).is_some());
+		assert_eq!(manager.poll_packet(&1).unwrap().data, [ID_REPLICA_DESTRUCTION, a as u8, 0]);
+		assert!(manager.serialize(&ReplicaSerialization { network_id: a, parent_child_info: None, components: vec![] }).is_err());
+		assert_eq!(manager.construct(construction()).unwrap(), a);
+	}
+
+	#[test]
+	fn test_update() {
+		use crate::raknet::client::replica::switch::SwitchConstruction;
+
+		let mut manager = ReplicaManager::new();
+		manager.add_client(1).unwrap();
+		let mut constr = construction();
+		constr.components = vec![SwitchConstruction { is_active: false }.into()];
+		let a = manager.construct(constr).unwrap();
+		assert_eq!(kinds(&mut manager, 1), [(PacketKind::Construction, a)]);
+
+		let mut constr = construction();
+		constr.network_id = a;
+		constr.components = vec![SwitchConstruction { is_active: true }.into()];
+		manager.update(constr).unwrap();
+		assert_eq!(manager.poll_packet(&1).unwrap().data, [ID_REPLICA_SERIALIZATION, a as u8, 0, 0x40]);
+		assert_eq!(manager.construction(a).unwrap().component::<SwitchConstruction>(), Some(&SwitchConstruction { is_active: true }));
+
+		let mut constr = construction();
+		constr.network_id = a;
+		assert!(manager.update(constr).is_err());
+	}
+}
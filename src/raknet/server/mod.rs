@@ -4,11 +4,13 @@ use std::io::Result as Res;
 
 use endio::{Deserialize, Serialize};
 use endio::LittleEndian as LE;
-use lu_packets_derive::VariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, VariantTests};
 
+use crate::dissect::Dissector;
 use super::SystemAddress;
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[test_params(crate::world::server::LuMessage)]
 #[non_exhaustive]
 #[repr(u8)]
@@ -20,12 +22,14 @@ pub enum Message<U> {
 	UserMessage(U) = 83,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct InternalPing {
 	pub send_time: u32,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ConnectionRequest {
 	pub password: Box<[u8]>,
 }
@@ -46,7 +50,17 @@ impl<'a, W: Write> Serialize<LE, W> for &'a ConnectionRequest {
 	}
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+impl crate::dissect::Dissect for ConnectionRequest {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.node("password", std::any::type_name::<Box<[u8]>>(), |dissector| {
+			dissector.leaf(&self.password, self.password.len() as u64 * 8);
+			Ok(())
+		})
+	}
+}
+
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NewIncomingConnection {
 	pub peer_addr: SystemAddress,
 	pub local_addr: SystemAddress,
@@ -0,0 +1,485 @@
+/*!
+	RakNet 3.25 reliability layer.
+
+	Turns the UDP datagrams exchanged by connected RakNet peers into the payloads the [`raknet::client::Message`](crate::raknet::client::Message) and [`raknet::server::Message`](crate::raknet::server::Message) types decode, and back again. Handles acknowledgements, resending, duplicate detection, sequencing, ordering channels, and splitting of payloads that exceed the MTU.
+
+	This module is sans-IO: it never touches a socket or a clock. Feed it received datagrams with [`ReliabilityLayer::handle_datagram`], take decoded payloads out with [`ReliabilityLayer::receive`], queue payloads with [`ReliabilityLayer::send`], and send whatever [`ReliabilityLayer::poll_transmit`] returns. Times are passed in as milliseconds from an arbitrary but fixed starting point.
+*/
+mod packet;
+mod split;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::{Error, ErrorKind::InvalidData};
+use std::io::Result as Res;
+
+pub use self::packet::*;
+pub use self::split::*;
+
+/// Number of ordering channels supported by RakNet.
+pub const ORDERING_CHANNEL_COUNT: usize = 32;
+/// Default maximum transmission unit, including IP and UDP headers.
+pub const DEFAULT_MTU: usize = 1492;
+/// Smallest supported maximum transmission unit, the size of datagrams every IPv4 host has to accept.
+pub const MIN_MTU: usize = 576;
+/// Size of the IP and UDP headers, which count towards the MTU.
+pub const UDP_HEADER_SIZE: usize = 28;
+
+const MIN_RESEND_TIMEOUT: u32 = 100;
+const MAX_RESEND_TIMEOUT: u32 = 10000;
+const INITIAL_RESEND_TIMEOUT: u32 = 1000;
+/// How far ahead of the next expected one reliable message numbers and ordering indexes are accepted, to bound the state kept for them.
+const RECEIVE_WINDOW: u32 = 1024;
+
+#[derive(Debug)]
+struct Resend {
+	next_send: u32,
+	packet: Packet,
+}
+
+/**
+	Reliability state of one side of a connection.
+
+	Each connected peer needs its own instance.
+*/
+#[derive(Debug)]
+pub struct ReliabilityLayer {
+	mtu: usize,
+	next_message_number: u32,
+	next_split_id: u16,
+	ordered_write_index: [u32; ORDERING_CHANNEL_COUNT],
+	sequenced_write_index: [u32; ORDERING_CHANNEL_COUNT],
+	send_queue: VecDeque<Packet>,
+	resend_queue: BTreeMap<u32, Resend>,
+	acks_to_send: Vec<u32>,
+	remote_system_time: Option<u32>,
+	srtt: Option<u32>,
+	/// All reliable message numbers below this have been received.
+	reliable_watermark: u32,
+	/// Reliable message numbers above the watermark that have been received.
+	reliable_received: BTreeSet<u32>,
+	ordered_read_index: [u32; ORDERING_CHANNEL_COUNT],
+	sequenced_read_index: [u32; ORDERING_CHANNEL_COUNT],
+	out_of_order: Vec<BTreeMap<u32, Vec<u8>>>,
+	reassembler: Reassembler,
+	received: VecDeque<Vec<u8>>,
+}
+
+impl ReliabilityLayer {
+	/**
+		Creates a layer for a new connection, with datagrams limited to the given MTU.
+
+		### Panics
+		Panics if `mtu` is less than [`MIN_MTU`].
+	*/
+	pub fn new(mtu: usize) -> Self {
+		assert!(mtu >= MIN_MTU, "MTU {} is less than the minimum of {}", mtu, MIN_MTU);
+		Self { mtu, next_message_number: 0, next_split_id: 0, ordered_write_index: [0; ORDERING_CHANNEL_COUNT], sequenced_write_index: [0; ORDERING_CHANNEL_COUNT], send_queue: VecDeque::new(), resend_queue: BTreeMap::new(), acks_to_send: vec![], remote_system_time: None, srtt: None, reliable_watermark: 0, reliable_received: BTreeSet::new(), ordered_read_index: [0; ORDERING_CHANNEL_COUNT], sequenced_read_index: [0; ORDERING_CHANNEL_COUNT], out_of_order: vec![BTreeMap::new(); ORDERING_CHANNEL_COUNT], reassembler: Reassembler::default(), received: VecDeque::new() }
+	}
+
+	/// Sets the limits for reassembling split packets received from the peer.
+	pub fn set_split_limits(&mut self, limits: SplitLimits) {
+		self.reassembler = Reassembler::new(limits);
+	}
+
+	/// Maximum payload size that can be sent without splitting.
+	pub fn max_payload_size(&self) -> usize {
+		self.mtu - UDP_HEADER_SIZE - Datagram::MAX_DATAGRAM_HEADER_SIZE - Datagram::MAX_PACKET_HEADER_SIZE
+	}
+
+	/// Smoothed round trip time in milliseconds, if any reliable packet has been acknowledged yet.
+	pub fn rtt(&self) -> Option<u32> {
+		self.srtt
+	}
+
+	/// Whether there are queued or unacknowledged packets.
+	pub fn has_pending(&self) -> bool {
+		!self.send_queue.is_empty() || !self.resend_queue.is_empty() || !self.acks_to_send.is_empty()
+	}
+
+	/**
+		Queues a payload for sending.
+
+		The payload must start with the RakNet message ID, as produced by serializing a [`Message`](crate::raknet::client::Message). Only the lower 5 bits of `ordering_channel` are used, and only for sequenced and ordered reliabilities.
+
+		Payloads larger than [`max_payload_size`](Self::max_payload_size) are split into multiple packets, which are always sent reliably.
+	*/
+	pub fn send(&mut self, data: Vec<u8>, reliability: Reliability, ordering_channel: u8) {
+		let max_payload_size = self.max_payload_size();
+		let (reliability, fragments) = if data.len() > max_payload_size { fragment(&data, reliability, max_payload_size) } else { (reliability, vec![data]) };
+		let channel = ordering_channel & 0x1f;
+		let ordering = if reliability.is_sequenced() {
+			let index = &mut self.sequenced_write_index[channel as usize];
+			let ordering = OrderingInfo { channel, index: *index };
+			*index = index.wrapping_add(1);
+			Some(ordering)
+		} else if reliability.is_ordered() {
+			let index = &mut self.ordered_write_index[channel as usize];
+			let ordering = OrderingInfo { channel, index: *index };
+			*index = index.wrapping_add(1);
+			Some(ordering)
+		} else {
+			None
+		};
+		let count = fragments.len() as u32;
+		let split_id = if count > 1 {
+			let id = self.next_split_id;
+			self.next_split_id = self.next_split_id.wrapping_add(1);
+			Some(id)
+		} else {
+			None
+		};
+		for (index, data) in fragments.into_iter().enumerate() {
+			let split = split_id.map(|id| SplitInfo { id, index: index as u32, count });
+			let message_number = self.next_message_number;
+			self.next_message_number = self.next_message_number.wrapping_add(1);
+			self.send_queue.push_back(Packet { message_number, reliability, ordering, split, data });
+		}
+	}
+
+	/// Returns the next received payload, in delivery order.
+	pub fn receive(&mut self) -> Option<Vec<u8>> {
+		self.received.pop_front()
+	}
+
+	/**
+		Processes a datagram received from the peer.
+
+		Payloads that are ready for delivery can be taken out with [`receive`](Self::receive) afterwards.
+	*/
+	pub fn handle_datagram(&mut self, data: &[u8], now: u32) -> Res<()> {
+		let datagram = Datagram::deserialize(data)?;
+		if let Some(acks) = datagram.acks {
+			self.handle_acks(&acks, now);
+		}
+		if datagram.remote_system_time.is_some() {
+			self.remote_system_time = datagram.remote_system_time;
+		}
+		for packet in datagram.packets {
+			self.handle_packet(packet, now)?;
+		}
+		Ok(())
+	}
+
+	fn handle_acks(&mut self, acks: &Acks, now: u32) {
+		let mut acked_any = false;
+		for &(min, max) in &acks.ranges {
+			let acked: Vec<u32> = self.resend_queue.range(min..=max).map(|(&number, _)| number).collect();
+			for number in acked {
+				self.resend_queue.remove(&number);
+				acked_any = true;
+			}
+		}
+		if acked_any {
+			let sample = now.wrapping_sub(acks.remote_system_time);
+			self.srtt = Some(match self.srtt {
+				None => sample,
+				Some(srtt) => srtt.saturating_mul(7).saturating_add(sample) / 8,
+			});
+		}
+	}
+
+	fn handle_packet(&mut self, packet: Packet, now: u32) -> Res<()> {
+		if packet.reliability.is_reliable() {
+			match self.mark_reliable_received(packet.message_number) {
+				// not acked, so the peer resends it once the window has moved
+				Received::OutOfWindow => return Ok(()),
+				Received::Duplicate => {
+					// the ack for the original got lost
+					self.acks_to_send.push(packet.message_number);
+					return Ok(());
+				}
+				Received::New => self.acks_to_send.push(packet.message_number),
+			}
+		}
+		if let Some(split) = packet.split {
+			return match self.reassembler.insert(split, packet.reliability, packet.ordering, packet.data, now)? {
+				Some(x) => self.deliver(x.reliability, x.ordering, x.data),
+				None => Ok(()),
+			};
+		}
+		self.deliver(packet.reliability, packet.ordering, packet.data)
+	}
+
+	fn mark_reliable_received(&mut self, message_number: u32) -> Received {
+		if is_before(message_number, self.reliable_watermark) {
+			return Received::Duplicate;
+		}
+		if message_number.wrapping_sub(self.reliable_watermark) >= RECEIVE_WINDOW {
+			return Received::OutOfWindow;
+		}
+		if !self.reliable_received.insert(message_number) {
+			return Received::Duplicate;
+		}
+		while self.reliable_received.remove(&self.reliable_watermark) {
+			self.reliable_watermark = self.reliable_watermark.wrapping_add(1);
+		}
+		Received::New
+	}
+
+	fn deliver(&mut self, reliability: Reliability, ordering: Option<OrderingInfo>, data: Vec<u8>) -> Res<()> {
+		let ordering = match ordering {
+			Some(x) => x,
+			None => {
+				self.received.push_back(data);
+				return Ok(());
+			}
+		};
+		let channel = ordering.channel as usize;
+		if channel >= ORDERING_CHANNEL_COUNT {
+			return Err(Error::new(InvalidData, "invalid ordering channel"));
+		}
+		if reliability.is_sequenced() {
+			let expected = &mut self.sequenced_read_index[channel];
+			if !is_before(ordering.index, *expected) {
+				*expected = ordering.index.wrapping_add(1);
+				self.received.push_back(data);
+			}
+			return Ok(());
+		}
+		let expected = self.ordered_read_index[channel];
+		if is_before(ordering.index, expected) || ordering.index.wrapping_sub(expected) >= RECEIVE_WINDOW {
+			return Ok(());
+		}
+		self.out_of_order[channel].insert(ordering.index, data);
+		let mut expected = expected;
+		while let Some(data) = self.out_of_order[channel].remove(&expected) {
+			self.received.push_back(data);
+			expected = expected.wrapping_add(1);
+		}
+		self.ordered_read_index[channel] = expected;
+		Ok(())
+	}
+
+	fn resend_timeout(&self) -> u32 {
+		match self.srtt {
+			None => INITIAL_RESEND_TIMEOUT,
+			Some(srtt) => srtt.saturating_mul(2).clamp(MIN_RESEND_TIMEOUT, MAX_RESEND_TIMEOUT),
+		}
+	}
+
+	fn take_ack_ranges(&mut self) -> Vec<(u32, u32)> {
+		self.acks_to_send.sort_unstable();
+		self.acks_to_send.dedup();
+		let mut ranges: Vec<(u32, u32)> = vec![];
+		for &number in &self.acks_to_send {
+			match ranges.last_mut() {
+				Some((_, max)) if max.wrapping_add(1) == number => *max = number,
+				_ => ranges.push((number, number)),
+			}
+		}
+		self.acks_to_send.clear();
+		ranges
+	}
+
+	/**
+		Returns the datagrams that should be sent to the peer now.
+
+		This includes acknowledgements, newly queued packets, and reliable packets whose acknowledgement is overdue. Call this regularly, e.g. every few ten milliseconds, even if nothing was queued.
+	*/
+	pub fn poll_transmit(&mut self, now: u32) -> Res<Vec<Vec<u8>>> {
+		self.reassembler.expire(now);
+		let max_size = self.mtu - UDP_HEADER_SIZE;
+		let timeout = self.resend_timeout();
+		let mut due: VecDeque<Packet> = VecDeque::new();
+		for resend in self.resend_queue.values_mut() {
+			if !is_before(now, resend.next_send) {
+				resend.next_send = now.wrapping_add(timeout);
+				due.push_back(resend.packet.clone());
+			}
+		}
+		let mut datagrams = vec![];
+		let mut ranges = self.take_ack_ranges();
+		loop {
+			let mut size = Datagram::MAX_DATAGRAM_HEADER_SIZE;
+			let acks = if ranges.is_empty() {
+				None
+			} else {
+				let fit = ((max_size - size - 4 - 2) / 9).clamp(1, ranges.len());
+				let rest = ranges.split_off(fit);
+				let acks = Acks { remote_system_time: self.remote_system_time.unwrap_or(0), ranges: std::mem::replace(&mut ranges, rest) };
+				size += 4 + 2 + acks.ranges.len() * 9;
+				Some(acks)
+			};
+			let mut packets = vec![];
+			while let Some(next) = due.front().or_else(|| self.send_queue.front()) {
+				let next_size = Datagram::MAX_PACKET_HEADER_SIZE + next.data.len();
+				if !packets.is_empty() && size + next_size > max_size {
+					break;
+				}
+				let packet = match due.pop_front() {
+					Some(x) => x,
+					None => {
+						let packet = self.send_queue.pop_front().unwrap();
+						if packet.reliability.is_reliable() {
+							self.resend_queue.insert(packet.message_number, Resend { next_send: now.wrapping_add(timeout), packet: packet.clone() });
+						}
+						packet
+					}
+				};
+				size += next_size;
+				packets.push(packet);
+			}
+			if acks.is_none() && packets.is_empty() {
+				break;
+			}
+			let remote_system_time = if packets.is_empty() { None } else { Some(now) };
+			datagrams.push(Datagram { acks, remote_system_time, packets }.serialize()?);
+		}
+		Ok(datagrams)
+	}
+}
+
+/// Result of marking a reliable message number as received.
+enum Received {
+	New,
+	Duplicate,
+	/// Too far ahead to be tracked.
+	OutOfWindow,
+}
+
+/// Whether sequence number or timestamp `a` comes before `b`, taking wraparound into account.
+fn is_before(a: u32, b: u32) -> bool {
+	(a.wrapping_sub(b) as i32) < 0
+}
+
+impl Default for ReliabilityLayer {
+	fn default() -> Self {
+		Self::new(DEFAULT_MTU)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn transfer(from: &mut ReliabilityLayer, to: &mut ReliabilityLayer, now: u32) -> usize {
+		let datagrams = from.poll_transmit(now).unwrap();
+		for datagram in &datagrams {
+			to.handle_datagram(datagram, now).unwrap();
+		}
+		datagrams.len()
+	}
+
+	#[test]
+	fn test_reliable_ordered() {
+		let mut a = ReliabilityLayer::default();
+		let mut b = ReliabilityLayer::default();
+		a.send(vec![0x53, 1], Reliability::ReliableOrdered, 0);
+		a.send(vec![0x53, 2], Reliability::ReliableOrdered, 0);
+		transfer(&mut a, &mut b, 0);
+		assert_eq!(b.receive(), Some(vec![0x53, 1]));
+		assert_eq!(b.receive(), Some(vec![0x53, 2]));
+		assert_eq!(b.receive(), None);
+		assert!(a.has_pending());
+		transfer(&mut b, &mut a, 20);
+		assert!(!a.has_pending());
+		assert_eq!(a.rtt(), Some(20));
+	}
+
+	#[test]
+	fn test_resend_and_reorder() {
+		let mut a = ReliabilityLayer::default();
+		let mut b = ReliabilityLayer::default();
+		a.send(vec![1], Reliability::ReliableOrdered, 0);
+		// lost
+		a.poll_transmit(0).unwrap();
+		a.send(vec![2], Reliability::ReliableOrdered, 0);
+		transfer(&mut a, &mut b, 10);
+		assert_eq!(b.receive(), None);
+		transfer(&mut a, &mut b, INITIAL_RESEND_TIMEOUT);
+		assert_eq!(b.receive(), Some(vec![1]));
+		assert_eq!(b.receive(), Some(vec![2]));
+		// duplicate of 2 arrives, should not be delivered again
+		transfer(&mut a, &mut b, INITIAL_RESEND_TIMEOUT + 10);
+		transfer(&mut a, &mut b, 2 * INITIAL_RESEND_TIMEOUT + 10);
+		assert_eq!(b.receive(), None);
+	}
+
+	#[test]
+	fn test_split() {
+		let mut a = ReliabilityLayer::new(576);
+		let mut b = ReliabilityLayer::default();
+		let data: Vec<u8> = (0..=255).cycle().take(5000).collect();
+		a.send(data.clone(), Reliability::ReliableOrdered, 0);
+		a.send(vec![0x53], Reliability::ReliableOrdered, 0);
+		assert!(transfer(&mut a, &mut b, 0) > 1);
+		assert_eq!(b.receive(), Some(data));
+		assert_eq!(b.receive(), Some(vec![0x53]));
+	}
+
+	#[test]
+	fn test_sequenced_drops_old() {
+		let mut a = ReliabilityLayer::default();
+		let mut b = ReliabilityLayer::default();
+		a.send(vec![1], Reliability::UnreliableSequenced, 1);
+		let old = a.poll_transmit(0).unwrap();
+		a.send(vec![2], Reliability::UnreliableSequenced, 1);
+		transfer(&mut a, &mut b, 0);
+		for datagram in &old {
+			b.handle_datagram(datagram, 0).unwrap();
+		}
+		assert_eq!(b.receive(), Some(vec![2]));
+		assert_eq!(b.receive(), None);
+	}
+
+	fn reliable_ordered(message_number: u32, index: u32) -> Vec<u8> {
+		let packet = Packet { message_number, reliability: Reliability::ReliableOrdered, ordering: Some(OrderingInfo { channel: 0, index }), split: None, data: vec![index as u8] };
+		Datagram { acks: None, remote_system_time: None, packets: vec![packet] }.serialize().unwrap()
+	}
+
+	#[test]
+	fn test_receive_window() {
+		let mut b = ReliabilityLayer::default();
+		b.handle_datagram(&reliable_ordered(RECEIVE_WINDOW, 0), 0).unwrap();
+		assert!(b.reliable_received.is_empty());
+		assert!(!b.has_pending());
+		b.handle_datagram(&reliable_ordered(1, RECEIVE_WINDOW), 0).unwrap();
+		assert!(b.out_of_order[0].is_empty());
+		b.handle_datagram(&reliable_ordered(0, 0), 0).unwrap();
+		assert_eq!(b.receive(), Some(vec![0]));
+		assert_eq!(b.receive(), None);
+	}
+
+	#[test]
+	fn test_wraparound() {
+		let mut b = ReliabilityLayer::new(DEFAULT_MTU);
+		b.reliable_watermark = u32::MAX - 1;
+		b.ordered_read_index[0] = u32::MAX;
+		b.handle_datagram(&reliable_ordered(0, 1), 0).unwrap();
+		b.handle_datagram(&reliable_ordered(u32::MAX, 0), 0).unwrap();
+		b.handle_datagram(&reliable_ordered(u32::MAX - 1, u32::MAX), 0).unwrap();
+		assert_eq!(b.receive(), Some(vec![255]));
+		assert_eq!(b.receive(), Some(vec![0]));
+		assert_eq!(b.receive(), Some(vec![1]));
+		assert_eq!(b.reliable_watermark, 1);
+		assert!(b.reliable_received.is_empty());
+		// old message numbers are duplicates, not far ahead
+		b.handle_datagram(&reliable_ordered(u32::MAX, 0), 0).unwrap();
+		assert_eq!(b.receive(), None);
+		assert_eq!(b.take_ack_ranges(), vec![(0, 0), (u32::MAX - 1, u32::MAX)]);
+	}
+
+	#[test]
+	fn test_time_overflow() {
+		let mut a = ReliabilityLayer::default();
+		let start = u32::MAX - 10;
+		a.send(vec![1], Reliability::Reliable, 0);
+		assert_eq!(a.poll_transmit(start).unwrap().len(), 1);
+		let next_send = a.resend_queue[&0].next_send;
+		assert_eq!(next_send, INITIAL_RESEND_TIMEOUT - 11);
+		// not due yet just after the wrap
+		assert!(a.poll_transmit(u32::MAX).unwrap().is_empty());
+		assert!(a.poll_transmit(5).unwrap().is_empty());
+		assert_eq!(a.poll_transmit(next_send).unwrap().len(), 1);
+		// round trip samples across the wrap
+		a.handle_acks(&Acks { remote_system_time: u32::MAX - 5, ranges: vec![(0, 0)] }, 5);
+		assert_eq!(a.srtt, Some(11));
+		assert!(a.resend_queue.is_empty());
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_small_mtu() {
+		ReliabilityLayer::new(100);
+	}
+}
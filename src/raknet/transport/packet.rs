@@ -0,0 +1,298 @@
+//! Wire format of RakNet 3.25 datagrams.
+use std::io::{Error, ErrorKind::InvalidData, Read, Write};
+use std::io::Result as Res;
+
+use endio::{LERead, LEWrite};
+use endio_bit::{BEBitReader, BEBitWriter};
+
+/// Delivery guarantees of a packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Reliability {
+	/// Delivered at most once, in no particular order.
+	Unreliable = 0,
+	/// Delivered at most once, older packets on the same ordering channel are dropped.
+	UnreliableSequenced = 1,
+	/// Delivered exactly once, in no particular order.
+	Reliable = 2,
+	/// Delivered exactly once, in order of sending on the same ordering channel.
+	ReliableOrdered = 3,
+	/// Delivered at most once, older packets on the same ordering channel are dropped, the newest is resent until acknowledged.
+	ReliableSequenced = 4,
+}
+
+impl Reliability {
+	/// Whether packets with this reliability are acknowledged and resent.
+	pub fn is_reliable(self) -> bool {
+		matches!(self, Self::Reliable | Self::ReliableOrdered | Self::ReliableSequenced)
+	}
+
+	/// Whether packets with this reliability carry [`OrderingInfo`].
+	pub fn is_ordered(self) -> bool {
+		matches!(self, Self::UnreliableSequenced | Self::ReliableOrdered | Self::ReliableSequenced)
+	}
+
+	/// Whether packets with this reliability are sequenced, i.e. older packets are dropped instead of delivered late.
+	pub fn is_sequenced(self) -> bool {
+		matches!(self, Self::UnreliableSequenced | Self::ReliableSequenced)
+	}
+
+	fn from_bits(bits: u8) -> Res<Self> {
+		Ok(match bits {
+			0 => Self::Unreliable,
+			1 => Self::UnreliableSequenced,
+			2 => Self::Reliable,
+			3 => Self::ReliableOrdered,
+			4 => Self::ReliableSequenced,
+			_ => return Err(crate::Error::UnknownDiscriminant { type_name: "Reliability", value: bits as u64 }.into()),
+		})
+	}
+}
+
+/// Ordering channel and index of a sequenced or ordered packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderingInfo {
+	/// The ordering channel, only the lower 5 bits are transmitted.
+	pub channel: u8,
+	pub index: u32,
+}
+
+/// Position of a fragment in a split packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SplitInfo {
+	/// Shared by all fragments of the same split packet.
+	pub id: u16,
+	pub index: u32,
+	pub count: u32,
+}
+
+/// A single packet inside a [`Datagram`], carrying (part of) a message starting with a RakNet message ID.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packet {
+	pub message_number: u32,
+	pub reliability: Reliability,
+	pub ordering: Option<OrderingInfo>,
+	pub split: Option<SplitInfo>,
+	pub data: Vec<u8>,
+}
+
+/// Acknowledgements for reliable packets, as inclusive ranges of message numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Acks {
+	/// The remote system time of the datagram being acknowledged, echoed back for round trip time measurement.
+	pub remote_system_time: u32,
+	pub ranges: Vec<(u32, u32)>,
+}
+
+/**
+	A UDP datagram as sent by a RakNet 3.25 peer, once the connection has been established.
+
+	Consists of optional acknowledgements, an optional timestamp, and any number of packets.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Datagram {
+	pub acks: Option<Acks>,
+	pub remote_system_time: Option<u32>,
+	pub packets: Vec<Packet>,
+}
+
+impl Datagram {
+	pub fn deserialize(mut data: &[u8]) -> Res<Self> {
+		let mut reader = BEBitReader::new(&mut data);
+		let acks = if reader.read_bit()? {
+			let remote_system_time = LERead::read(&mut reader)?;
+			let count = read_compressed_u16(&mut reader)?;
+			let mut ranges = vec![];
+			for _ in 0..count {
+				let min_equals_max = reader.read_bit()?;
+				let min = LERead::read(&mut reader)?;
+				let max = if min_equals_max { min } else { LERead::read(&mut reader)? };
+				if max < min {
+					return Err(crate::Error::Malformed { type_name: "Acks", reason: "range maximum below minimum" }.into());
+				}
+				ranges.push((min, max));
+			}
+			Some(Acks { remote_system_time, ranges })
+		} else {
+			None
+		};
+		let mut remote_system_time = None;
+		let mut packets = vec![];
+		// the rest of the current byte is padding for ack-only datagrams
+		if !reader.get_ref().is_empty() {
+			if reader.read_bit()? {
+				remote_system_time = Some(LERead::read(&mut reader)?);
+			}
+			loop {
+				packets.push(Self::deser_packet(&mut reader)?);
+				if reader.get_ref().is_empty() {
+					break;
+				}
+			}
+		}
+		Ok(Self { acks, remote_system_time, packets })
+	}
+
+	fn deser_packet<R: Read>(reader: &mut BEBitReader<R>) -> Res<Packet> {
+		let message_number = LERead::read(reader)?;
+		let reliability = Reliability::from_bits(reader.read_bits(3)?)?;
+		let ordering = if reliability.is_ordered() {
+			let channel = reader.read_bits(5)?;
+			let index = LERead::read(reader)?;
+			Some(OrderingInfo { channel, index })
+		} else {
+			None
+		};
+		let split = if reader.read_bit()? {
+			let id = LERead::read(reader)?;
+			let index = read_compressed_u32(reader)?;
+			let count = read_compressed_u32(reader)?;
+			Some(SplitInfo { id, index, count })
+		} else {
+			None
+		};
+		let bit_len = read_compressed_u16(reader)?;
+		reader.align();
+		let mut data = vec![0; (bit_len as usize + 7) / 8];
+		reader.read_exact(&mut data)?;
+		Ok(Packet { message_number, reliability, ordering, split, data })
+	}
+
+	pub fn serialize(&self) -> Res<Vec<u8>> {
+		let mut writer = BEBitWriter::new(vec![]);
+		writer.write_bit(self.acks.is_some())?;
+		if let Some(acks) = &self.acks {
+			LEWrite::write(&mut writer, acks.remote_system_time)?;
+			write_compressed_u16(&mut writer, acks.ranges.len() as u16)?;
+			for &(min, max) in &acks.ranges {
+				writer.write_bit(min == max)?;
+				LEWrite::write(&mut writer, min)?;
+				if min != max {
+					LEWrite::write(&mut writer, max)?;
+				}
+			}
+		}
+		if !self.packets.is_empty() {
+			writer.write_bit(self.remote_system_time.is_some())?;
+			if let Some(time) = self.remote_system_time {
+				LEWrite::write(&mut writer, time)?;
+			}
+			for packet in &self.packets {
+				Self::ser_packet(&mut writer, packet)?;
+			}
+		}
+		writer.flush()?;
+		Ok(writer.get_ref().clone())
+	}
+
+	fn ser_packet(writer: &mut BEBitWriter<Vec<u8>>, packet: &Packet) -> Res<()> {
+		LEWrite::write(writer, packet.message_number)?;
+		writer.write_bits(packet.reliability as u8, 3)?;
+		if packet.reliability.is_ordered() {
+			let ordering = packet.ordering.ok_or_else(|| Error::new(InvalidData, "ordered packet without ordering info"))?;
+			writer.write_bits(ordering.channel, 5)?;
+			LEWrite::write(writer, ordering.index)?;
+		}
+		writer.write_bit(packet.split.is_some())?;
+		if let Some(split) = packet.split {
+			LEWrite::write(writer, split.id)?;
+			write_compressed_u32(writer, split.index)?;
+			write_compressed_u32(writer, split.count)?;
+		}
+		if packet.data.len() > (u16::MAX / 8) as usize {
+			return Err(Error::new(InvalidData, "packet too large, needs to be split"));
+		}
+		write_compressed_u16(writer, packet.data.len() as u16 * 8)?;
+		// flushing the bit writer pads the current byte, which aligns the data
+		writer.flush()?;
+		Write::write_all(writer, &packet.data)
+	}
+
+	/// Size of a packet header in bytes, rounded up, as an upper bound for MTU calculations.
+	pub const MAX_PACKET_HEADER_SIZE: usize = 4 + 1 + 4 + 2 + 5 + 5 + 3;
+	/// Size of the datagram header in bytes, rounded up, as an upper bound for MTU calculations. Does not include acks.
+	pub const MAX_DATAGRAM_HEADER_SIZE: usize = 1 + 4;
+}
+
+/*
+	RakNet's compressed integer encoding: Starting from the most significant byte, a 1 bit is written for each byte that is zero. On the first non-zero byte, a 0 bit is written and the remaining bytes are written raw, in little endian order. If the last byte is reached, its upper nibble is elided if it is zero.
+*/
+fn read_compressed<R: Read>(reader: &mut BEBitReader<R>, bytes: &mut [u8]) -> Res<()> {
+	for current in (1..bytes.len()).rev() {
+		if !reader.read_bit()? {
+			reader.read_exact(&mut bytes[..=current])?;
+			return Ok(());
+		}
+		bytes[current] = 0;
+	}
+	bytes[0] = if reader.read_bit()? { reader.read_bits(4)? } else { reader.read_bits(8)? };
+	Ok(())
+}
+
+fn write_compressed<W: Write>(writer: &mut BEBitWriter<W>, bytes: &[u8]) -> Res<()> {
+	for current in (1..bytes.len()).rev() {
+		if bytes[current] != 0 {
+			writer.write_bit(false)?;
+			return Write::write_all(writer, &bytes[..=current]);
+		}
+		writer.write_bit(true)?;
+	}
+	if bytes[0] & 0xf0 == 0 {
+		writer.write_bit(true)?;
+		writer.write_bits(bytes[0], 4)
+	} else {
+		writer.write_bit(false)?;
+		writer.write_bits(bytes[0], 8)
+	}
+}
+
+pub(super) fn read_compressed_u16<R: Read>(reader: &mut BEBitReader<R>) -> Res<u16> {
+	let mut bytes = [0; 2];
+	read_compressed(reader, &mut bytes)?;
+	Ok(u16::from_le_bytes(bytes))
+}
+
+pub(super) fn read_compressed_u32<R: Read>(reader: &mut BEBitReader<R>) -> Res<u32> {
+	let mut bytes = [0; 4];
+	read_compressed(reader, &mut bytes)?;
+	Ok(u32::from_le_bytes(bytes))
+}
+
+pub(super) fn write_compressed_u16<W: Write>(writer: &mut BEBitWriter<W>, value: u16) -> Res<()> {
+	write_compressed(writer, &value.to_le_bytes())
+}
+
+pub(super) fn write_compressed_u32<W: Write>(writer: &mut BEBitWriter<W>, value: u32) -> Res<()> {
+	write_compressed(writer, &value.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_compressed() {
+		for &value in &[0u32, 1, 0x0f, 0x10, 0xff, 0x100, 0xffff, 0x12345, u32::MAX] {
+			let mut writer = BEBitWriter::new(vec![]);
+			write_compressed_u32(&mut writer, value).unwrap();
+			writer.flush().unwrap();
+			let data = writer.get_ref().clone();
+			let mut reader = BEBitReader::new(&data[..]);
+			assert_eq!(read_compressed_u32(&mut reader).unwrap(), value);
+		}
+	}
+
+	#[test]
+	fn test_datagram() {
+		let datagram = Datagram { acks: Some(Acks { remote_system_time: 1234, ranges: vec![(0, 0), (2, 5)] }), remote_system_time: Some(5678), packets: vec![Packet { message_number: 7, reliability: Reliability::ReliableOrdered, ordering: Some(OrderingInfo { channel: 0, index: 3 }), split: None, data: vec![0x53, 1, 2, 3] }, Packet { message_number: 8, reliability: Reliability::Reliable, ordering: None, split: Some(SplitInfo { id: 1, index: 0, count: 2 }), data: vec![0x24; 300] }, Packet { message_number: 9, reliability: Reliability::Unreliable, ordering: None, split: None, data: vec![0x00, 42, 0, 0, 0] }] };
+		let data = datagram.serialize().unwrap();
+		assert_eq!(Datagram::deserialize(&data).unwrap(), datagram);
+	}
+
+	#[test]
+	fn test_ack_only_datagram() {
+		let datagram = Datagram { acks: Some(Acks { remote_system_time: 1, ranges: vec![(3, 3)] }), remote_system_time: None, packets: vec![] };
+		let data = datagram.serialize().unwrap();
+		assert_eq!(Datagram::deserialize(&data).unwrap(), datagram);
+	}
+}
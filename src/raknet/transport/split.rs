@@ -0,0 +1,179 @@
+//! Split packet fragmentation and reassembly.
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::io::Result as Res;
+
+use super::{OrderingInfo, Reliability, SplitInfo};
+
+/**
+	Limits on incoming split packets.
+
+	Split packet headers are controlled by the peer, so without limits a peer could make us buffer arbitrary amounts of data.
+*/
+#[derive(Clone, Debug)]
+pub struct SplitLimits {
+	/// Maximum number of fragments in a single split packet.
+	pub max_fragment_count: u32,
+	/// Maximum size of a reassembled packet in bytes.
+	pub max_total_size: usize,
+	/// Maximum number of split packets being reassembled at the same time.
+	pub max_pending: usize,
+	/// Time in milliseconds after which an incomplete split packet that hasn't received any new fragments is discarded.
+	pub timeout: u32,
+}
+
+impl Default for SplitLimits {
+	fn default() -> Self {
+		Self { max_fragment_count: 4096, max_total_size: 1 << 22, max_pending: 32, timeout: 10000 }
+	}
+}
+
+#[derive(Debug)]
+struct PendingSplit {
+	fragments: Vec<Option<Vec<u8>>>,
+	received: u32,
+	size: usize,
+	last_activity: u32,
+	reliability: Reliability,
+	ordering: Option<OrderingInfo>,
+}
+
+/// A split packet whose fragments have all been received.
+#[derive(Debug, PartialEq)]
+pub struct Reassembled {
+	pub reliability: Reliability,
+	pub ordering: Option<OrderingInfo>,
+	pub data: Vec<u8>,
+}
+
+/// Gathers split packet fragments by split ID and index.
+#[derive(Debug, Default)]
+pub struct Reassembler {
+	limits: SplitLimits,
+	pending: HashMap<u16, PendingSplit>,
+}
+
+impl Reassembler {
+	pub fn new(limits: SplitLimits) -> Self {
+		Self { limits, pending: HashMap::new() }
+	}
+
+	pub fn limits(&self) -> &SplitLimits {
+		&self.limits
+	}
+
+	/// Number of split packets currently being reassembled.
+	pub fn pending(&self) -> usize {
+		self.pending.len()
+	}
+
+	/**
+		Adds a fragment, returning the reassembled packet if this was the last missing fragment.
+
+		Fragments violating the [`SplitLimits`] result in an error, and discard the split packet they belong to.
+	*/
+	pub fn insert(&mut self, info: SplitInfo, reliability: Reliability, ordering: Option<OrderingInfo>, data: Vec<u8>, now: u32) -> Res<Option<Reassembled>> {
+		if info.count == 0 || info.count > self.limits.max_fragment_count {
+			return Err(Error::new(InvalidData, format!("invalid split packet fragment count: {}", info.count)));
+		}
+		if info.index >= info.count {
+			return Err(Error::new(InvalidData, format!("invalid split packet fragment index: {} of {}", info.index, info.count)));
+		}
+		if !self.pending.contains_key(&info.id) && self.pending.len() >= self.limits.max_pending {
+			return Err(Error::new(InvalidData, "too many pending split packets"));
+		}
+		let pending = self.pending.entry(info.id).or_insert_with(|| PendingSplit { fragments: vec![None; info.count as usize], received: 0, size: 0, last_activity: now, reliability, ordering });
+		if pending.fragments.len() != info.count as usize {
+			self.pending.remove(&info.id);
+			return Err(Error::new(InvalidData, "inconsistent split packet fragment count"));
+		}
+		pending.last_activity = now;
+		let slot = &mut pending.fragments[info.index as usize];
+		if slot.is_some() {
+			return Ok(None);
+		}
+		pending.size += data.len();
+		if pending.size > self.limits.max_total_size {
+			self.pending.remove(&info.id);
+			return Err(Error::new(InvalidData, "split packet exceeds maximum size"));
+		}
+		*slot = Some(data);
+		pending.received += 1;
+		if pending.received < info.count {
+			return Ok(None);
+		}
+		let pending = self.pending.remove(&info.id).unwrap();
+		let mut data = Vec::with_capacity(pending.size);
+		for fragment in pending.fragments {
+			data.extend_from_slice(&fragment.unwrap());
+		}
+		Ok(Some(Reassembled { reliability: pending.reliability, ordering: pending.ordering, data }))
+	}
+
+	/// Discards incomplete split packets that timed out, returning how many were discarded.
+	pub fn expire(&mut self, now: u32) -> usize {
+		let timeout = self.limits.timeout;
+		let before = self.pending.len();
+		self.pending.retain(|_, pending| now.wrapping_sub(pending.last_activity) < timeout);
+		before - self.pending.len()
+	}
+}
+
+/**
+	Splits a payload into fragments of at most `max_fragment_size` bytes.
+
+	Split packets are always delivered reliably, so unreliable reliabilities are upgraded to their reliable counterpart, returned together with the fragments.
+*/
+pub fn fragment(data: &[u8], reliability: Reliability, max_fragment_size: usize) -> (Reliability, Vec<Vec<u8>>) {
+	let reliability = match reliability {
+		Reliability::Unreliable => Reliability::Reliable,
+		Reliability::UnreliableSequenced => Reliability::ReliableSequenced,
+		x => x,
+	};
+	(reliability, data.chunks(max_fragment_size.max(1)).map(|x| x.to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_reassembly() {
+		let data: Vec<u8> = (0..=255).cycle().take(3000).collect();
+		let (reliability, fragments) = fragment(&data, Reliability::Unreliable, 1000);
+		assert_eq!(reliability, Reliability::Reliable);
+		assert_eq!(fragments.len(), 3);
+		let mut reassembler = Reassembler::default();
+		let count = fragments.len() as u32;
+		let mut result = None;
+		for (index, fragment) in fragments.into_iter().enumerate().rev() {
+			assert!(result.is_none());
+			result = reassembler.insert(SplitInfo { id: 5, index: index as u32, count }, reliability, None, fragment, 0).unwrap();
+		}
+		assert_eq!(result.unwrap().data, data);
+		assert_eq!(reassembler.pending(), 0);
+	}
+
+	#[test]
+	fn test_limits() {
+		let mut reassembler = Reassembler::new(SplitLimits { max_fragment_count: 4, max_total_size: 10, max_pending: 1, timeout: 100 });
+		let rel = Reliability::Reliable;
+		assert!(reassembler.insert(SplitInfo { id: 0, index: 0, count: 5 }, rel, None, vec![0], 0).is_err());
+		assert!(reassembler.insert(SplitInfo { id: 0, index: 2, count: 2 }, rel, None, vec![0], 0).is_err());
+		assert!(reassembler.insert(SplitInfo { id: 0, index: 0, count: 2 }, rel, None, vec![0; 6], 0).unwrap().is_none());
+		assert!(reassembler.insert(SplitInfo { id: 1, index: 0, count: 2 }, rel, None, vec![0], 0).is_err());
+		assert!(reassembler.insert(SplitInfo { id: 0, index: 1, count: 2 }, rel, None, vec![0; 6], 0).is_err());
+		assert_eq!(reassembler.pending(), 0);
+		assert!(reassembler.insert(SplitInfo { id: 2, index: 0, count: 2 }, rel, None, vec![0], 0).unwrap().is_none());
+		assert_eq!(reassembler.expire(50), 0);
+		assert_eq!(reassembler.expire(100), 1);
+	}
+
+	#[test]
+	fn test_expire_wrap() {
+		let mut reassembler = Reassembler::new(SplitLimits { max_fragment_count: 4, max_total_size: 10, max_pending: 1, timeout: 100 });
+		assert!(reassembler.insert(SplitInfo { id: 0, index: 0, count: 2 }, Reliability::Reliable, None, vec![0], u32::MAX - 10).unwrap().is_none());
+		assert_eq!(reassembler.expire(50), 0);
+		assert_eq!(reassembler.expire(89), 1);
+	}
+}
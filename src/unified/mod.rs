@@ -6,17 +6,18 @@ use crate::chat::{GeneralChatMessage, PrivateChatMessage};
 use crate::common::ServiceId;
 use crate::general::client::GeneralMessage;
 use crate::raknet::client::{
-	replica::{ReplicaConstruction, ReplicaSerialization},
+	replica::{ReplicaConstruction, ReplicaDestruction, ReplicaScopeChange, ReplicaSerialization},
 	ConnectedPong, ConnectionRequestAccepted,
 };
 use crate::raknet::server::{ConnectionRequest, InternalPing, NewIncomingConnection};
 use crate::world::client::{AddFriendRequest, AddFriendResponse, BlueprintLoadItemResponse, BlueprintSaveResponse, CharacterCreateResponse, CharacterDeleteResponse, CharacterListResponse, ChatModerationString, CreateCharacter, FriendUpdateNotify, GetFriendsListResponse, GetIgnoreListResponse, LoadStaticZone, MinimumChatModeResponse, MinimumChatModeResponsePrivate, TeamInvite, TransferToWorld, UpdateFreeTrialStatus};
 use crate::world::gm::client::SubjectGameMessage;
 use crate::world::server::WorldMessage;
-use endio::{Deserialize, Serialize};
-use lu_packets_derive::MessageFromVariants;
+use endio::Serialize;
+use lu_packets_derive::{Dissect, EnumDeserialize, MessageFromVariants};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, MessageFromVariants)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, MessageFromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[repr(u8)]
 pub enum Message {
@@ -27,11 +28,15 @@ pub enum Message {
 	NewIncomingConnection(NewIncomingConnection) = 17,
 	DisconnectionNotification = 19,
 	ReplicaConstruction(ReplicaConstruction) = 36,
+	ReplicaDestruction(ReplicaDestruction) = 37,
+	ReplicaScopeChange(ReplicaScopeChange) = 38,
 	ReplicaSerialization(ReplicaSerialization) = 39,
+	ReplicaDownloadComplete = 41,
 	UserMessage(UserMessage) = 83,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, MessageFromVariants)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, MessageFromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum UserMessage {
 	General(GeneralMessage) = ServiceId::General as u16,
@@ -41,7 +46,8 @@ pub enum UserMessage {
 	Auth(AuthMessage) = ServiceId::Auth as u16,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, MessageFromVariants)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, MessageFromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[post_disc_padding = 1]
 #[repr(u32)]
@@ -68,7 +74,8 @@ pub enum AnyClientMessage {
 	UpdateFreeTrialStatus(UpdateFreeTrialStatus) = 62,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, MessageFromVariants)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, MessageFromVariants)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[post_disc_padding = 9]
 #[repr(u32)]
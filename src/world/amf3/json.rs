@@ -0,0 +1,368 @@
+/*!
+	A lossless mapping of [`Amf3`] to JSON, through [`serde`].
+
+	Values with a JSON equivalent map to it, all others are marked with a single-key object naming their type:
+
+	| AMF3 | JSON |
+	|---|---|
+	| `Undefined` | `{"undefined": null}` |
+	| `Null` | `null` |
+	| `False`, `True` | `false`, `true` |
+	| `Integer` | `{"int": 5}` |
+	| `Double` | `3.14`, or `{"double": "NaN"}` for `NaN`, `"Infinity"` and `"-Infinity"` |
+	| `String` | `"string"` |
+	| `XmlDocument`, `Xml` | `{"xml_document": "<a/>"}`, `{"xml": "<a/>"}` |
+	| `Date` | `{"date": 1234.0}`, in milliseconds since the Unix epoch |
+	| `Array` | `{"assoc": {"key": true}, "dense": [1.0]}`, with empty parts left out |
+	| `Object` | `{"object": {"class": "Name", "dynamic": true, "sealed": {"x": 1.0}, "members": {"y": 2.0}}}` |
+	| `ByteArray` | `{"bytes": [1, 2, 3]}` |
+
+	Numbers without a marker are always doubles, so integers written by hand need to be marked as well. When reading, a plain JSON array is accepted as an array with only a dense part.
+
+	The order of associative entries and members is kept. What isn't kept is which values were shared as AMF3 references, so converting to JSON and back yields separate copies of shared values.
+
+	Deserialization needs a self-describing format such as JSON. With the `json` feature, [`to_string`] and [`from_str`] convert to and from JSON text directly.
+
+	Example:
+
+	```
+	# #[cfg(feature = "json")] {
+	use lu_packets::amf3;
+	use lu_packets::world::amf3::json;
+
+	let amf3 = amf3! { "enabled": false, "count": 3 };
+	let text = json::to_string(&amf3).unwrap();
+	assert_eq!(text, r#"{"assoc":{"enabled":false,"count":{"int":3}}}"#);
+	assert_eq!(json::from_str(&text).unwrap(), amf3);
+	# }
+	```
+*/
+use std::convert::TryFrom;
+use std::fmt::{Formatter, Result as FmtResult};
+use std::sync::Arc;
+
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::{Amf3, Amf3Array, Amf3Date, Amf3Object, Amf3String, Amf3Traits};
+
+/// Converts AMF3 to JSON text.
+#[cfg(feature = "json")]
+pub fn to_string(amf3: &Amf3) -> serde_json::Result<String> {
+	serde_json::to_string(amf3)
+}
+
+/// Converts JSON text to AMF3.
+#[cfg(feature = "json")]
+pub fn from_str(json: &str) -> serde_json::Result<Amf3> {
+	serde_json::from_str(json)
+}
+
+fn serialize_tagged<S: Serializer, T: Serialize + ?Sized>(serializer: S, tag: &str, value: &T) -> Result<S::Ok, S::Error> {
+	let mut map = serializer.serialize_map(Some(1))?;
+	map.serialize_entry(tag, value)?;
+	map.end()
+}
+
+/// Associative entries, serialized as a map in their order.
+struct Entries<'a>(&'a [(Amf3String, Amf3)]);
+
+impl Serialize for Entries<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+	}
+}
+
+struct EntriesBuf(Vec<(Amf3String, Amf3)>);
+
+impl<'de> Deserialize<'de> for EntriesBuf {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct EntriesVisitor;
+
+		impl<'de> Visitor<'de> for EntriesVisitor {
+			type Value = EntriesBuf;
+
+			fn expecting(&self, f: &mut Formatter) -> FmtResult {
+				f.write_str("a map of AMF3 values")
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+				let mut entries = vec![];
+				while let Some(entry) = map.next_entry()? {
+					entries.push(entry);
+				}
+				Ok(EntriesBuf(entries))
+			}
+		}
+
+		deserializer.deserialize_map(EntriesVisitor)
+	}
+}
+
+impl Serialize for Amf3String {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for Amf3String {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let string = String::deserialize(deserializer)?;
+		Self::try_from(&string[..]).map_err(|_| de::Error::custom("string too long for AMF3"))
+	}
+}
+
+impl Serialize for Amf3Date {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(self.millis)
+	}
+}
+
+impl<'de> Deserialize<'de> for Amf3Date {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(Self { millis: f64::deserialize(deserializer)? })
+	}
+}
+
+impl Serialize for Amf3Array {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let len = !self.map.is_empty() as usize + !self.vec.is_empty() as usize;
+		let mut map = serializer.serialize_map(Some(len))?;
+		if !self.map.is_empty() {
+			map.serialize_entry("assoc", &Entries(&self.map))?;
+		}
+		if !self.vec.is_empty() {
+			map.serialize_entry("dense", &self.vec)?;
+		}
+		map.end()
+	}
+}
+
+/// Reads the parts of an array, after its first key has been read.
+fn visit_array<'de, A: MapAccess<'de>>(mut map: A, mut key: Option<String>) -> Result<Amf3Array, A::Error> {
+	let mut array = Amf3Array::new();
+	while let Some(k) = key {
+		match &k[..] {
+			"assoc" => array.map = map.next_value::<EntriesBuf>()?.0,
+			"dense" => array.vec = map.next_value()?,
+			_ => return Err(de::Error::unknown_field(&k, &["assoc", "dense"])),
+		}
+		key = map.next_key()?;
+	}
+	Ok(array)
+}
+
+struct ArrayVisitor;
+
+impl<'de> Visitor<'de> for ArrayVisitor {
+	type Value = Amf3Array;
+
+	fn expecting(&self, f: &mut Formatter) -> FmtResult {
+		f.write_str("an AMF3 array")
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut array = Amf3Array::new();
+		while let Some(value) = seq.next_element()? {
+			array.vec.push(value);
+		}
+		Ok(array)
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let key = map.next_key()?;
+		visit_array(map, key)
+	}
+}
+
+impl<'de> Deserialize<'de> for Amf3Array {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ArrayVisitor)
+	}
+}
+
+impl Serialize for Amf3Object {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if self.sealed.len() != self.traits.sealed_names.len() {
+			return Err(serde::ser::Error::custom("number of sealed values doesn't match traits"));
+		}
+		let sealed: Vec<_> = self.traits.sealed_names.iter().cloned().zip(self.sealed.iter().cloned()).collect();
+		let mut map = serializer.serialize_map(Some(3 + self.traits.is_dynamic as usize))?;
+		map.serialize_entry("class", &self.traits.class_name)?;
+		map.serialize_entry("dynamic", &self.traits.is_dynamic)?;
+		map.serialize_entry("sealed", &Entries(&sealed))?;
+		if self.traits.is_dynamic {
+			map.serialize_entry("members", &Entries(&self.dynamic))?;
+		}
+		map.end()
+	}
+}
+
+impl<'de> Deserialize<'de> for Amf3Object {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct ObjectVisitor;
+
+		impl<'de> Visitor<'de> for ObjectVisitor {
+			type Value = Amf3Object;
+
+			fn expecting(&self, f: &mut Formatter) -> FmtResult {
+				f.write_str("an AMF3 object")
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+				let mut class_name = None;
+				let mut is_dynamic = false;
+				let mut sealed = vec![];
+				let mut dynamic = vec![];
+				while let Some(key) = map.next_key::<String>()? {
+					match &key[..] {
+						"class" => class_name = Some(map.next_value()?),
+						"dynamic" => is_dynamic = map.next_value()?,
+						"sealed" => sealed = map.next_value::<EntriesBuf>()?.0,
+						"members" => dynamic = map.next_value::<EntriesBuf>()?.0,
+						_ => return Err(de::Error::unknown_field(&key, &["class", "dynamic", "sealed", "members"])),
+					}
+				}
+				let class_name = class_name.ok_or_else(|| de::Error::missing_field("class"))?;
+				if !is_dynamic && !dynamic.is_empty() {
+					return Err(de::Error::custom("members in object that isn't dynamic"));
+				}
+				let (sealed_names, sealed) = sealed.into_iter().unzip();
+				Ok(Amf3Object { traits: Arc::new(Amf3Traits { class_name, is_dynamic, sealed_names }), sealed, dynamic })
+			}
+		}
+
+		deserializer.deserialize_map(ObjectVisitor)
+	}
+}
+
+impl Serialize for Amf3 {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Undefined => serialize_tagged(serializer, "undefined", &()),
+			Self::Null => serializer.serialize_unit(),
+			Self::False => serializer.serialize_bool(false),
+			Self::True => serializer.serialize_bool(true),
+			Self::Integer(x) => serialize_tagged(serializer, "int", x),
+			Self::Double(x) if x.is_finite() => serializer.serialize_f64(*x),
+			Self::Double(x) if x.is_nan() => serialize_tagged(serializer, "double", "NaN"),
+			Self::Double(x) if *x > 0.0 => serialize_tagged(serializer, "double", "Infinity"),
+			Self::Double(_) => serialize_tagged(serializer, "double", "-Infinity"),
+			Self::String(x) => x.serialize(serializer),
+			Self::XmlDocument(x) => serialize_tagged(serializer, "xml_document", &**x),
+			Self::Date(x) => serialize_tagged(serializer, "date", &**x),
+			Self::Array(x) => x.serialize(serializer),
+			Self::Object(x) => serialize_tagged(serializer, "object", &**x),
+			Self::Xml(x) => serialize_tagged(serializer, "xml", &**x),
+			Self::ByteArray(x) => serialize_tagged(serializer, "bytes", &**x),
+		}
+	}
+}
+
+struct Amf3Visitor;
+
+impl<'de> Visitor<'de> for Amf3Visitor {
+	type Value = Amf3;
+
+	fn expecting(&self, f: &mut Formatter) -> FmtResult {
+		f.write_str("an AMF3 value")
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Amf3::Null)
+	}
+
+	fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Amf3::Null)
+	}
+
+	fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+		Ok(v.into())
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+		Ok(Amf3::Double(v as f64))
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+		Ok(Amf3::Double(v as f64))
+	}
+
+	fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+		Ok(Amf3::Double(v))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		Amf3String::try_from(v).map(Amf3::String).map_err(|_| E::custom("string too long for AMF3"))
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+		ArrayVisitor.visit_seq(seq).map(Amf3::from)
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let key: String = match map.next_key()? {
+			Some(x) => x,
+			None => return Ok(Amf3Array::new().into()),
+		};
+		let value = match &key[..] {
+			"assoc" | "dense" => return visit_array(map, Some(key)).map(Amf3::from),
+			"undefined" => {
+				map.next_value::<IgnoredAny>()?;
+				Amf3::Undefined
+			}
+			"int" => Amf3::Integer(map.next_value()?),
+			"double" => match &map.next_value::<String>()?[..] {
+				"NaN" => Amf3::Double(f64::NAN),
+				"Infinity" => Amf3::Double(f64::INFINITY),
+				"-Infinity" => Amf3::Double(f64::NEG_INFINITY),
+				_ => return Err(de::Error::custom("expected NaN, Infinity or -Infinity")),
+			},
+			"xml_document" => Amf3::XmlDocument(Arc::new(map.next_value()?)),
+			"date" => Amf3::Date(Arc::new(map.next_value()?)),
+			"object" => Amf3::Object(Arc::new(map.next_value()?)),
+			"xml" => Amf3::Xml(Arc::new(map.next_value()?)),
+			"bytes" => Amf3::ByteArray(Arc::new(map.next_value()?)),
+			_ => return Err(de::Error::unknown_field(&key, &["assoc", "dense", "undefined", "int", "double", "xml_document", "date", "object", "xml", "bytes"])),
+		};
+		if map.next_key::<IgnoredAny>()?.is_some() {
+			return Err(de::Error::custom("marked AMF3 value with more than one key"));
+		}
+		Ok(value)
+	}
+}
+
+impl<'de> Deserialize<'de> for Amf3 {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(Amf3Visitor)
+	}
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+	use std::convert::TryInto;
+
+	use super::*;
+
+	#[test]
+	fn test_roundtrip() {
+		let traits = Arc::new(Amf3Traits { class_name: "Pt".try_into().unwrap(), is_dynamic: true, sealed_names: vec!["x".try_into().unwrap()] });
+		let object = Amf3Object { traits, sealed: vec![Amf3::Integer(-1)], dynamic: vec![("y".try_into().unwrap(), Amf3::Null)] };
+		let mut array = Amf3Array::new();
+		array.insert("z".try_into().unwrap(), Amf3::Double(f64::INFINITY));
+		array.insert("a".try_into().unwrap(), Amf3::Undefined);
+		array.vec = vec![object.into(), Amf3Date { millis: 1.5 }.into(), vec![1, 2].into(), Amf3::Double(2.0), Amf3::Xml(Arc::new("<a/>".try_into().unwrap()))];
+		let amf3 = Amf3::from(array);
+
+		let text = to_string(&amf3).unwrap();
+		assert_eq!(text, r#"{"assoc":{"z":{"double":"Infinity"},"a":{"undefined":null}},"dense":[{"object":{"class":"Pt","dynamic":true,"sealed":{"x":{"int":-1}},"members":{"y":null}}},{"date":1.5},{"bytes":[1,2]},2.0,{"xml":"<a/>"}]}"#);
+		assert_eq!(from_str(&text).unwrap(), amf3);
+	}
+
+	#[test]
+	fn test_plain() {
+		assert_eq!(from_str("[1, \"a\", {}]").unwrap(), amf3![1.0, "a", amf3! {}]);
+		assert!(from_str(r#"{"int": 1, "date": 2}"#).is_err());
+	}
+}
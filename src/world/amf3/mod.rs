@@ -0,0 +1,839 @@
+//! (De-)serialization support for the [AMF3 format](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf).
+#[cfg(feature = "serde")]
+pub mod json;
+
+use std::borrow::Borrow;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::io::{Read, Result as Res, Write};
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
+use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
+use lu_packets_derive::GmParam;
+
+use crate::{limits, Error};
+
+/**
+	Reads AMF3 data, keeping the reference tables.
+
+	Entries of the object table are `None` while the object is being read, since references to an object from within itself can't be represented.
+*/
+struct Amf3Reader<'a, R: Read> {
+	inner: &'a mut R,
+	string_ref_table: Vec<Amf3String>,
+	object_ref_table: Vec<Option<Amf3>>,
+	traits_ref_table: Vec<Arc<Amf3Traits>>,
+	/// Number of arrays and objects currently being read.
+	depth: usize,
+}
+
+impl<R: Read> Amf3Reader<'_, R> {
+	fn object_ref(&self, index: u32) -> Res<Amf3> {
+		match self.object_ref_table.get(index as usize) {
+			Some(Some(x)) => Ok(x.clone()),
+			Some(None) => Err(Error::UnsupportedAmf3Reference { index }.into()),
+			None => Err(Error::InvalidAmf3Reference { index }.into()),
+		}
+	}
+}
+
+impl<R: Read> Read for Amf3Reader<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> Res<usize> {
+		self.inner.read(buf)
+	}
+}
+
+/**
+	Writes AMF3 data, keeping the reference tables.
+
+	Strings are referenced if an equal string has been written before. Objects and traits are referenced if the same allocation has been written before with the same marker, which reproduces the references of deserialized data. The marker is part of the key since an `XmlDocument` and an `Xml` can share an allocation, but would decode differently from a reference.
+*/
+struct Amf3Writer<'a, W: Write> {
+	inner: &'a mut W,
+	string_ref_table: Vec<Amf3String>,
+	object_ref_table: Vec<(u8, *const ())>,
+	traits_ref_table: Vec<*const Amf3Traits>,
+}
+
+impl<W: Write> Amf3Writer<'_, W> {
+	/// Writes a reference if the object has been written before, and adds it to the table otherwise. Returns whether a reference was written.
+	fn write_object_ref<T>(&mut self, marker: u8, object: &Arc<T>) -> Res<bool> {
+		let key = (marker, Arc::as_ptr(object) as *const ());
+		match self.object_ref_table.iter().position(|&x| x == key) {
+			Some(index) => {
+				LEWrite::write(self, &U29((index as u32) << 1))?;
+				Ok(true)
+			}
+			None => {
+				self.object_ref_table.push(key);
+				Ok(false)
+			}
+		}
+	}
+}
+
+impl<W: Write> Write for Amf3Writer<'_, W> {
+	fn write(&mut self, buf: &[u8]) -> Res<usize> {
+		self.inner.write(buf)
+	}
+
+	fn flush(&mut self) -> Res<()> {
+		self.inner.flush()
+	}
+}
+
+/// An error returned when an integer is too large to be represented by an `U29`.
+#[derive(Debug)]
+pub struct U29Error;
+
+/// An unsigned integer whose serialized form has variable length, with a maximum value of 2^29-1. [`See spec section 1.3.1`](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf#%5B%7B%22num%22%3A18%2C%22gen%22%3A0%7D%2C%7B%22name%22%3A%22XYZ%22%7D%2C88%2C643%2C0%5D).
+#[derive(Debug)]
+struct U29(u32);
+
+/// Converts the value to a [`U29`] if it is less than 2^29, returns [`U29Error`] otherwise.
+impl TryFrom<usize> for U29 {
+	type Error = U29Error;
+
+	fn try_from(value: usize) -> Result<Self, Self::Error> {
+		if value >= 1 << 29 {
+			Err(U29Error)
+		} else {
+			Ok(Self(value as u32))
+		}
+	}
+}
+
+impl<R: Read> Deserialize<LE, R> for U29 {
+	fn deserialize(reader: &mut R) -> Res<Self> {
+		let mut value = 0;
+		for _ in 0..3 {
+			let byte: u8 = LERead::read(reader)?;
+			let byte = byte as u32;
+			value = (value << 7) | (byte & 0x7f);
+			if byte & 0x80 == 0 {
+				return Ok(Self(value));
+			}
+		}
+		let byte: u8 = LERead::read(reader)?;
+		let byte = byte as u32;
+		value = (value << 8) | byte;
+		Ok(Self(value))
+	}
+}
+
+impl<'a, W: Write> Serialize<LE, W> for &'a U29 {
+	fn serialize(self, writer: &mut W) -> Res<()> {
+		let v = self.0;
+		if v <= 0x7f {
+			LEWrite::write(writer, v as u8)
+		} else if v <= 0x3fff {
+			LEWrite::write(writer, (v >> 7) as u8 | 0x80)?;
+			LEWrite::write(writer, v as u8 & 0x7f)
+		} else if v <= 0x1fffff {
+			LEWrite::write(writer, (v >> 14) as u8 | 0x80)?;
+			#[rustfmt::skip]
+			LEWrite::write(writer, (v >> 7 ) as u8 | 0x80)?;
+			LEWrite::write(writer, v as u8 & 0x7f)
+		} else {
+			LEWrite::write(writer, (v >> 22) as u8 | 0x80)?;
+			LEWrite::write(writer, (v >> 15) as u8 | 0x80)?;
+			#[rustfmt::skip]
+			LEWrite::write(writer, (v >> 8 ) as u8 | 0x80)?;
+			LEWrite::write(writer, v as u8)
+		}
+	}
+}
+
+/**
+	A string with a maximum length of 2^29-1.
+
+	[See spec section 3.8 for more](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf#%5B%7B%22num%22%3A22%2C%22gen%22%3A0%7D%2C%7B%22name%22%3A%22XYZ%22%7D%2C88%2C196%2C0%5D).
+*/
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Amf3String(String);
+
+impl Debug for Amf3String {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		self.0.fmt(f)
+	}
+}
+
+impl Borrow<str> for Amf3String {
+	fn borrow(&self) -> &str {
+		&self.0[..]
+	}
+}
+
+impl TryFrom<&str> for Amf3String {
+	type Error = U29Error;
+
+	fn try_from(string: &str) -> Result<Self, Self::Error> {
+		if string.len() & (1 << 29) != 0 {
+			Err(U29Error)
+		} else {
+			Ok(Self(string.into()))
+		}
+	}
+}
+
+impl<R: Read> Deserialize<LE, Amf3Reader<'_, R>> for Amf3String {
+	fn deserialize(reader: &mut Amf3Reader<'_, R>) -> Res<Self> {
+		let value_and_is_inline: U29 = LERead::read(reader)?;
+		let is_inline = value_and_is_inline.0 & 0x01 == 1;
+		let value = value_and_is_inline.0 >> 1;
+		let string = if !is_inline {
+			let index = value;
+			match reader.string_ref_table.get(index as usize) {
+				Some(x) => x.0.clone(),
+				None => return Err(Error::InvalidAmf3Reference { index }.into()),
+			}
+		} else {
+			let length = limits::check_string("Amf3String", value as u64)?;
+			let vec = limits::read_bytes(reader, length)?;
+
+			let string = match String::from_utf8(vec) {
+				Ok(x) => x,
+				Err(_) => return Err(Error::InvalidString { type_name: "Amf3String", reason: "not valid utf8" }.into()),
+			};
+			if string != "" {
+				reader.string_ref_table.push(Self(string.clone()));
+			}
+			string
+		};
+
+		Ok(Self(string))
+	}
+}
+
+impl<'a, W: Write> Serialize<LE, Amf3Writer<'_, W>> for &'a Amf3String {
+	fn serialize(self, writer: &mut Amf3Writer<'_, W>) -> Res<()> {
+		if self.0 == "" {
+			let length_and_is_inline = U29(1);
+			return LEWrite::write(writer, &length_and_is_inline);
+		}
+		match writer.string_ref_table.iter().position(|x| x == self) {
+			Some(index) => {
+				let index_and_is_inline = U29((index as u32) << 1);
+				LEWrite::write(writer, &index_and_is_inline)
+			}
+			None => {
+				let length_and_is_inline = U29((self.0.len() as u32) << 1 | 1);
+				LEWrite::write(writer, &length_and_is_inline)?;
+				writer.string_ref_table.push(Amf3String(self.0.clone()));
+				Write::write_all(writer, self.0.as_bytes())
+			}
+		}
+	}
+}
+
+/**
+	Both a dense and associative array at the same time.
+
+	The associative part keeps the order of its entries, so that arrays are written the same way they were read. Equality doesn't depend on this order.
+
+	[See spec section 3.11 for more](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf#%5B%7B%22num%22%3A24%2C%22gen%22%3A0%7D%2C%7B%22name%22%3A%22XYZ%22%7D%2C88%2C720%2C0%5D).
+*/
+#[derive(Clone, Default)]
+pub struct Amf3Array {
+	pub map: Vec<(Amf3String, Amf3)>,
+	pub vec: Vec<Amf3>,
+}
+
+impl Amf3Array {
+	pub fn new() -> Self {
+		Self { map: vec![], vec: vec![] }
+	}
+
+	/// The value of a key, or `None` if the key isn't present.
+	pub fn get(&self, key: &str) -> Option<&Amf3> {
+		self.map.iter().find(|(k, _)| k.0 == key).map(|(_, v)| v)
+	}
+
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut Amf3> {
+		self.map.iter_mut().find(|(k, _)| k.0 == key).map(|(_, v)| v)
+	}
+
+	/// Sets the value of a key, returning the previous value if the key was present. New keys are added at the end.
+	pub fn insert(&mut self, key: Amf3String, value: Amf3) -> Option<Amf3> {
+		match self.get_mut(&key.0) {
+			Some(x) => Some(std::mem::replace(x, value)),
+			None => {
+				self.map.push((key, value));
+				None
+			}
+		}
+	}
+}
+
+/// The dense parts are compared in order, the associative parts regardless of order.
+impl PartialEq for Amf3Array {
+	fn eq(&self, other: &Self) -> bool {
+		let contains = |a: &Self, b: &Self| a.map.iter().all(|entry| b.map.contains(entry));
+		self.vec == other.vec && self.map.len() == other.map.len() && contains(self, other) && contains(other, self)
+	}
+}
+
+impl Debug for Amf3Array {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		let map_empty = self.map.is_empty();
+		let vec_empty = self.vec.is_empty();
+		if !map_empty && vec_empty {
+			write!(f, "amf3! ")?;
+			f.debug_map().entries(self.map.iter().map(|(k, v)| (k, v))).finish()
+		} else if map_empty && !vec_empty {
+			write!(f, "amf3! ")?;
+			self.vec.fmt(f)
+		} else if map_empty && vec_empty {
+			write!(f, "amf3! {{}}")
+		} else {
+			write!(f, "Amf3Array {{ map: {:?}, vec: {:?} }}", self.map, self.vec)
+		}
+	}
+}
+
+impl Index<usize> for Amf3Array {
+	type Output = Amf3;
+
+	fn index(&self, index: usize) -> &Self::Output {
+		&self.vec[index]
+	}
+}
+
+impl IndexMut<usize> for Amf3Array {
+	fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+		&mut self.vec[index]
+	}
+}
+
+/**
+	### Panics
+	Panics if the key isn't present, use [`Amf3Array::get`] to check.
+*/
+impl Index<&Amf3String> for Amf3Array {
+	type Output = Amf3;
+
+	fn index(&self, key: &Amf3String) -> &Amf3 {
+		&self[&key.0[..]]
+	}
+}
+
+/**
+	### Panics
+	Panics if the key isn't present, use [`Amf3Array::get`] to check.
+*/
+impl Index<&str> for Amf3Array {
+	type Output = Amf3;
+
+	fn index(&self, key: &str) -> &Amf3 {
+		self.get(key).expect("key not present in Amf3Array")
+	}
+}
+
+fn deser_array<R: Read>(reader: &mut Amf3Reader<'_, R>, length: u32) -> Res<Amf3Array> {
+	let mut map = vec![];
+	loop {
+		let key: Amf3String = LERead::read(reader)?;
+		if key.0 == "" {
+			break;
+		}
+		let value = deser_amf3(reader)?;
+		map.push((key, value));
+	}
+	let length = limits::check_vec("Amf3Array", length as u64)?;
+	let mut vec = limits::vec_with_capacity(length);
+	for _ in 0..length {
+		let value = deser_amf3(reader)?;
+		vec.push(value);
+	}
+	Ok(Amf3Array { map, vec })
+}
+
+fn ser_array<W: Write>(writer: &mut Amf3Writer<'_, W>, array: &Amf3Array) -> Res<()> {
+	let length_and_is_inline = U29((array.vec.len() as u32) << 1 | 1);
+	LEWrite::write(writer, &length_and_is_inline)?;
+	for (key, value) in &array.map {
+		LEWrite::write(writer, key)?;
+		ser_amf3(writer, value)?;
+	}
+	LEWrite::write(writer, &Amf3String("".into()))?;
+	for value in &array.vec {
+		ser_amf3(writer, value)?;
+	}
+	Ok(())
+}
+
+/**
+	The class of an [`Amf3Object`]: its name and the names of its sealed members.
+
+	Objects sharing the same `Arc` of traits are written with a reference to the traits after the first.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Amf3Traits {
+	/// Empty for anonymous objects.
+	pub class_name: Amf3String,
+	pub is_dynamic: bool,
+	pub sealed_names: Vec<Amf3String>,
+}
+
+/**
+	An object with sealed members described by its traits, and dynamic members if the traits allow them.
+
+	Externalizable objects, which are serialized by custom code of their class, aren't supported.
+
+	[See spec section 3.12 for more](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf).
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Amf3Object {
+	pub traits: Arc<Amf3Traits>,
+	/// The values of the sealed members, in the order of [`Amf3Traits::sealed_names`].
+	pub sealed: Vec<Amf3>,
+	pub dynamic: Vec<(Amf3String, Amf3)>,
+}
+
+fn deser_object<R: Read>(reader: &mut Amf3Reader<'_, R>, header: u32) -> Res<Amf3Object> {
+	let traits = if header & 0x01 == 0 {
+		let index = header >> 1;
+		match reader.traits_ref_table.get(index as usize) {
+			Some(x) => x.clone(),
+			None => return Err(Error::InvalidAmf3Reference { index }.into()),
+		}
+	} else {
+		if header & 0x02 != 0 {
+			return Err(Error::Malformed { type_name: "Amf3Object", reason: "externalizable objects aren't supported" }.into());
+		}
+		let is_dynamic = header & 0x04 != 0;
+		let sealed_count = header >> 3;
+		let class_name = LERead::read(reader)?;
+		let mut sealed_names = vec![];
+		for _ in 0..sealed_count {
+			sealed_names.push(LERead::read(reader)?);
+		}
+		let traits = Arc::new(Amf3Traits { class_name, is_dynamic, sealed_names });
+		reader.traits_ref_table.push(traits.clone());
+		traits
+	};
+	let mut sealed = Vec::with_capacity(traits.sealed_names.len());
+	for _ in 0..traits.sealed_names.len() {
+		sealed.push(deser_amf3(reader)?);
+	}
+	let mut dynamic = vec![];
+	if traits.is_dynamic {
+		loop {
+			let key: Amf3String = LERead::read(reader)?;
+			if key.0 == "" {
+				break;
+			}
+			let value = deser_amf3(reader)?;
+			dynamic.push((key, value));
+		}
+	}
+	Ok(Amf3Object { traits, sealed, dynamic })
+}
+
+fn ser_object<W: Write>(writer: &mut Amf3Writer<'_, W>, object: &Amf3Object) -> Res<()> {
+	let traits = &object.traits;
+	if object.sealed.len() != traits.sealed_names.len() {
+		return Err(Error::Malformed { type_name: "Amf3Object", reason: "number of sealed values doesn't match traits" }.into());
+	}
+	if !traits.is_dynamic && !object.dynamic.is_empty() {
+		return Err(Error::Malformed { type_name: "Amf3Object", reason: "dynamic members in object with non-dynamic traits" }.into());
+	}
+	let ptr = Arc::as_ptr(traits);
+	match writer.traits_ref_table.iter().position(|&x| x == ptr) {
+		Some(index) => LEWrite::write(writer, &U29((index as u32) << 2 | 0x01))?,
+		None => {
+			writer.traits_ref_table.push(ptr);
+			let header = (traits.sealed_names.len() as u32) << 4 | (traits.is_dynamic as u32) << 3 | 0x03;
+			LEWrite::write(writer, &U29(header))?;
+			LEWrite::write(writer, &traits.class_name)?;
+			for name in &traits.sealed_names {
+				LEWrite::write(writer, name)?;
+			}
+		}
+	}
+	for value in &object.sealed {
+		ser_amf3(writer, value)?;
+	}
+	if traits.is_dynamic {
+		for (key, value) in &object.dynamic {
+			LEWrite::write(writer, key)?;
+			ser_amf3(writer, value)?;
+		}
+		LEWrite::write(writer, &Amf3String("".into()))?;
+	}
+	Ok(())
+}
+
+/// A point in time, in milliseconds since the Unix epoch, in UTC.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Amf3Date {
+	pub millis: f64,
+}
+
+/**
+	A type that can be (de-)serialized in the AMF3 format.
+
+	Types that go into the object reference table are kept in an [`Arc`]. When serializing, a value sharing its `Arc` with a value written before is written as a reference, so deserialized data is written with the same references it was read with. References from an object to itself can't be represented and fail to deserialize.
+
+	Integers outside the 29-bit range of AMF3 integers are written as doubles. Doubles are little-endian, as used by LU. The vector and dictionary types aren't supported.
+
+	[See spec section 3 for more](https://wwwimages2.adobe.com/content/dam/acom/en/devnet/pdf/amf-file-format-spec.pdf#%5B%7B%22num%22%3A20%2C%22gen%22%3A0%7D%2C%7B%22name%22%3A%22XYZ%22%7D%2C88%2C305%2C0%5D).
+*/
+#[derive(Clone, GmParam, PartialEq)]
+#[repr(u8)]
+pub enum Amf3 {
+	Undefined = 0,
+	Null = 1,
+	False = 2,
+	True = 3,
+	Integer(i32) = 4,
+	Double(f64) = 5,
+	String(Amf3String) = 6,
+	XmlDocument(Arc<Amf3String>) = 7,
+	Date(Arc<Amf3Date>) = 8,
+	Array(Arc<Amf3Array>) = 9,
+	Object(Arc<Amf3Object>) = 10,
+	Xml(Arc<Amf3String>) = 11,
+	ByteArray(Arc<Vec<u8>>) = 12,
+}
+
+impl Amf3 {
+	fn marker(&self) -> u8 {
+		match self {
+			Self::Undefined => 0,
+			Self::Null => 1,
+			Self::False => 2,
+			Self::True => 3,
+			Self::Integer(_) => 4,
+			Self::Double(_) => 5,
+			Self::String(_) => 6,
+			Self::XmlDocument(_) => 7,
+			Self::Date(_) => 8,
+			Self::Array(_) => 9,
+			Self::Object(_) => 10,
+			Self::Xml(_) => 11,
+			Self::ByteArray(_) => 12,
+		}
+	}
+}
+
+impl Debug for Amf3 {
+	#[rustfmt::skip]
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Self::Undefined      => write!(f, "Amf3::Undefined"),
+			Self::Null           => write!(f, "Amf3::Null"),
+			Self::False          => write!(f, "false"),
+			Self::True           => write!(f, "true"),
+			Self::Integer(x)     => x.fmt(f),
+			Self::Double(x)      => x.fmt(f),
+			Self::String(x)      => x.fmt(f),
+			Self::XmlDocument(x) => f.debug_tuple("XmlDocument").field(x).finish(),
+			Self::Date(x)        => x.fmt(f),
+			Self::Array (x)      => x.fmt(f),
+			Self::Object(x)      => x.fmt(f),
+			Self::Xml(x)         => f.debug_tuple("Xml").field(x).finish(),
+			Self::ByteArray(x)   => f.debug_tuple("ByteArray").field(x).finish(),
+		}
+	}
+}
+
+impl<R: Read> Deserialize<LE, R> for Amf3 {
+	fn deserialize(reader: &mut R) -> Res<Self> {
+		let mut reader = Amf3Reader { inner: reader, string_ref_table: vec![], object_ref_table: vec![], traits_ref_table: vec![], depth: 0 };
+		deser_amf3(&mut reader)
+	}
+}
+
+fn deser_amf3<R: Read>(reader: &mut Amf3Reader<R>) -> Res<Amf3> {
+	let disc: u8 = LERead::read(reader)?;
+	Ok(match disc {
+		0 => Amf3::Undefined,
+		1 => Amf3::Null,
+		2 => Amf3::False,
+		3 => Amf3::True,
+		4 => {
+			let value: U29 = LERead::read(reader)?;
+			// sign-extend from 29 bits
+			Amf3::Integer(((value.0 << 3) as i32) >> 3)
+		}
+		5 => Amf3::Double(LERead::read(reader)?),
+		6 => Amf3::String(LERead::read(reader)?),
+		7..=12 => deser_ref_type(reader, disc)?,
+		_ => return Err(Error::UnknownDiscriminant { type_name: "Amf3", value: disc as u64 }.into()),
+	})
+}
+
+/// Reads a value of a type that goes into the object reference table.
+fn deser_ref_type<R: Read>(reader: &mut Amf3Reader<R>, disc: u8) -> Res<Amf3> {
+	let value_and_is_inline: U29 = LERead::read(reader)?;
+	let value = value_and_is_inline.0 >> 1;
+	if value_and_is_inline.0 & 0x01 == 0 {
+		let object = reader.object_ref(value)?;
+		if object.marker() != disc {
+			return Err(Error::Malformed { type_name: "Amf3", reason: "reference to object of different type" }.into());
+		}
+		return Ok(object);
+	}
+	let index = reader.object_ref_table.len();
+	reader.object_ref_table.push(None);
+	let object = match disc {
+		7 | 11 => {
+			let vec = limits::read_bytes(reader, limits::check_string("Amf3String", value as u64)?)?;
+			let string = match String::from_utf8(vec) {
+				Ok(x) => Arc::new(Amf3String(x)),
+				Err(_) => return Err(Error::InvalidString { type_name: "Amf3String", reason: "not valid utf8" }.into()),
+			};
+			if disc == 7 {
+				Amf3::XmlDocument(string)
+			} else {
+				Amf3::Xml(string)
+			}
+		}
+		8 => Amf3::Date(Arc::new(Amf3Date { millis: LERead::read(reader)? })),
+		9 | 10 => {
+			reader.depth += 1;
+			limits::check("Amf3", "max_amf3_depth", |x| x.max_amf3_depth, reader.depth as u64)?;
+			let object = if disc == 9 { Amf3::Array(Arc::new(deser_array(reader, value)?)) } else { Amf3::Object(Arc::new(deser_object(reader, value)?)) };
+			reader.depth -= 1;
+			object
+		}
+		12 => Amf3::ByteArray(Arc::new(limits::read_bytes(reader, limits::check_string("Amf3ByteArray", value as u64)?)?)),
+		_ => unreachable!(),
+	};
+	reader.object_ref_table[index] = Some(object.clone());
+	Ok(object)
+}
+
+impl<'a, W: Write> Serialize<LE, W> for &'a Amf3 {
+	fn serialize(self, writer: &mut W) -> Res<()> {
+		let mut writer = Amf3Writer { inner: writer, string_ref_table: vec![], object_ref_table: vec![], traits_ref_table: vec![] };
+		ser_amf3(&mut writer, self)
+	}
+}
+
+/// Dissected as a single value, since references to earlier values depend on the whole message.
+impl crate::dissect::Dissect for Amf3 {
+	fn dissect(&self, dissector: &mut crate::dissect::Dissector) -> Res<()> {
+		dissector.decoded(self, |writer| LEWrite::write(writer, self), |reader| LERead::read::<Amf3>(reader).map(drop))
+	}
+}
+
+fn ser_amf3<W: Write>(writer: &mut Amf3Writer<W>, amf3: &Amf3) -> Res<()> {
+	if let Amf3::Integer(x) = amf3 {
+		if *x < -(1 << 28) || *x >= 1 << 28 {
+			return ser_amf3(writer, &Amf3::Double(*x as f64));
+		}
+	}
+	LEWrite::write(writer, amf3.marker())?;
+	match amf3 {
+		Amf3::Undefined | Amf3::Null | Amf3::False | Amf3::True => Ok(()),
+		Amf3::Integer(x) => LEWrite::write(writer, &U29(*x as u32 & 0x1fff_ffff)),
+		Amf3::Double(x) => LEWrite::write(writer, x),
+		Amf3::String(x) => LEWrite::write(writer, x),
+		Amf3::XmlDocument(x) | Amf3::Xml(x) => {
+			if !writer.write_object_ref(amf3.marker(), x)? {
+				LEWrite::write(writer, &U29((x.0.len() as u32) << 1 | 1))?;
+				Write::write_all(writer, x.0.as_bytes())?;
+			}
+			Ok(())
+		}
+		Amf3::Date(x) => {
+			if !writer.write_object_ref(amf3.marker(), x)? {
+				LEWrite::write(writer, &U29(1))?;
+				LEWrite::write(writer, x.millis)?;
+			}
+			Ok(())
+		}
+		Amf3::Array(x) => {
+			if !writer.write_object_ref(amf3.marker(), x)? {
+				ser_array(writer, x)?;
+			}
+			Ok(())
+		}
+		Amf3::Object(x) => {
+			if !writer.write_object_ref(amf3.marker(), x)? {
+				ser_object(writer, x)?;
+			}
+			Ok(())
+		}
+		Amf3::ByteArray(x) => {
+			if !writer.write_object_ref(amf3.marker(), x)? {
+				LEWrite::write(writer, &U29((x.len() as u32) << 1 | 1))?;
+				Write::write_all(writer, x)?;
+			}
+			Ok(())
+		}
+	}
+}
+
+impl From<bool> for Amf3 {
+	fn from(b: bool) -> Self {
+		if b {
+			Self::True
+		} else {
+			Self::False
+		}
+	}
+}
+
+impl From<i32> for Amf3 {
+	fn from(i: i32) -> Self {
+		Self::Integer(i)
+	}
+}
+
+impl From<f32> for Amf3 {
+	fn from(f: f32) -> Self {
+		Self::Double(f.into())
+	}
+}
+
+impl From<f64> for Amf3 {
+	fn from(f: f64) -> Self {
+		Self::Double(f)
+	}
+}
+
+impl TryFrom<&str> for Amf3 {
+	type Error = U29Error;
+
+	fn try_from(string: &str) -> Result<Self, Self::Error> {
+		Ok(Self::String(string.try_into()?))
+	}
+}
+
+impl From<Amf3Date> for Amf3 {
+	fn from(date: Amf3Date) -> Self {
+		Self::Date(Arc::new(date))
+	}
+}
+
+impl From<Amf3Array> for Amf3 {
+	fn from(array: Amf3Array) -> Self {
+		Self::Array(Arc::new(array))
+	}
+}
+
+impl From<Amf3Object> for Amf3 {
+	fn from(object: Amf3Object) -> Self {
+		Self::Object(Arc::new(object))
+	}
+}
+
+impl From<Vec<u8>> for Amf3 {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self::ByteArray(Arc::new(bytes))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryInto;
+	use std::sync::Arc;
+
+	use endio::{LERead, LEWrite};
+	use crate::{DecodeLimits, Error};
+	use super::{Amf3, Amf3Array, Amf3Date, U29};
+
+	#[test]
+	fn test_u29() {
+		for (bytes, integer) in &[(&b"\x7f"[..], 0x7f), (&b"\xa2\x43"[..], 4419), (&b"\x88\x00"[..], 1024), (&b"\xff\xff\x7e"[..], 0x1ffffe), (&b"\x80\xc0\x80\x00"[..], 0x200000), (&b"\xbf\xff\xff\xfe"[..], 0xffffffe)] {
+			let mut reader = &bytes[..];
+			let val: U29 = reader.read().unwrap();
+			assert_eq!(val.0, *integer);
+			let mut writer = vec![];
+			writer.write(&val).unwrap();
+			assert_eq!(&&writer[..], bytes);
+		}
+	}
+
+	#[test]
+	fn test_references() {
+		#[rustfmt::skip]
+		let bytes = b"\x09\x0f\x01\
+			\x0a\x13\x05Pt\x03x\x04\xff\xff\xff\xff\
+			\x0a\x01\x01\
+			\x0a\x02\
+			\x08\x01\x00\x00\x00\x00\x00\x00\x00\x00\
+			\x0c\x05\x01\x02\
+			\x00\
+			\x06\x00";
+		let mut reader = &bytes[..];
+		let amf3: Amf3 = reader.read().unwrap();
+		let array = match &amf3 {
+			Amf3::Array(x) => x,
+			_ => panic!(),
+		};
+		let (a, b, c) = match (&array[0], &array[1], &array[2]) {
+			(Amf3::Object(a), Amf3::Object(b), Amf3::Object(c)) => (a, b, c),
+			_ => panic!(),
+		};
+		assert_eq!(a.sealed, vec![Amf3::Integer(-1)]);
+		assert_eq!(b.sealed, vec![Amf3::Null]);
+		assert!(Arc::ptr_eq(&a.traits, &b.traits));
+		assert!(Arc::ptr_eq(a, c));
+		assert_eq!(array[3], Amf3::from(Amf3Date { millis: 0.0 }));
+		assert_eq!(array[4], Amf3::from(vec![1, 2]));
+		assert_eq!(array[5], Amf3::Undefined);
+		assert_eq!(array[6], Amf3::String("Pt".try_into().unwrap()));
+
+		let mut writer = vec![];
+		writer.write(&amf3).unwrap();
+		assert_eq!(&writer[..], &bytes[..]);
+	}
+
+	#[test]
+	fn test_xml_sharing_allocation() {
+		let string = Arc::new("x".try_into().unwrap());
+		let amf3 = Amf3::from(Amf3Array { map: vec![], vec: vec![Amf3::XmlDocument(string.clone()), Amf3::Xml(string.clone()), Amf3::Xml(string)] });
+		let mut writer = vec![];
+		writer.write(&amf3).unwrap();
+		assert_eq!(&writer[..], &b"\x09\x07\x01\x07\x03x\x0b\x03x\x0b\x04"[..]);
+		let mut reader = &writer[..];
+		assert_eq!(reader.read::<Amf3>().unwrap(), amf3);
+	}
+
+	#[test]
+	fn test_array_eq() {
+		let a = Amf3Array { map: vec![("a".try_into().unwrap(), Amf3::True), ("b".try_into().unwrap(), Amf3::False)], vec: vec![] };
+		let mut b = a.clone();
+		b.map.reverse();
+		assert_eq!(a, b);
+		b.map[0].1 = Amf3::True;
+		assert_ne!(a, b);
+	}
+
+	#[test]
+	fn test_self_reference() {
+		let mut reader = &b"\x09\x03\x01\x09\x00"[..];
+		let err = reader.read::<Amf3>().unwrap_err();
+		assert!(matches!(Error::from(err), Error::UnsupportedAmf3Reference { index: 0 }));
+	}
+
+	#[test]
+	fn test_limits() {
+		let nested = |depth| {
+			let mut bytes = b"\x09\x03\x01".repeat(depth);
+			bytes.push(0x01);
+			bytes
+		};
+		assert!(crate::from_slice::<Amf3>(&nested(64)).is_ok());
+		let err = crate::from_slice::<Amf3>(&nested(65)).unwrap_err();
+		assert!(matches!(err.root(), Error::LimitExceeded { limit: "max_amf3_depth", value: 65, .. }));
+
+		let huge_string = b"\x06\xff\xff\xff\xff";
+		let err = crate::from_slice::<Amf3>(huge_string).unwrap_err();
+		assert!(matches!(err.root(), Error::LimitExceeded { limit: "max_string_len", .. }));
+		let err = DecodeLimits::UNLIMITED.scope(|| crate::from_slice::<Amf3>(huge_string)).unwrap_err();
+		assert!(matches!(err.root(), Error::Io(_)));
+	}
+
+	#[test]
+	fn test_integer_range() {
+		let mut writer = vec![];
+		writer.write(&Amf3::Integer(1 << 28)).unwrap();
+		assert_eq!(writer, b"\x05\x00\x00\x00\x00\x00\x00\xb0\x41");
+	}
+}
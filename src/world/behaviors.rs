@@ -0,0 +1,689 @@
+/*!
+	Typed model behavior commands and UI messages.
+
+	The property behavior editor sends its commands through [`ControlBehaviors`], with the command name in `command` and its arguments in an untyped [`Amf3`] array. The server echoes the resulting behavior state back through [`UiMessageServerToSingleClient`]. This module models both directions as enums, [`BehaviorCommand`] and [`BehaviorUiMessage`], which convert from and to the untyped messages.
+
+	Example:
+
+	```
+	use std::convert::TryFrom;
+	use lu_packets::world::behaviors::{ActionContext, BehaviorCommand};
+	use lu_packets::world::gm::server::ControlBehaviors;
+
+	let command = BehaviorCommand::RemoveStrip { behavior_id: Some(10), context: ActionContext { state_id: 0, strip_id: 2 } };
+	let message = ControlBehaviors::from(&command);
+	assert_eq!(BehaviorCommand::try_from(&message).unwrap(), command);
+	```
+*/
+use std::borrow::Borrow;
+use std::convert::{TryFrom, TryInto};
+use std::io::{Error as IoError, Result as Res};
+use std::sync::Arc;
+
+use crate::common::{LuVarString, ObjId};
+use crate::world::amf3::{Amf3, Amf3Array, Amf3String};
+use crate::world::gm::client::UiMessageServerToSingleClient;
+use crate::world::gm::server::ControlBehaviors;
+use crate::{error, Error};
+
+/// ID of a behavior, sent as a decimal string.
+pub type BehaviorId = i32;
+/// ID of a state of a behavior, from 0 (the home state) to 6.
+pub type StateId = u32;
+/// ID of a strip within a state.
+pub type StripId = u32;
+
+fn malformed(type_name: &'static str, reason: &'static str) -> IoError {
+	Error::Malformed { type_name, reason }.into()
+}
+
+fn key(key: &str) -> Amf3String {
+	key.try_into().unwrap()
+}
+
+/// Read access to the arguments of a message, reporting missing or mistyped keys as errors.
+struct Args<'a> {
+	type_name: &'static str,
+	array: &'a Amf3Array,
+}
+
+impl<'a> Args<'a> {
+	fn new(type_name: &'static str, args: &'a Amf3) -> Res<Self> {
+		match args {
+			Amf3::Array(array) => Ok(Self { type_name, array }),
+			_ => Err(malformed(type_name, "arguments aren't an array")),
+		}
+	}
+
+	/// An error about the argument `key`, which is added to the error's path.
+	fn error(&self, key: &'static str, reason: &'static str) -> IoError {
+		error::push_frame(malformed(self.type_name, reason), key)
+	}
+
+	fn get(&self, key: &'static str) -> Res<&'a Amf3> {
+		self.array.get(key).ok_or_else(|| self.error(key, "missing argument"))
+	}
+
+	fn bool(&self, key: &'static str) -> Res<bool> {
+		match self.get(key)? {
+			Amf3::False => Ok(false),
+			Amf3::True => Ok(true),
+			_ => Err(self.error(key, "expected a bool")),
+		}
+	}
+
+	fn string(&self, key: &'static str) -> Res<&'a Amf3String> {
+		match self.get(key)? {
+			Amf3::String(x) => Ok(x),
+			_ => Err(self.error(key, "expected a string")),
+		}
+	}
+
+	fn array(&self, key: &'static str) -> Res<Args<'a>> {
+		Args::new(self.type_name, self.get(key)?)
+	}
+
+	fn number(&self, key: &'static str) -> Res<f64> {
+		match self.get(key)? {
+			Amf3::Integer(x) => Ok(*x as f64),
+			Amf3::Double(x) => Ok(*x),
+			_ => Err(self.error(key, "expected a number")),
+		}
+	}
+
+	/// A number that should be an integer in the range of `T`.
+	fn integer<T: TryFrom<i64>>(&self, key: &'static str) -> Res<T> {
+		let x = self.number(key)?;
+		if x.fract() != 0.0 || x < i64::MIN as f64 || x >= i64::MAX as f64 {
+			return Err(self.error(key, "expected an integer"));
+		}
+		T::try_from(x as i64).map_err(|_| self.error(key, "integer out of range"))
+	}
+
+	/// A number that should be a non-negative integer, such as an index or ID.
+	fn index(&self, key: &'static str) -> Res<u32> {
+		self.integer(key)
+	}
+
+	/// An integer sent as a decimal string.
+	fn decimal<T: std::str::FromStr>(&self, key: &'static str) -> Res<T> {
+		let string: &str = self.string(key)?.borrow();
+		string.parse().map_err(|_| self.error(key, "expected a decimal string"))
+	}
+
+	/// The behavior ID, if present and not empty.
+	fn behavior_id(&self, key: &'static str) -> Res<Option<BehaviorId>> {
+		match self.array.get(key) {
+			None => Ok(None),
+			Some(Amf3::String(x)) if Borrow::<str>::borrow(x).is_empty() => Ok(None),
+			Some(_) => self.decimal(key).map(Some),
+		}
+	}
+
+	fn context(&self, state_key: &'static str, strip_key: &'static str) -> Res<ActionContext> {
+		Ok(ActionContext { state_id: self.index(state_key)?, strip_id: self.index(strip_key)? })
+	}
+
+	fn ui(&self, key: &'static str) -> Res<Ui> {
+		let ui = self.array(key)?;
+		Ok(Ui { x: ui.number("x")?, y: ui.number("y")? })
+	}
+
+	fn action(&self, key: &'static str) -> Res<Action> {
+		Action::from_args(&self.array(key)?)
+	}
+
+	fn actions(&self, key: &'static str) -> Res<Vec<Action>> {
+		let actions = self.array(key)?;
+		actions.array.vec.iter().map(|x| Action::from_args(&Args::new(self.type_name, x)?)).collect()
+	}
+}
+
+/// Write access to the arguments of a message.
+#[derive(Default)]
+struct ArgsBuf(Amf3Array);
+
+impl ArgsBuf {
+	fn set(&mut self, k: &str, value: impl Into<Amf3>) -> &mut Self {
+		self.0.insert(key(k), value.into());
+		self
+	}
+
+	fn set_decimal(&mut self, k: &str, value: impl ToString) -> &mut Self {
+		self.set(k, Amf3::String(key(&value.to_string())))
+	}
+
+	fn set_behavior_id(&mut self, k: &str, value: Option<BehaviorId>) -> &mut Self {
+		if let Some(x) = value {
+			self.set_decimal(k, x);
+		}
+		self
+	}
+
+	fn set_context(&mut self, state_key: &str, strip_key: &str, context: &ActionContext) -> &mut Self {
+		self.set(state_key, context.state_id as f64).set(strip_key, context.strip_id as f64)
+	}
+
+	fn set_ui(&mut self, k: &str, ui: &Ui) -> &mut Self {
+		let mut buf = ArgsBuf::default();
+		buf.set("x", ui.x).set("y", ui.y);
+		self.set(k, buf)
+	}
+
+	fn set_actions(&mut self, k: &str, actions: &[Action]) -> &mut Self {
+		let mut array = Amf3Array::new();
+		array.vec = actions.iter().map(|x| x.to_args().into()).collect();
+		self.set(k, array)
+	}
+}
+
+impl From<ArgsBuf> for Amf3 {
+	fn from(buf: ArgsBuf) -> Self {
+		Amf3::Array(Arc::new(buf.0))
+	}
+}
+
+/// The state and strip an action or strip belongs to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionContext {
+	pub state_id: StateId,
+	pub strip_id: StripId,
+}
+
+/// Position of a strip in the behavior editor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ui {
+	pub x: f64,
+	pub y: f64,
+}
+
+/// Value of the parameter of an action.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionValue {
+	Double(f64),
+	String(Amf3String),
+}
+
+/// A block of a strip, such as a trigger like `OnInteract` or an effect like `FlyUp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Action {
+	/// Name of the block.
+	pub type_: Amf3String,
+	/// Name and value of the block's parameter, if it has one.
+	pub parameter: Option<(Amf3String, ActionValue)>,
+	/// Handle the client uses to refer to the block, usually empty.
+	pub callback_id: Amf3String,
+}
+
+impl Action {
+	fn from_args(args: &Args) -> Res<Self> {
+		let type_ = args.string("Type")?.clone();
+		let callback_id = match args.array.get("__callbackID__") {
+			Some(_) => args.string("__callbackID__")?.clone(),
+			None => key(""),
+		};
+		let mut parameter = None;
+		for (k, v) in &args.array.map {
+			let name: &str = k.borrow();
+			if name == "Type" || name == "__callbackID__" {
+				continue;
+			}
+			if parameter.is_some() {
+				return Err(malformed(args.type_name, "action with more than one parameter"));
+			}
+			let value = match v {
+				Amf3::Integer(x) => ActionValue::Double(*x as f64),
+				Amf3::Double(x) => ActionValue::Double(*x),
+				Amf3::String(x) => ActionValue::String(x.clone()),
+				_ => return Err(malformed(args.type_name, "action parameter isn't a number or string")),
+			};
+			parameter = Some((k.clone(), value));
+		}
+		Ok(Self { type_, parameter, callback_id })
+	}
+
+	fn to_args(&self) -> ArgsBuf {
+		let mut buf = ArgsBuf::default();
+		buf.set("Type", Amf3::String(self.type_.clone()));
+		match &self.parameter {
+			Some((k, ActionValue::Double(x))) => {
+				buf.0.insert(k.clone(), Amf3::Double(*x));
+			}
+			Some((k, ActionValue::String(x))) => {
+				buf.0.insert(k.clone(), Amf3::String(x.clone()));
+			}
+			None => {}
+		}
+		buf.set("__callbackID__", Amf3::String(self.callback_id.clone()));
+		buf
+	}
+}
+
+/**
+	A command of the behavior editor, sent in [`ControlBehaviors`].
+
+	Commands editing a behavior carry the ID of the behavior, which is `None` if the client left it out or empty, such as when editing a behavior that hasn't been assigned an ID yet.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum BehaviorCommand {
+	/// Requests [`BehaviorUiMessage::UpdateBehaviorList`].
+	SendBehaviorListToClient {
+		object_id: ObjId,
+	},
+	ModelTypeChanged {
+		model_type: i32,
+	},
+	ToggleExecutionUpdates {
+		enabled: bool,
+	},
+	AddStrip {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		ui: Ui,
+		actions: Vec<Action>,
+	},
+	RemoveStrip {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+	},
+	/// Moves the source strip to the end of the destination strip, or before `destination_action_index`.
+	MergeStrips {
+		behavior_id: Option<BehaviorId>,
+		source: ActionContext,
+		destination: ActionContext,
+		destination_action_index: u32,
+	},
+	/// Moves the actions of the source strip from `source_action_index` on to a new strip.
+	SplitStrip {
+		behavior_id: Option<BehaviorId>,
+		source: ActionContext,
+		source_action_index: u32,
+		destination: ActionContext,
+		destination_ui: Ui,
+	},
+	UpdateStripUi {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		ui: Ui,
+	},
+	AddAction {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		action_index: u32,
+		action: Action,
+	},
+	/// Moves the actions of the source strip from `source_action_index` on into the destination strip.
+	MigrateActions {
+		behavior_id: Option<BehaviorId>,
+		source: ActionContext,
+		source_action_index: u32,
+		destination: ActionContext,
+		destination_action_index: u32,
+	},
+	RearrangeStrip {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		source_action_index: u32,
+		destination_action_index: u32,
+	},
+	/// Adds a behavior from the inventory to the model.
+	Add {
+		behavior_id: Option<BehaviorId>,
+		behavior_index: u32,
+	},
+	/// Removes the actions of the strip from `action_index` on.
+	RemoveActions {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		action_index: u32,
+	},
+	Rename {
+		behavior_id: Option<BehaviorId>,
+		name: Amf3String,
+	},
+	/// Requests [`BehaviorUiMessage::UpdateBehaviorBlocks`].
+	SendBehaviorBlocksToClient {
+		behavior_id: Option<BehaviorId>,
+	},
+	MoveToInventory {
+		behavior_id: Option<BehaviorId>,
+		behavior_index: u32,
+	},
+	UpdateAction {
+		behavior_id: Option<BehaviorId>,
+		context: ActionContext,
+		action_index: u32,
+		action: Action,
+	},
+}
+
+impl BehaviorCommand {
+	/// The name of the command, as sent in [`ControlBehaviors::command`].
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::SendBehaviorListToClient { .. } => "sendBehaviorListToClient",
+			Self::ModelTypeChanged { .. } => "modelTypeChanged",
+			Self::ToggleExecutionUpdates { .. } => "toggleExecutionUpdates",
+			Self::AddStrip { .. } => "addStrip",
+			Self::RemoveStrip { .. } => "removeStrip",
+			Self::MergeStrips { .. } => "mergeStrips",
+			Self::SplitStrip { .. } => "splitStrip",
+			Self::UpdateStripUi { .. } => "updateStripUI",
+			Self::AddAction { .. } => "addAction",
+			Self::MigrateActions { .. } => "migrateActions",
+			Self::RearrangeStrip { .. } => "rearrangeStrip",
+			Self::Add { .. } => "add",
+			Self::RemoveActions { .. } => "removeActions",
+			Self::Rename { .. } => "rename",
+			Self::SendBehaviorBlocksToClient { .. } => "sendBehaviorBlocksToClient",
+			Self::MoveToInventory { .. } => "moveToInventory",
+			Self::UpdateAction { .. } => "updateAction",
+		}
+	}
+
+	/// Parses the arguments of the command with the given name.
+	pub fn from_args(command: &str, args: &Amf3) -> Res<Self> {
+		let args = Args::new("BehaviorCommand", args)?;
+		let id = || args.behavior_id("BehaviorID");
+		let context = || args.context("stateID", "stripID");
+		let source = || args.context("srcStateID", "srcStripID");
+		let destination = || args.context("dstStateID", "dstStripID");
+		Ok(match command {
+			"sendBehaviorListToClient" => Self::SendBehaviorListToClient { object_id: args.decimal("objectID")? },
+			"modelTypeChanged" => Self::ModelTypeChanged { model_type: args.integer("ModelType")? },
+			"toggleExecutionUpdates" => Self::ToggleExecutionUpdates { enabled: args.bool("enabled")? },
+			"addStrip" => Self::AddStrip { behavior_id: id()?, context: context()?, ui: args.ui("ui")?, actions: args.array("strip")?.actions("actions")? },
+			"removeStrip" => Self::RemoveStrip { behavior_id: id()?, context: context()? },
+			"mergeStrips" => Self::MergeStrips { behavior_id: id()?, source: source()?, destination: destination()?, destination_action_index: args.index("dstActionIndex")? },
+			"splitStrip" => Self::SplitStrip { behavior_id: id()?, source: source()?, source_action_index: args.index("srcActionIndex")?, destination: destination()?, destination_ui: args.ui("dstStripUI")? },
+			"updateStripUI" => Self::UpdateStripUi { behavior_id: id()?, context: context()?, ui: args.ui("ui")? },
+			"addAction" => Self::AddAction { behavior_id: id()?, context: context()?, action_index: args.index("actionIndex")?, action: args.action("action")? },
+			"migrateActions" => Self::MigrateActions { behavior_id: id()?, source: source()?, source_action_index: args.index("srcActionIndex")?, destination: destination()?, destination_action_index: args.index("dstActionIndex")? },
+			"rearrangeStrip" => Self::RearrangeStrip { behavior_id: id()?, context: context()?, source_action_index: args.index("srcActionIndex")?, destination_action_index: args.index("dstActionIndex")? },
+			"add" => Self::Add { behavior_id: id()?, behavior_index: args.index("BehaviorIndex")? },
+			"removeActions" => Self::RemoveActions { behavior_id: id()?, context: context()?, action_index: args.index("actionIndex")? },
+			"rename" => Self::Rename { behavior_id: id()?, name: args.string("Name")?.clone() },
+			"sendBehaviorBlocksToClient" => Self::SendBehaviorBlocksToClient { behavior_id: id()? },
+			"moveToInventory" => Self::MoveToInventory { behavior_id: id()?, behavior_index: args.index("BehaviorIndex")? },
+			"updateAction" => Self::UpdateAction { behavior_id: id()?, context: context()?, action_index: args.index("actionIndex")?, action: args.action("action")? },
+			_ => return Err(malformed("BehaviorCommand", "unknown command")),
+		})
+	}
+
+	/// The arguments of the command.
+	pub fn to_args(&self) -> Amf3 {
+		let mut buf = ArgsBuf::default();
+		match self {
+			Self::SendBehaviorListToClient { object_id } => {
+				buf.set_decimal("objectID", object_id);
+			}
+			Self::ModelTypeChanged { model_type } => {
+				buf.set("ModelType", *model_type as f64);
+			}
+			Self::ToggleExecutionUpdates { enabled } => {
+				buf.set("enabled", *enabled);
+			}
+			Self::AddStrip { behavior_id, context, ui, actions } => {
+				let mut strip = ArgsBuf::default();
+				strip.set_actions("actions", actions);
+				buf.set("strip", strip).set_ui("ui", ui).set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::RemoveStrip { behavior_id, context } => {
+				buf.set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::MergeStrips { behavior_id, source, destination, destination_action_index } => {
+				buf.set_context("srcStateID", "srcStripID", source).set_context("dstStateID", "dstStripID", destination).set("dstActionIndex", *destination_action_index as f64).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::SplitStrip { behavior_id, source, source_action_index, destination, destination_ui } => {
+				buf.set_context("srcStateID", "srcStripID", source).set("srcActionIndex", *source_action_index as f64).set_context("dstStateID", "dstStripID", destination).set_ui("dstStripUI", destination_ui).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::UpdateStripUi { behavior_id, context, ui } => {
+				buf.set_ui("ui", ui).set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::AddAction { behavior_id, context, action_index, action } | Self::UpdateAction { behavior_id, context, action_index, action } => {
+				buf.set("action", action.to_args()).set("actionIndex", *action_index as f64).set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::MigrateActions { behavior_id, source, source_action_index, destination, destination_action_index } => {
+				buf.set_context("srcStateID", "srcStripID", source).set("srcActionIndex", *source_action_index as f64).set_context("dstStateID", "dstStripID", destination).set("dstActionIndex", *destination_action_index as f64).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::RearrangeStrip { behavior_id, context, source_action_index, destination_action_index } => {
+				buf.set("srcActionIndex", *source_action_index as f64).set("dstActionIndex", *destination_action_index as f64).set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::Add { behavior_id, behavior_index } | Self::MoveToInventory { behavior_id, behavior_index } => {
+				buf.set("BehaviorIndex", *behavior_index as f64).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::RemoveActions { behavior_id, context, action_index } => {
+				buf.set("actionIndex", *action_index as f64).set_context("stateID", "stripID", context).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::Rename { behavior_id, name } => {
+				buf.set("Name", Amf3::String(name.clone())).set_behavior_id("BehaviorID", *behavior_id);
+			}
+			Self::SendBehaviorBlocksToClient { behavior_id } => {
+				buf.set_behavior_id("BehaviorID", *behavior_id);
+			}
+		}
+		buf.into()
+	}
+}
+
+impl TryFrom<&ControlBehaviors> for BehaviorCommand {
+	type Error = IoError;
+
+	fn try_from(message: &ControlBehaviors) -> Res<Self> {
+		Self::from_args(&String::from(&message.command), &message.args)
+	}
+}
+
+impl From<&BehaviorCommand> for ControlBehaviors {
+	fn from(command: &BehaviorCommand) -> Self {
+		Self { args: command.to_args(), command: LuVarString::try_from(command.name().as_bytes()).unwrap() }
+	}
+}
+
+/// Entry of [`BehaviorUiMessage::UpdateBehaviorList`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BehaviorInfo {
+	pub id: BehaviorId,
+	pub name: Amf3String,
+	pub is_locked: bool,
+	pub is_loot: bool,
+}
+
+/// A strip of [`StateInfo`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StripInfo {
+	pub id: StripId,
+	pub ui: Ui,
+	pub actions: Vec<Action>,
+}
+
+/// A state of [`BehaviorUiMessage::UpdateBehaviorBlocks`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateInfo {
+	pub id: StateId,
+	pub strips: Vec<StripInfo>,
+}
+
+/// A message echoing behavior state to the behavior editor, sent in [`UiMessageServerToSingleClient`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BehaviorUiMessage {
+	/// The behaviors of a model.
+	UpdateBehaviorList { object_id: ObjId, behaviors: Vec<BehaviorInfo> },
+	/// The states, strips and actions of a behavior.
+	UpdateBehaviorBlocks { object_id: ObjId, behavior_id: BehaviorId, states: Vec<StateInfo> },
+	/// The ID assigned to a behavior that was edited without one.
+	UpdateBehaviorId { object_id: ObjId, behavior_id: BehaviorId },
+}
+
+impl BehaviorUiMessage {
+	/// The name of the message, as sent in [`UiMessageServerToSingleClient::message_name`].
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::UpdateBehaviorList { .. } => "UpdateBehaviorList",
+			Self::UpdateBehaviorBlocks { .. } => "UpdateBehaviorBlocks",
+			Self::UpdateBehaviorId { .. } => "UpdateBehaviorID",
+		}
+	}
+
+	/// Parses the arguments of the message with the given name.
+	pub fn from_args(message_name: &str, args: &Amf3) -> Res<Self> {
+		let args = Args::new("BehaviorUiMessage", args)?;
+		Ok(match message_name {
+			"UpdateBehaviorList" => {
+				let behaviors = args
+					.array("behaviors")?
+					.array
+					.vec
+					.iter()
+					.map(|x| {
+						let info = Args::new(args.type_name, x)?;
+						Ok(BehaviorInfo { id: info.decimal("id")?, name: info.string("name")?.clone(), is_locked: info.bool("isLocked")?, is_loot: info.bool("isLoot")? })
+					})
+					.collect::<Res<_>>()?;
+				Self::UpdateBehaviorList { object_id: args.decimal("objectID")?, behaviors }
+			}
+			"UpdateBehaviorBlocks" => {
+				let states = args
+					.array("states")?
+					.array
+					.vec
+					.iter()
+					.map(|x| {
+						let state = Args::new(args.type_name, x)?;
+						let strips = state
+							.array("strips")?
+							.array
+							.vec
+							.iter()
+							.map(|x| {
+								let strip = Args::new(args.type_name, x)?;
+								Ok(StripInfo { id: strip.index("id")?, ui: strip.ui("ui")?, actions: strip.actions("actions")? })
+							})
+							.collect::<Res<_>>()?;
+						Ok(StateInfo { id: state.index("id")?, strips })
+					})
+					.collect::<Res<_>>()?;
+				Self::UpdateBehaviorBlocks { object_id: args.decimal("objectID")?, behavior_id: args.decimal("BehaviorID")?, states }
+			}
+			"UpdateBehaviorID" => Self::UpdateBehaviorId { object_id: args.decimal("objectID")?, behavior_id: args.decimal("behaviorID")? },
+			_ => return Err(malformed("BehaviorUiMessage", "unknown message")),
+		})
+	}
+
+	/// The arguments of the message.
+	pub fn to_args(&self) -> Amf3 {
+		let mut buf = ArgsBuf::default();
+		match self {
+			Self::UpdateBehaviorList { object_id, behaviors } => {
+				let mut array = Amf3Array::new();
+				array.vec = behaviors
+					.iter()
+					.map(|x| {
+						let mut info = ArgsBuf::default();
+						info.set_decimal("id", x.id).set("isLocked", x.is_locked).set("isLoot", x.is_loot).set("name", Amf3::String(x.name.clone()));
+						info.into()
+					})
+					.collect();
+				buf.set("behaviors", array).set_decimal("objectID", object_id);
+			}
+			Self::UpdateBehaviorBlocks { object_id, behavior_id, states } => {
+				let mut array = Amf3Array::new();
+				array.vec = states
+					.iter()
+					.map(|state| {
+						let mut strips = Amf3Array::new();
+						strips.vec = state
+							.strips
+							.iter()
+							.map(|strip| {
+								let mut info = ArgsBuf::default();
+								info.set("id", strip.id as f64).set_ui("ui", &strip.ui).set_actions("actions", &strip.actions);
+								info.into()
+							})
+							.collect();
+						let mut info = ArgsBuf::default();
+						info.set("id", state.id as f64).set("strips", strips);
+						info.into()
+					})
+					.collect();
+				buf.set_decimal("BehaviorID", behavior_id).set_decimal("objectID", object_id).set("states", array);
+			}
+			Self::UpdateBehaviorId { object_id, behavior_id } => {
+				buf.set_decimal("behaviorID", behavior_id).set_decimal("objectID", object_id);
+			}
+		}
+		buf.into()
+	}
+}
+
+impl TryFrom<&UiMessageServerToSingleClient> for BehaviorUiMessage {
+	type Error = IoError;
+
+	fn try_from(message: &UiMessageServerToSingleClient) -> Res<Self> {
+		Self::from_args(&String::from(&message.message_name), &message.args)
+	}
+}
+
+impl From<&BehaviorUiMessage> for UiMessageServerToSingleClient {
+	fn from(message: &BehaviorUiMessage) -> Self {
+		Self { args: message.to_args(), message_name: LuVarString::try_from(message.name().as_bytes()).unwrap() }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::{TryFrom, TryInto};
+
+	use crate::world::amf3::Amf3;
+	use crate::Error;
+	use crate::world::gm::client::UiMessageServerToSingleClient;
+	use crate::world::gm::server::ControlBehaviors;
+	use super::{Action, ActionContext, ActionValue, BehaviorCommand, BehaviorInfo, BehaviorUiMessage, StateInfo, StripInfo, Ui};
+
+	fn action(type_: &str, parameter: Option<(&str, ActionValue)>) -> Action {
+		Action { type_: type_.try_into().unwrap(), parameter: parameter.map(|(k, v)| (k.try_into().unwrap(), v)), callback_id: "".try_into().unwrap() }
+	}
+
+	#[test]
+	fn test_parse() {
+		let message = ControlBehaviors {
+			args: amf3! {
+				"BehaviorID": "10",
+				"actionIndex": 1.0,
+				"stateID": 0.0,
+				"stripID": 2.0,
+				"action": amf3! { "Type": "FlyUp", "Distance": 25.0, "__callbackID__": "" },
+			},
+			command: lu!(b"updateAction"),
+		};
+		let command = BehaviorCommand::try_from(&message).unwrap();
+		assert_eq!(command, BehaviorCommand::UpdateAction { behavior_id: Some(10), context: ActionContext { state_id: 0, strip_id: 2 }, action_index: 1, action: action("FlyUp", Some(("Distance", ActionValue::Double(25.0)))) });
+		assert_eq!(BehaviorCommand::from_args("toggleExecutionUpdates", &amf3! { "enabled": false }).unwrap(), BehaviorCommand::ToggleExecutionUpdates { enabled: false });
+		assert_eq!(BehaviorCommand::from_args("rename", &amf3! { "BehaviorID": "", "Name": "Dance" }).unwrap(), BehaviorCommand::Rename { behavior_id: None, name: "Dance".try_into().unwrap() });
+	}
+
+	#[test]
+	fn test_invalid() {
+		assert!(BehaviorCommand::from_args("removeStrip", &amf3! { "stateID": 0.0 }).is_err());
+		assert!(BehaviorCommand::from_args("removeStrip", &amf3! { "stateID": 0.5, "stripID": 1.0 }).is_err());
+		assert!(BehaviorCommand::from_args("removeStrip", &Amf3::Null).is_err());
+		assert!(BehaviorCommand::from_args("unknown", &amf3! {}).is_err());
+		assert!(BehaviorCommand::from_args("modelTypeChanged", &amf3! { "ModelType": 4294967296.0 }).is_err());
+		let err = Error::from(BehaviorCommand::from_args("removeStrip", &amf3! { "stateID": 0.0 }).unwrap_err());
+		assert_eq!(err.path(), &["stripID"]);
+		assert!(matches!(err.root(), Error::Malformed { reason: "missing argument", .. }));
+	}
+
+	#[test]
+	fn test_roundtrip() {
+		let command = BehaviorCommand::AddStrip { behavior_id: None, context: ActionContext { state_id: 1, strip_id: 0 }, ui: Ui { x: 103.0, y: 82.0 }, actions: vec![action("OnInteract", None), action("Smash", Some(("Force", ActionValue::Double(2.0))))] };
+		let message = ControlBehaviors::from(&command);
+		assert_eq!(BehaviorCommand::try_from(&message).unwrap(), command);
+
+		let message = BehaviorUiMessage::UpdateBehaviorBlocks { object_id: 1152921508901814230, behavior_id: 10, states: vec![StateInfo { id: 0, strips: vec![StripInfo { id: 0, ui: Ui { x: 0.0, y: 0.0 }, actions: vec![action("OnAttack", None)] }] }] };
+		assert_eq!(BehaviorUiMessage::try_from(&UiMessageServerToSingleClient::from(&message)).unwrap(), message);
+
+		let message = BehaviorUiMessage::UpdateBehaviorList { object_id: 1152921508901814230, behaviors: vec![BehaviorInfo { id: 10, name: "Dance".try_into().unwrap(), is_locked: false, is_loot: true }] };
+		assert_eq!(BehaviorUiMessage::from_args(message.name(), &message.to_args()).unwrap(), message);
+	}
+}
@@ -1,14 +1,16 @@
 //! Client-received world messages.
-use std::io::{Error, ErrorKind::InvalidData, Read, Write};
+use std::io::{Read, Write};
 use std::io::Result as Res;
 
 use endio::{Deserialize, LERead, LEWrite, Serialize};
 use endio::LittleEndian as LE;
-use lu_packets_derive::{MessageFromVariants, VariantTests};
+use lu_packets_derive::{Dissect, EnumDeserialize, MessageFromVariants, VariantTests};
 
+use crate::Error;
 use crate::chat::ChatChannel;
 use crate::chat::client::ChatMessage;
-use crate::common::{ObjId, LuString33, LuWString33, LuWString42, LVec, ServiceId};
+use crate::common::{ObjId, LuString33, LuWString33, LuWString42, LVec, LVecElement, ServiceId};
+use crate::dissect::Dissector;
 use crate::general::client::{DisconnectNotify, Handshake, GeneralMessage};
 use super::{Lot, lnv::LuNameValue, Vector3, ZoneId};
 use super::gm::client::SubjectGameMessage;
@@ -17,7 +19,8 @@ use super::gm::client::SubjectGameMessage;
 pub type Message = crate::raknet::client::Message<LuMessage>;
 
 /// All client-received LU messages from a world server.
-#[derive(Debug, Deserialize, MessageFromVariants, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, MessageFromVariants, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum LuMessage {
 	General(GeneralMessage) = ServiceId::General as u16,
@@ -44,7 +47,8 @@ impl From<DisconnectNotify> for Message {
 }
 
 /// All client-received world messages.
-#[derive(Debug, Deserialize, PartialEq, Serialize, MessageFromVariants, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, MessageFromVariants, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 #[post_disc_padding = 1]
 #[repr(u32)]
@@ -70,7 +74,8 @@ pub enum ClientMessage {
 	UpdateFreeTrialStatus(UpdateFreeTrialStatus) = 62,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum InstanceType {
 	Public,
@@ -101,7 +106,8 @@ pub enum InstanceType {
 
 	However, these are quite advanced architectures, and for now it is unlikely that any server project will actually pull these off.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LoadStaticZone {
 	/// ID of the zone to be loaded.
 	pub zone_id: ZoneId,
@@ -115,7 +121,8 @@ pub struct LoadStaticZone {
 	pub instance_type: InstanceType,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CreateCharacter {
 	pub data: LuNameValue,
 }
@@ -136,6 +143,7 @@ pub struct CreateCharacter {
 	The LU client can't handle sending more than four characters.
 */
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterListResponse {
 	/// Index into the list of characters below, specifying which character was used last.
 	pub selected_char: u8,
@@ -174,8 +182,22 @@ where
 	}
 }
 
+impl crate::dissect::Dissect for CharacterListResponse {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("length", &(self.chars.len() as u8))?;
+		dissector.field("selected_char", &self.selected_char)?;
+		dissector.node("chars", std::any::type_name::<Vec<CharListChar>>(), |dissector| {
+			for (i, chr) in self.chars.iter().enumerate() {
+				dissector.field(&i.to_string(), chr)?;
+			}
+			Ok(())
+		})
+	}
+}
+
 /// A character from the [`CharacterListResponse`] message.
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharListChar {
 	pub obj_id: ObjId,
 	#[padding = 4]
@@ -211,7 +233,8 @@ pub struct CharListChar {
 	### Response
 	None.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum CharacterCreateResponse {
 	/// The character has been successfully created.
@@ -238,7 +261,8 @@ pub enum CharacterCreateResponse {
 	### Response
 	None.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterDeleteResponse {
 	/// Whether the deletion was successful.
 	pub success: bool,
@@ -253,7 +277,8 @@ pub struct CharacterDeleteResponse {
 	### Response
 	Close the connection after the connection to the other instance has been established.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TransferToWorld {
 	/// The host to connect to.
 	pub redirect_ip: LuString33,
@@ -263,7 +288,8 @@ pub struct TransferToWorld {
 	pub is_maintenance_transfer: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum BlueprintSaveResponseType {
 	EverythingWorked,
@@ -283,20 +309,25 @@ pub enum BlueprintSaveResponseType {
 	FindMatchesFailed,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BlueprintSaveResponseModel {
 	pub blueprint_id: ObjId,
 	pub lxfml_compressed: LVec<u32, u8>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+impl LVecElement for BlueprintSaveResponseModel {}
+
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BlueprintSaveResponse {
 	pub local_id: ObjId,
 	pub reason_code: BlueprintSaveResponseType,
 	pub models: LVec<u32, BlueprintSaveResponseModel>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BlueprintLoadItemResponse {
 	pub success: bool,
 	pub item_id: ObjId,
@@ -315,7 +346,8 @@ pub struct BlueprintLoadItemResponse {
 	### Response
 	Respond with [`AddFriendResponse`](crate::chat::server::AddFriendResponse) once the user has made their choice.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddFriendRequest {
 	/// Name of the requesting character.
 	pub sender_name: LuWString33,
@@ -324,6 +356,7 @@ pub struct AddFriendRequest {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum AddFriendResponseType {
 	Accepted { is_online: bool, sender_id: ObjId, zone_id: ZoneId, is_best_friend: bool, is_free_trial: bool },
@@ -342,6 +375,7 @@ pub enum AddFriendResponseType {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddFriendResponse {
 	pub char_name: LuWString33,
 	pub response_type: AddFriendResponseType,
@@ -372,7 +406,7 @@ impl<R: Read> Deserialize<LE, R> for AddFriendResponse {
 				10 => AddFriendResponseType::Mythran,
 				11 => AddFriendResponseType::Cancelled,
 				12 => AddFriendResponseType::FriendIsFreeTrial,
-				_  => { return Err(Error::new(InvalidData, "invalid discriminant for AddFriendResponseType")) }
+				_  => { return Err(Error::UnknownDiscriminant { type_name: "AddFriendResponseType", value: disc as u64 }.into()) }
 			}
 		})
 	}
@@ -413,7 +447,30 @@ impl<'a, W: Write> Serialize<LE, W> for &'a AddFriendResponse {
 	}
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// The response type is dissected as its discriminant, followed by the fields of all variants, with default values if the variant doesn't have them.
+impl crate::dissect::Dissect for AddFriendResponse {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		let default_zone_id = ZoneId { map_id: 0, instance_id: 0, clone_id: 0 };
+		let (is_online, sender_id, zone_id, is_best_friend, is_free_trial) = match &self.response_type {
+			AddFriendResponseType::Accepted { is_online, sender_id, zone_id, is_best_friend, is_free_trial } => (is_online, sender_id, zone_id, is_best_friend, is_free_trial),
+			AddFriendResponseType::AlreadyFriend { is_best_friend } | AddFriendResponseType::GeneralError { is_best_friend } => (&false, &0, &default_zone_id, is_best_friend, &false),
+			_ => (&false, &0, &default_zone_id, &false, &false),
+		};
+		dissector.node("response_type", std::any::type_name::<AddFriendResponseType>(), |dissector| {
+			dissector.leaf(&self.response_type, 8);
+			Ok(())
+		})?;
+		dissector.field("is_online", is_online)?;
+		dissector.field("char_name", &self.char_name)?;
+		dissector.field("sender_id", sender_id)?;
+		dissector.field("zone_id", zone_id)?;
+		dissector.field("is_best_friend", is_best_friend)?;
+		dissector.field("is_free_trial", is_free_trial)
+	}
+}
+
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 6]
 pub struct FriendState {
 	pub is_online: bool,
@@ -425,7 +482,10 @@ pub struct FriendState {
 	pub char_name: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+impl LVecElement for FriendState {}
+
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 #[post_disc_padding = 2]
 pub enum GetFriendsListResponse {
@@ -433,7 +493,8 @@ pub enum GetFriendsListResponse {
 	GeneralError,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum FriendUpdateType {
 	Logout,
@@ -442,7 +503,8 @@ pub enum FriendUpdateType {
 	FreeTrialChange,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FriendUpdateNotify {
 	pub update_type: FriendUpdateType,
 	pub char_name: LuWString33,
@@ -451,14 +513,18 @@ pub struct FriendUpdateNotify {
 	pub is_free_trial: bool,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 6]
 pub struct IgnoreState {
 	pub object_id: ObjId,
 	pub char_name: LuWString33,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+impl LVecElement for IgnoreState {}
+
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 #[post_disc_padding = 2]
 pub enum GetIgnoreListResponse {
@@ -478,7 +544,8 @@ pub enum GetIgnoreListResponse {
 	### Response
 	Respond with [`TeamInviteResponse`](crate::chat::server::TeamInviteResponse) once the user has made their choice.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamInvite {
 	/// Name of the requesting character.
 	pub sender_name: LuWString33,
@@ -486,13 +553,15 @@ pub struct TeamInvite {
 	pub sender_id: ObjId,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MinimumChatModeResponse {
 	pub chat_mode: u8, // todo: type?
 	pub chat_channel: ChatChannel,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MinimumChatModeResponsePrivate {
 	pub chat_mode: u8, // todo: type?
 	pub chat_channel: ChatChannel,
@@ -500,13 +569,15 @@ pub struct MinimumChatModeResponsePrivate {
 	pub recipient_gm_level: u8,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModerationSpan {
 	pub start_index: u8,
 	pub length: u8,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ChatModerationString {
 	//#[padding=2]
 	pub request_id: u8,
@@ -547,7 +618,7 @@ impl<'a, W: Write + LEWrite> Serialize<LE, W> for &'a ChatModerationString {
 		LEWrite::write(writer, self.chat_mode)?;
 		LEWrite::write(writer, &self.whisper_name)?;
 		if self.spans.len() > 64 {
-			return Err(Error::new(InvalidData, "spans longer than 64"));
+			return Err(Error::LengthOverflow { type_name: "ChatModerationString", length: self.spans.len() as u64 }.into());
 		}
 		for span in &self.spans {
 			LEWrite::write(writer, span.start_index)?;
@@ -560,6 +631,25 @@ impl<'a, W: Write + LEWrite> Serialize<LE, W> for &'a ChatModerationString {
 	}
 }
 
+impl crate::dissect::Dissect for ChatModerationString {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("string_okay", &self.spans.is_empty())?;
+		dissector.field("source_id", &0u16)?;
+		dissector.field("request_id", &self.request_id)?;
+		dissector.field("chat_mode", &self.chat_mode)?;
+		dissector.field("whisper_name", &self.whisper_name)?;
+		dissector.node("spans", std::any::type_name::<Vec<ModerationSpan>>(), |dissector| {
+			for (i, span) in self.spans.iter().enumerate() {
+				dissector.field(&i.to_string(), span)?;
+			}
+			Ok(())
+		})?;
+		// unused spans are zeroed
+		dissector.skip(64u64.saturating_sub(self.spans.len() as u64) * 16);
+		Ok(())
+	}
+}
+
 /**
 	Notifies the client that its free trial status has changed.
 
@@ -572,8 +662,27 @@ impl<'a, W: Write + LEWrite> Serialize<LE, W> for &'a ChatModerationString {
 	### Response
 	None.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdateFreeTrialStatus {
 	/// Whether the player is on free trial.
 	pub is_free_trial: bool,
 }
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_json() {
+		let bin = &include_bytes!("tests/CharacterListResponse.bin")[..];
+		let msg: ClientMessage = crate::from_slice(bin).unwrap();
+		let json = serde_json::to_string(&msg).unwrap();
+		assert!(json.starts_with(r#"{"CharacterListResponse":{"selected_char":3,"chars":[{"obj_id":1152921506064087003,"char_name":"ShastaFantastic","pending_name":"","#));
+		let parsed: ClientMessage = serde_json::from_str(&json).unwrap();
+		assert_eq!(parsed, msg);
+		let mut out = vec![];
+		LEWrite::write(&mut out, &parsed).unwrap();
+		assert_eq!(out, bin);
+	}
+}
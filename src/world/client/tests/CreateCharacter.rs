@@ -4,11 +4,11 @@ ClientMessage::CreateCharacter(
 			"editor_level": 0i32,
 			"requiresrename": false,
 			"legoclub": false,
-			"levelid": 1000i64,
+			"levelid": 1000u64,
 			"position.z": -47.223167f32,
 			"rotation.y": 0.733435f32,
 			"position.x": -627.1862f32,
-			"accountID": 1267289i64,
+			"accountID": 1267289u64,
 			"rotation.x": 0.0f32,
 			"rotation.w": 0.6797596f32,
 			"name": "FeralRunningSidekick",
@@ -16,9 +16,9 @@ ClientMessage::CreateCharacter(
 			"freetrial": true,
 			"gmlevel": 0i32,
 			"rotation.z": 0.0f32,
-			"reputation": 0i64,
+			"reputation": 0u64,
 			"template": 1i32,
-			"objid": 1152921510115197038u64,
+			"objid": 1152921510115197038i64,
 			"xmlData": b"<obj v=\"1\"><mf hc=\"1\" hs=\"9\" hd=\"0\" t=\"15\" l=\"15\" hdc=\"0\" cd=\"21\" lh=\"38710288\" rh=\"38262980\" es=\"3\" ess=\"22\" ms=\"24\"/><char acct=\"1267289\" cc=\"0\" gm=\"0\" ft=\"1\"/><dest hm=\"4\" hc=\"4\" im=\"0\" ic=\"0\" am=\"0\" ac=\"0\" d=\"0\"/><inv><items><in t=\"0\"><i l=\"4511\" id=\"1152921510115197039\" s=\"0\" eq=\"1\"/><i l=\"2516\" id=\"1152921510115197040\" s=\"1\" eq=\"1\"/></in></items></inv><lvl l=\"1\" cv=\"1\" sb=\"500\"/></obj>",
 			"editor_enabled": false,
 			"position.y": 613.32623f32,
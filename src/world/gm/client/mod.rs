@@ -1,7 +1,7 @@
 use std::cmp::PartialEq;
 
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::{FromVariants, GameMessage, GmParam, VariantTests};
+use lu_packets_derive::{Dissect, EnumDeserialize, FromVariants, GameMessage, GmParam, VariantTests};
 
 use crate::common::{ObjId, OBJID_EMPTY};
 
@@ -10,13 +10,15 @@ use crate::world::amf3::Amf3;
 pub use super::{EquipInventory, InventoryType, KillType, UnEquipInventory, LootType, MissionState, PetNotificationType, MoveItemInInventory, MoveInventoryBatch, RemoveSkill, RemoveItemFromInventory, SetIgnoreProjectileCollision, ModifyPlayerZoneStatistic};
 use super::{GmString, GmWString};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SubjectGameMessage {
 	pub subject_id: ObjId,
 	pub message: GameMessage,
 }
 
-#[derive(Debug, Deserialize, FromVariants, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, FromVariants, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum GameMessage {
 	Teleport(Teleport) = 19,
@@ -186,6 +188,7 @@ pub enum GameMessage {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Teleport {
 	#[default(true)]
 	pub ignore_y: bool,
@@ -204,6 +207,7 @@ pub struct Teleport {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DropClientLoot {
 	#[default(false)]
 	pub use_position: bool,
@@ -219,6 +223,7 @@ pub struct DropClientLoot {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Die {
 	#[default(false)]
 	pub client_death: bool,
@@ -236,6 +241,7 @@ pub struct Die {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PreloadAnimation {
 	pub animation_id: GmWString,
 	#[default(false)]
@@ -245,6 +251,7 @@ pub struct PreloadAnimation {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayAnimation {
 	pub animation_id: GmWString,
 	#[default(true)]
@@ -261,11 +268,13 @@ pub struct PlayAnimation {
 const SECONDARY_PRIORITY: f32 = 0.4;
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetName {
 	pub name: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EchoStartSkill {
 	#[default(false)]
 	pub used_mouse: bool,
@@ -287,6 +296,7 @@ pub struct EchoStartSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddSkill {
 	#[default(0)]
 	pub ai_combat_weight: i32,
@@ -306,6 +316,7 @@ pub struct AddSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetCurrency {
 	pub currency: i64,
 	#[default(LootType::None)]
@@ -322,12 +333,14 @@ pub struct SetCurrency {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamPickupItem {
 	pub loot_id: ObjId,
 	pub loot_owner_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayFxEffect {
 	#[default(-1)]
 	pub effect_id: i32,
@@ -344,18 +357,21 @@ pub struct PlayFxEffect {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StopFxEffect {
 	pub kill_immediate: bool,
 	pub name: GmString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Resurrect {
 	#[default(false)]
 	pub rez_immediately: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetStunned {
 	#[default(OBJID_EMPTY)]
 	pub originator: ObjId,
@@ -388,7 +404,8 @@ pub struct SetStunned {
 	pub ignore_immunity: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum StunState {
 	Push,
@@ -396,6 +413,7 @@ pub enum StunState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetStunImmunity {
 	#[default(OBJID_EMPTY)]
 	pub caster: ObjId,
@@ -409,7 +427,8 @@ pub struct SetStunImmunity {
 	pub immune_to_stun_use_item: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum ImmunityState {
 	Push,
@@ -417,6 +436,7 @@ pub enum ImmunityState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Knockback {
 	#[default(OBJID_EMPTY)]
 	pub caster: ObjId,
@@ -428,6 +448,7 @@ pub struct Knockback {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EnableRebuild {
 	pub enable: bool,
 	pub fail: bool,
@@ -438,7 +459,8 @@ pub struct EnableRebuild {
 	pub user: ObjId,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum FailReason {
 	NotGiven,
@@ -448,6 +470,7 @@ pub enum FailReason {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddItemToInventoryClientSync {
 	pub bound: bool,
 	pub is_boe: bool,
@@ -472,12 +495,14 @@ pub struct AddItemToInventoryClientSync {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct OfferMission {
 	pub mission_id: i32,
 	pub offerer: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyMission {
 	pub mission_id: i32,
 	pub mission_state: MissionState,
@@ -486,13 +511,15 @@ pub struct NotifyMission {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RebuildNotifyState {
 	pub prev_state: RebuildChallengeState,
 	pub state: RebuildChallengeState,
 	pub player: ObjId,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum RebuildChallengeState {
 	Open = 0,
@@ -503,18 +530,21 @@ pub enum RebuildChallengeState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ToggleInteractionUpdates {
 	#[default(false)]
 	pub enable: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TerminateInteraction {
 	pub terminator_id: ObjId,
 	pub terminate_type: TerminateType,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum TerminateType {
 	Range,
@@ -523,42 +553,49 @@ pub enum TerminateType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EmotePlayed {
 	pub emote_id: i32,
 	pub target_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamSetOffWorldFlag {
 	pub player_id: ObjId,
 	pub zone_id: ZoneId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetInventorySize {
 	pub inventory_type: InventoryType,
 	pub size: i32, // todo: check if can be made unsigned
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivityStop {
 	pub exit: bool,
 	pub user_cancel: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CancelMission {
 	pub mission_id: i32,
 	pub reset_completed: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ResetMissions {
 	#[default(-1)]
 	pub mission_id: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyClientShootingGalleryScore {
 	pub add_time: f32,
 	pub score: i32, // todo: unsigned?
@@ -567,32 +604,38 @@ pub struct NotifyClientShootingGalleryScore {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetUserCtrlCompPause {
 	pub paused: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyClientFlagChange {
 	pub flag: bool,
 	pub flag_id: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Help {
 	pub help_id: i32, // todo: type
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VendorTransactionResult {
 	pub result: i32, // todo: type
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct HasBeenCollectedByClient {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TransferToZone {
 	#[default(false)]
 	pub check_transfer_allowed: bool,
@@ -619,6 +662,7 @@ pub struct TransferToZone {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TransferToZoneCheckedIm {
 	#[default(false)]
 	pub is_there_a_queue: bool,
@@ -645,6 +689,7 @@ pub struct TransferToZoneCheckedIm {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct InvalidZoneTransferList {
 	pub customer_feedback_url: GmWString,
 	pub invalid_map_transfer_list: GmWString,
@@ -653,6 +698,7 @@ pub struct InvalidZoneTransferList {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TransferToLastNonInstance {
 	#[default(true)]
 	pub use_last_position: bool,
@@ -674,6 +720,7 @@ pub struct TransferToLastNonInstance {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayMessageBox {
 	pub show: bool,
 	pub callback_client: ObjId,
@@ -684,6 +731,7 @@ pub struct DisplayMessageBox {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Smash {
 	#[default(false)]
 	pub ignore_object_visibility: bool,
@@ -693,6 +741,7 @@ pub struct Smash {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UnSmash {
 	#[default(OBJID_EMPTY)]
 	pub builder_id: ObjId,
@@ -701,11 +750,13 @@ pub struct UnSmash {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetGravityScale {
 	pub scale: f32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlaceModelResponse {
 	#[default(Vector3::ZERO)]
 	pub position: Vector3,
@@ -718,6 +769,7 @@ pub struct PlaceModelResponse {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetJetPackMode {
 	#[default(false)]
 	pub bypass_checks: bool,
@@ -737,16 +789,19 @@ pub struct SetJetPackMode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RegisterPetId {
 	pub obj_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RegisterPetDbId {
 	pub pet_db_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShowActivityCountdown {
 	pub play_additional_sound: bool,
 	pub play_countdown_sound: bool,
@@ -755,6 +810,7 @@ pub struct ShowActivityCountdown {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayTooltip {
 	#[default(false)]
 	pub do_or_die: bool,
@@ -775,16 +831,19 @@ pub struct DisplayTooltip {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartActivityTime {
 	pub start_time: f32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivityPause {
 	pub pause: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UseItemResult {
 	pub item_template_id: Lot,
 	#[default(false)]
@@ -792,6 +851,7 @@ pub struct UseItemResult {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetResponse {
 	pub obj_id_pet: ObjId,
 	pub pet_command_type: i32, // todo: type
@@ -800,6 +860,7 @@ pub struct PetResponse {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SendActivitySummaryLeaderboardData {
 	pub game_id: i32,   // todo: type
 	pub info_type: i32, // todo: type
@@ -809,12 +870,14 @@ pub struct SendActivitySummaryLeaderboardData {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientNotifyPet {
 	pub obj_id_source: ObjId,
 	pub pet_notification_type: PetNotificationType,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyPetTamingMinigame {
 	pub pet_id: ObjId,
 	pub player_taming_id: ObjId,
@@ -826,7 +889,8 @@ pub struct NotifyPetTamingMinigame {
 	pub tele_rot: Quaternion,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PetTamingNotifyType {
 	Success,
@@ -838,6 +902,7 @@ pub enum PetTamingNotifyType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetTamingTryBuildResult {
 	#[default(true)]
 	pub success: bool,
@@ -846,6 +911,7 @@ pub struct PetTamingTryBuildResult {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AddPetToPlayer {
 	pub elemental_type: i32,
 	pub name: GmWString,
@@ -854,6 +920,7 @@ pub struct AddPetToPlayer {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetPetName {
 	pub name: GmWString,
 	#[default(OBJID_EMPTY)]
@@ -861,13 +928,15 @@ pub struct SetPetName {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetNameChanged {
 	pub moderation_status: PetModerationStatus,
 	pub name: GmWString,
 	pub owner_name: GmWString,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PetModerationStatus {
 	Unnamed,
@@ -879,12 +948,14 @@ pub enum PetModerationStatus {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShowPetActionButton {
 	pub button_label: PetAbilityType,
 	pub show: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PetAbilityType {
 	Invalid, // todo: option
@@ -894,17 +965,20 @@ pub enum PetAbilityType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetEmoteLockState {
 	pub lock: bool,
 	pub emote_id: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UseItemRequirementsResponse {
 	pub use_response: UseItemResponse,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum UseItemResponse {
 	NoImaginationForPet = 1,
@@ -913,6 +987,7 @@ pub enum UseItemResponse {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayEmbeddedEffectOnAllClientsNearObject {
 	pub effect_name: GmWString,
 	pub from_object_id: ObjId,
@@ -920,6 +995,7 @@ pub struct PlayEmbeddedEffectOnAllClientsNearObject {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyClientZoneObject {
 	pub name: GmWString,
 	pub param1: i32,
@@ -929,11 +1005,13 @@ pub struct NotifyClientZoneObject {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdateReputation {
 	pub reputation: i64, // todo: check if unsigned
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyRentalResponse {
 	pub clone_id: CloneId,
 	pub code: PropertyRentalResponseCode,
@@ -941,7 +1019,8 @@ pub struct PropertyRentalResponse {
 	pub rentdue: i64, // todo: type
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PropertyRentalResponseCode {
 	Ok = 0,
@@ -954,6 +1033,7 @@ pub enum PropertyRentalResponseCode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlatformResync {
 	pub reverse: bool,
 	pub stop_at_desired_waypoint: bool,
@@ -972,6 +1052,7 @@ pub struct PlatformResync {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayCinematic {
 	#[default(true)]
 	pub allow_ghost_updates: bool,
@@ -997,7 +1078,8 @@ pub struct PlayCinematic {
 	pub start_time_advance: f32,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum EndBehavior {
 	Return,
@@ -1005,6 +1087,7 @@ pub enum EndBehavior {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EndCinematic {
 	#[default(-1.0)]
 	pub lead_out: f32,
@@ -1014,17 +1097,20 @@ pub struct EndCinematic {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ScriptNetworkVarUpdate {
 	pub table_of_vars: LuNameValue,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BroadcastTextToChatbox {
 	pub attrs: LuNameValue,
 	pub text: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ServerTradeInvite {
 	#[default(false)]
 	pub need_invite_pop_up: bool,
@@ -1033,13 +1119,15 @@ pub struct ServerTradeInvite {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ServerTradeInitialReply {
 	pub invitee: ObjId,
 	pub result_type: ResultType,
 	pub name: GmWString,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum ResultType {
 	NotFound,
@@ -1050,6 +1138,7 @@ pub enum ResultType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ServerTradeFinalReply {
 	pub result: bool,
 	pub invitee: ObjId,
@@ -1057,33 +1146,39 @@ pub struct ServerTradeFinalReply {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ServerTradeAccept {
 	#[default(false)]
 	pub first: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct GetLastCustomBuild {
 	pub tokenized_lot_list: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct OrientToObject {
 	pub obj_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct OrientToPosition {
 	pub position: Vector3,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct OrientToAngle {
 	pub relative_to_current: bool,
 	pub angle: f32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyModerationStatusUpdate {
 	#[default(-1)]
 	pub new_moderation_status: i32, // todo: type
@@ -1091,6 +1186,7 @@ pub struct PropertyModerationStatusUpdate {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestClientBounce {
 	pub bounce_target_id: ObjId,
 	pub bounce_target_pos_on_server: Vector3,
@@ -1101,17 +1197,20 @@ pub struct RequestClientBounce {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BouncerActiveStatus {
 	pub active: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ObjectActivatedClient {
 	pub activator_id: ObjId,
 	pub object_activated_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyClientObject {
 	pub name: GmWString,
 	pub param1: i32,
@@ -1121,6 +1220,7 @@ pub struct NotifyClientObject {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DisplayZoneSummary {
 	#[default(false)]
 	pub is_property_map: bool,
@@ -1131,6 +1231,7 @@ pub struct DisplayZoneSummary {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartArrangingWithItem {
 	#[default(true)]
 	pub first_time: bool,
@@ -1148,6 +1249,7 @@ pub struct StartArrangingWithItem {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FinishArrangingWithItem {
 	#[default(OBJID_EMPTY)]
 	pub build_area_id: ObjId,
@@ -1166,6 +1268,7 @@ pub struct FinishArrangingWithItem {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetBuildModeConfirmed {
 	pub start: bool,
 	#[default(true)]
@@ -1180,18 +1283,21 @@ pub struct SetBuildModeConfirmed {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuildModeNotificationReport {
 	pub start: bool,
 	pub num_sent: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetModelToBuild {
 	#[default(LOT_NULL)]
 	pub template_id: Lot,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SpawnModelBricks {
 	#[default(0.0)]
 	pub amount: f32,
@@ -1200,18 +1306,21 @@ pub struct SpawnModelBricks {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyClientFailedPrecondition {
 	pub failed_reason: GmWString,
 	pub precondition_id: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModuleAssemblyDbDataForClient {
 	pub assembly_id: ObjId,
 	pub blob: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EchoSyncSkill {
 	#[default(false)]
 	pub done: bool,
@@ -1221,6 +1330,7 @@ pub struct EchoSyncSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DoClientProjectileImpact {
 	#[default(OBJID_EMPTY)]
 	pub org_id: ObjId,
@@ -1232,22 +1342,26 @@ pub struct DoClientProjectileImpact {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetPlayerAllowedRespawn {
 	pub dont_prompt_for_respawn: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UiMessageServerToSingleClient {
 	pub args: Amf3,
 	pub message_name: GmString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UncastSkill {
 	pub skill_id: i32, // todo: type
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FireEventClientSide {
 	pub args: GmWString,
 	pub object: ObjId,
@@ -1259,12 +1373,14 @@ pub struct FireEventClientSide {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ChangeObjectWorldState {
 	#[default(ObjectWorldState::InWorld)]
 	pub new_state: ObjectWorldState,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum ObjectWorldState {
 	InWorld,
@@ -1273,6 +1389,7 @@ pub enum ObjectWorldState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleLockInput {
 	#[default(true)]
 	pub lock_wheels: bool,
@@ -1285,17 +1402,20 @@ pub struct VehicleLockInput {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleUnlockInput {
 	#[default(true)]
 	pub lock_wheels: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingResetPlayerToLastReset {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingSetPlayerResetInfo {
 	pub current_lap: i32, // todo: unsigned, type?
 	pub furthest_reset_plane: u32,
@@ -1305,17 +1425,20 @@ pub struct RacingSetPlayerResetInfo {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LockNodeRotation {
 	pub node_name: GmString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyVehicleOfRacingObject {
 	#[default(OBJID_EMPTY)]
 	pub racing_object_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetNameBillboardState {
 	#[default(false)]
 	pub override_default: bool,
@@ -1323,6 +1446,7 @@ pub struct SetNameBillboardState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayerReachedRespawnCheckpoint {
 	pub pos: Vector3,
 	#[default(Quaternion::IDENTITY)]
@@ -1330,6 +1454,7 @@ pub struct PlayerReachedRespawnCheckpoint {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct HandleUgcEquipPostDeleteBasedOnEditMode {
 	pub inv_item: ObjId,
 	#[default(0)]
@@ -1337,17 +1462,20 @@ pub struct HandleUgcEquipPostDeleteBasedOnEditMode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct HandleUgcEquipPreCreateBasedOnEditMode {
 	pub model_count: i32,
 	pub model_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MatchResponse {
 	pub response: MatchResponseType,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum MatchResponseType {
 	Ok,
@@ -1360,12 +1488,14 @@ pub enum MatchResponseType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MatchUpdate {
 	pub data: LuNameValue,
 	pub match_update_type: MatchUpdateType,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum MatchUpdateType {
 	PlayerAdded,
@@ -1379,6 +1509,7 @@ pub enum MatchUpdateType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ChangeIdleFlags {
 	#[default(0)]
 	pub off: i32, // todo: type
@@ -1387,6 +1518,7 @@ pub struct ChangeIdleFlags {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyRacingClient {
 	#[default(RacingClientNotificationType::Invalid)]
 	pub event_type: RacingClientNotificationType,
@@ -1396,7 +1528,8 @@ pub struct NotifyRacingClient {
 	pub single_client: ObjId,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum RacingClientNotificationType {
 	Invalid,
@@ -1409,12 +1542,14 @@ pub enum RacingClientNotificationType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingPlayerLoaded {
 	pub player_id: ObjId,
 	pub vehicle_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetStatusImmunity {
 	pub state_change_type: ImmunityState,
 	pub immune_to_basic_attack: bool,
@@ -1429,6 +1564,7 @@ pub struct SetStatusImmunity {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetPetNameModerated {
 	#[default(OBJID_EMPTY)]
 	pub pet_db_id: ObjId,
@@ -1436,6 +1572,7 @@ pub struct SetPetNameModerated {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModifyLegoScore {
 	pub score: i64,
 	#[default(LootType::None)]
@@ -1443,6 +1580,7 @@ pub struct ModifyLegoScore {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetRailMovement {
 	pub path_go_forward: bool,
 	pub path_name: GmWString,
@@ -1454,6 +1592,7 @@ pub struct SetRailMovement {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartRailMovement {
 	#[default(true)]
 	pub damage_immune: bool,
@@ -1484,12 +1623,14 @@ pub struct StartRailMovement {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyRailActivatorStateChange {
 	#[default(true)]
 	pub active: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyRewardMailed {
 	pub object_id: ObjId,
 	pub start_point: Vector3,
@@ -1497,7 +1638,8 @@ pub struct NotifyRewardMailed {
 	pub template_id: Lot,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(i32)]
 pub enum StatisticId {
 	CurrencyCollected = 1,
@@ -1530,6 +1672,7 @@ pub enum StatisticId {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdatePlayerStatistic {
 	pub update_id: StatisticId,
 	#[default(1)]
@@ -1537,6 +1680,7 @@ pub struct UpdatePlayerStatistic {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyNotEnoughInvSpace {
 	pub free_slots_needed: u32,
 	#[default(InventoryType::Default)]
@@ -1544,16 +1688,19 @@ pub struct NotifyNotEnoughInvSpace {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyPropertyOfEditMode {
 	pub editing_active: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamSetLeader {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamGetStatusResponse {
 	pub leader_id: ObjId,
 	pub leader_zone_id: ZoneId,
@@ -1564,6 +1711,7 @@ pub struct TeamGetStatusResponse {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamAddPlayer {
 	#[default(false)]
 	pub is_free_trial: bool,
@@ -1578,6 +1726,7 @@ pub struct TeamAddPlayer {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TeamRemovePlayer {
 	pub disband: bool,
 	pub is_kicked: bool,
@@ -1590,6 +1739,7 @@ pub struct TeamRemovePlayer {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetResurrectRestoreValues {
 	#[default(-1)]
 	pub armor_restore: i32, // todo: option
@@ -1600,24 +1750,28 @@ pub struct SetResurrectRestoreValues {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetPropertyModerationStatus {
 	#[default(-1)]
 	pub moderation_status: i32, // todo: type
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdatePropertyModelCount {
 	#[default(0)]
 	pub model_count: u32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleStopBoost {
 	#[default(true)]
 	pub affect_passive: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartCelebrationEffect {
 	pub animation: GmWString,
 	#[default(11164)]
@@ -1641,12 +1795,14 @@ pub struct StartCelebrationEffect {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetLocalTeam {
 	#[default(false)]
 	pub is_local: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum ResponseMoveItemResponseCode {
 	Success,
@@ -1664,6 +1820,7 @@ pub enum ResponseMoveItemResponseCode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ResponseMoveItemBetweenInventoryTypes {
 	#[default(InventoryType::Default)]
 	pub inv_type_dst: InventoryType,
@@ -1674,6 +1831,7 @@ pub struct ResponseMoveItemBetweenInventoryTypes {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayerSetCameraCyclingMode {
 	#[default(true)]
 	pub allow_cycling_while_dead_only: bool,
@@ -1681,7 +1839,8 @@ pub struct PlayerSetCameraCyclingMode {
 	pub cycling_mode: CyclingMode,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum CyclingMode {
 	AllowCycleTeammates,
@@ -1689,12 +1848,14 @@ pub enum CyclingMode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetMountInventoryId {
 	#[default(OBJID_EMPTY)]
 	pub inventory_mount_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyLevelRewards {
 	pub level: i32,
 	#[default(false)]
@@ -1702,6 +1863,7 @@ pub struct NotifyLevelRewards {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MarkInventoryItemAsActive {
 	#[default(false)]
 	pub active: bool,
@@ -1711,7 +1873,8 @@ pub struct MarkInventoryItemAsActive {
 	pub item_id: ObjId,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum UnequippableActiveType {
 	Pet,
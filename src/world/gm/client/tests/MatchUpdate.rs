@@ -2,7 +2,7 @@ GameMessage::MatchUpdate(
 	MatchUpdate {
 		data: lnv! {
 			"playerName": "FeralRunningSidekick",
-			"player": 1152921510115197038u64,
+			"player": 1152921510115197038i64,
 		},
 		match_update_type: MatchUpdateType::PlayerAdded,
 	},
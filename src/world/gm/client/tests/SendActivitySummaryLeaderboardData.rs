@@ -7,46 +7,46 @@ GameMessage::SendActivitySummaryLeaderboardData(
 			"Result.Count": 1i32,
 			"Result[0].Index": "RowNumber",
 			"Result[0].RowCount": 6i32,
-			"Result[0].Row[0].CharacterID": 1152921507004579166i64,
-			"Result[0].Row[0].LastPlayed": 1327848148i64,
+			"Result[0].Row[0].CharacterID": 1152921507004579166u64,
+			"Result[0].Row[0].LastPlayed": 1327848148u64,
 			"Result[0].Row[0].NumPlayed": 6i32,
 			"Result[0].Row[0].Points": 53200i32,
-			"Result[0].Row[0].RowNumber": 1i64,
+			"Result[0].Row[0].RowNumber": 1u64,
 			"Result[0].Row[0].Time": 395i32,
 			"Result[0].Row[0].name": "CheekyMonkey",
-			"Result[0].Row[1].CharacterID": 1152921507861167019i64,
-			"Result[0].Row[1].LastPlayed": 1315246244i64,
+			"Result[0].Row[1].CharacterID": 1152921507861167019u64,
+			"Result[0].Row[1].LastPlayed": 1315246244u64,
 			"Result[0].Row[1].NumPlayed": 15i32,
 			"Result[0].Row[1].Points": 4300i32,
-			"Result[0].Row[1].RowNumber": 2i64,
+			"Result[0].Row[1].RowNumber": 2u64,
 			"Result[0].Row[1].Time": 240i32,
 			"Result[0].Row[1].name": "DivineDinoKing",
-			"Result[0].Row[2].CharacterID": 1152921507960010807i64,
-			"Result[0].Row[2].LastPlayed": 1326686822i64,
+			"Result[0].Row[2].CharacterID": 1152921507960010807u64,
+			"Result[0].Row[2].LastPlayed": 1326686822u64,
 			"Result[0].Row[2].NumPlayed": 11i32,
 			"Result[0].Row[2].Points": 6900i32,
-			"Result[0].Row[2].RowNumber": 3i64,
+			"Result[0].Row[2].RowNumber": 3u64,
 			"Result[0].Row[2].Time": 239i32,
 			"Result[0].Row[2].name": "BreezyFlyingNinja",
-			"Result[0].Row[3].CharacterID": 1152921507612937007i64,
-			"Result[0].Row[3].LastPlayed": 1320508667i64,
+			"Result[0].Row[3].CharacterID": 1152921507612937007u64,
+			"Result[0].Row[3].LastPlayed": 1320508667u64,
 			"Result[0].Row[3].NumPlayed": 23i32,
 			"Result[0].Row[3].Points": 7100i32,
-			"Result[0].Row[3].RowNumber": 4i64,
+			"Result[0].Row[3].RowNumber": 4u64,
 			"Result[0].Row[3].Time": 235i32,
 			"Result[0].Row[3].name": "Forkstealer",
-			"Result[0].Row[4].CharacterID": 1152921508123877941i64,
-			"Result[0].Row[4].LastPlayed": 1320239196i64,
+			"Result[0].Row[4].CharacterID": 1152921508123877941u64,
+			"Result[0].Row[4].LastPlayed": 1320239196u64,
 			"Result[0].Row[4].NumPlayed": 8i32,
 			"Result[0].Row[4].Points": 2200i32,
-			"Result[0].Row[4].RowNumber": 5i64,
+			"Result[0].Row[4].RowNumber": 5u64,
 			"Result[0].Row[4].Time": 193i32,
 			"Result[0].Row[4].name": "Stingrod",
-			"Result[0].Row[5].CharacterID": 1152921509260170524i64,
-			"Result[0].Row[5].LastPlayed": 1323411244i64,
+			"Result[0].Row[5].CharacterID": 1152921509260170524u64,
+			"Result[0].Row[5].LastPlayed": 1323411244u64,
 			"Result[0].Row[5].NumPlayed": 6i32,
 			"Result[0].Row[5].Points": 6200i32,
-			"Result[0].Row[5].RowNumber": 6i64,
+			"Result[0].Row[5].RowNumber": 6u64,
 			"Result[0].Row[5].Time": 175i32,
 			"Result[0].Row[5].name": "FrecklyCrunchEarlobe",
 		},
@@ -5,9 +5,11 @@ use std::io::{Read, Write};
 use std::io::Result as Res;
 
 use endio::{Deserialize, LERead, LEWrite, Serialize};
-use lu_packets_derive::{GameMessage, GmParam};
+use lu_packets_derive::{Dissect, EnumDeserialize, GameMessage, GmParam};
 
 use crate::common::{LuVarString, LuVarWString, ObjId, OBJID_EMPTY};
+use crate::dissect::{Layout, Primitive};
+use crate::limits;
 use crate::world::{LuNameValue, MapId, MAP_ID_INVALID};
 use super::{Lot, LOT_NULL};
 
@@ -17,6 +19,7 @@ type GmWString = LuVarWString<u32>;
 pub(super) trait GmParam: Sized {
 	fn deserialize<R: Read>(reader: &mut R) -> Res<Self>;
 	fn serialize<W: Write>(&self, writer: &mut W) -> Res<()>;
+	fn layout() -> Layout;
 }
 
 /// Implements `GmParam` by forwarding to [`Deserialize`] and [`Serialize`].
@@ -30,6 +33,10 @@ macro_rules! gm_param {
 			fn serialize<W: ::std::io::Write>(&self, writer: &mut W) -> ::std::io::Result<()> {
 				::endio::LEWrite::write(writer, self)
 			}
+
+			fn layout() -> crate::dissect::Layout {
+				crate::dissect::Layout::of::<$typ>()
+			}
 		}
 	};
 }
@@ -47,22 +54,22 @@ gm_param!(GmWString);
 impl GmParam for Vec<u8> {
 	fn deserialize<R: Read>(reader: &mut R) -> Res<Self> {
 		let str_len: u32 = LERead::read(reader)?;
-		let str_len = str_len as usize;
-		let mut vec = Vec::with_capacity(str_len);
-		unsafe {
-			vec.set_len(str_len);
-		}
-		Read::read(reader, &mut vec)?;
-		Ok(vec)
+		let str_len = limits::check_string("Vec<u8>", str_len as u64)?;
+		limits::read_bytes(reader, str_len)
 	}
 
 	fn serialize<W: Write>(&self, writer: &mut W) -> Res<()> {
 		LEWrite::write(writer, self.len() as u32)?;
 		Write::write_all(writer, self)
 	}
+
+	fn layout() -> Layout {
+		Layout::List { length: Primitive::U32, element: Box::new(Layout::Value(Primitive::U8)) }
+	}
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Clone, Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum InventoryType {
 	Default,
@@ -84,14 +91,16 @@ pub enum InventoryType {
 	All,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum KillType {
 	Violent,
 	Silent,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum MissionState {
 	Unavailable = 0,
@@ -106,7 +115,8 @@ pub enum MissionState {
 	ReadyToCompleteReported = 32,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum PetNotificationType {
 	OwnerDied = 1,
@@ -127,13 +137,15 @@ pub enum PetNotificationType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RemoveSkill {
 	#[default(false)]
 	pub from_skill_set: bool,
 	pub skill_id: u32,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum LootType {
 	None,
@@ -161,6 +173,7 @@ pub enum LootType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RemoveItemFromInventory {
 	#[default(false)]
 	pub confirmed: bool,
@@ -194,6 +207,7 @@ pub struct RemoveItemFromInventory {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EquipInventory {
 	#[default(false)]
 	pub ignore_cooldown: bool,
@@ -202,12 +216,14 @@ pub struct EquipInventory {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetIgnoreProjectileCollision {
 	#[default(false)]
 	pub should_ignore: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UnEquipInventory {
 	#[default(false)]
 	pub even_if_dead: bool,
@@ -222,6 +238,7 @@ pub struct UnEquipInventory {
 const INVENTORY_INVALID: i32 = -1;
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MoveItemInInventory {
 	#[default(INVENTORY_INVALID)]
 	pub dest_inv_type: i32, // todo: type
@@ -232,6 +249,7 @@ pub struct MoveItemInInventory {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MoveInventoryBatch {
 	#[default(false)]
 	pub allow_partial: bool,
@@ -254,6 +272,7 @@ pub struct MoveInventoryBatch {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModifyPlayerZoneStatistic {
 	#[default(false)]
 	pub set: bool,
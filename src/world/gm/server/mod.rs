@@ -1,7 +1,7 @@
 use std::cmp::PartialEq;
 
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::{GameMessage, GmParam, VariantTests};
+use lu_packets_derive::{Dissect, EnumDeserialize, GameMessage, GmParam, VariantTests};
 
 use crate::common::{ObjId, OBJID_EMPTY};
 
@@ -10,13 +10,15 @@ use crate::world::amf3::Amf3;
 pub use super::{EquipInventory, InventoryType, KillType, UnEquipInventory, MissionState, PetNotificationType, MoveItemInInventory, MoveInventoryBatch, RemoveItemFromInventory, SetIgnoreProjectileCollision, ModifyPlayerZoneStatistic};
 use super::{GmString, GmWString};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SubjectGameMessage {
 	pub subject_id: ObjId,
 	pub message: GameMessage,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum GameMessage {
 	RequestDie(RequestDie) = 38,
@@ -143,6 +145,7 @@ pub enum GameMessage {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestDie {
 	pub unknown: bool,
 	pub death_type: GmWString,
@@ -156,18 +159,21 @@ pub struct RequestDie {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayEmote {
 	pub emote_id: i32,
 	pub target_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ControlBehaviors {
 	pub args: Amf3,
 	pub command: GmString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartSkill {
 	#[default(false)]
 	pub used_mouse: bool,
@@ -191,6 +197,7 @@ pub struct StartSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CasterDead {
 	#[default(OBJID_EMPTY)]
 	pub caster: ObjId,
@@ -199,6 +206,7 @@ pub struct CasterDead {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VerifyAck {
 	#[default(false)]
 	pub different: bool,
@@ -208,6 +216,7 @@ pub struct VerifyAck {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SelectSkill {
 	#[default(false)]
 	pub from_skill_set: bool,
@@ -215,24 +224,28 @@ pub struct SelectSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PickupCurrency {
 	pub currency: u32,
 	pub position: Vector3,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PickupItem {
 	pub loot_object_id: ObjId,
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RebuildCancel {
 	pub early_release: bool,
 	pub user_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RespondToMission {
 	pub mission_id: i32,
 	pub player_id: ObjId,
@@ -242,12 +255,14 @@ pub struct RespondToMission {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ServerTerminateInteraction {
 	pub obj_id_terminator: ObjId,
 	pub terminate_type: TerminateType,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum TerminateType {
 	Range,
@@ -256,6 +271,7 @@ pub enum TerminateType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestUse {
 	pub is_multi_interact_use: bool,
 	pub multi_interact_id: u32,
@@ -265,7 +281,8 @@ pub struct RequestUse {
 	pub secondary: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum InteractionType {
 	MissionOfferer,
@@ -276,6 +293,7 @@ pub enum InteractionType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuyFromVendor {
 	#[default(false)]
 	pub confirmed: bool,
@@ -285,6 +303,7 @@ pub struct BuyFromVendor {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SellToVendor {
 	#[default(1)]
 	pub count: i32, // todo: unsigned?
@@ -292,18 +311,21 @@ pub struct SellToVendor {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct AcknowledgePossession {
 	#[default(OBJID_EMPTY)]
 	pub possessed_obj_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestActivityExit {
 	pub user_cancel: bool,
 	pub user_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ShootingGalleryFire {
 	pub target_pos: Vector3,
 	pub w: f32,
@@ -313,11 +335,13 @@ pub struct ShootingGalleryFire {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientItemConsumed {
 	pub item: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdateShootingGalleryRotation {
 	pub angle: f32,
 	pub facing: Vector3,
@@ -325,33 +349,39 @@ pub struct UpdateShootingGalleryRotation {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetTooltipFlag {
 	pub flag: bool,
 	pub tool_tip: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetFlag {
 	pub flag: bool,
 	pub flag_id: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct HasBeenCollected {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DespawnPet {
 	pub delete_pet: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayerLoaded {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestLinkedMission {
 	pub player_id: ObjId,
 	pub mission_id: i32,
@@ -359,6 +389,7 @@ pub struct RequestLinkedMission {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MissionDialogueOk {
 	pub is_complete: bool,
 	pub mission_state: MissionState,
@@ -367,6 +398,7 @@ pub struct MissionDialogueOk {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MessageBoxRespond {
 	pub button: i32,
 	pub identifier: GmWString,
@@ -374,6 +406,7 @@ pub struct MessageBoxRespond {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ChoiceBoxRespond {
 	pub button_identifier: GmWString,
 	pub button: i32,
@@ -381,11 +414,13 @@ pub struct ChoiceBoxRespond {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UseNonEquipmentItem {
 	pub item_to_use: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FetchModelMetadataRequest {
 	pub context: i32,
 	pub object_id: ObjId,
@@ -394,6 +429,7 @@ pub struct FetchModelMetadataRequest {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CommandPet {
 	pub generic_pos_info: Vector3,
 	pub obj_id_source: ObjId,
@@ -404,6 +440,7 @@ pub struct CommandPet {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestActivitySummaryLeaderboardData {
 	#[default(0)]
 	pub game_id: i32,
@@ -418,13 +455,15 @@ pub struct RequestActivitySummaryLeaderboardData {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyPet {
 	pub obj_id_source: ObjId,
 	pub obj_to_notify_pet_about: ObjId,
 	pub pet_notification_type: PetNotificationType,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum QueryType {
 	TopAll,
@@ -433,27 +472,32 @@ pub enum QueryType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientExitTamingMinigame {
 	#[default(true)]
 	pub voluntary_exit: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PetTamingMinigameResult {
 	pub success: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NotifyTamingBuildSuccess {
 	pub build_position: Vector3,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestSetPetName {
 	pub name: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CinematicUpdate {
 	#[default(CinematicEvent::Started)]
 	pub event: CinematicEvent,
@@ -466,7 +510,8 @@ pub struct CinematicUpdate {
 	pub waypoint: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum CinematicEvent {
 	Started,
@@ -475,6 +520,7 @@ pub enum CinematicEvent {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FireEventServerSide {
 	pub args: GmWString,
 	#[default(-1)]
@@ -487,6 +533,7 @@ pub struct FireEventServerSide {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyEditorBegin {
 	#[default(0)]
 	pub distance_type: i32,
@@ -499,17 +546,20 @@ pub struct PropertyEditorBegin {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ToggleGhostReferenceOverride {
 	#[default(false)]
 	pub ref_override: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetGhostReferencePosition {
 	pub pos: Vector3,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdateModelFromClient {
 	pub model_id: ObjId,
 	pub position: Vector3,
@@ -518,6 +568,7 @@ pub struct UpdateModelFromClient {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DeleteModelFromClient {
 	#[default(OBJID_EMPTY)]
 	pub model_id: ObjId,
@@ -525,7 +576,8 @@ pub struct DeleteModelFromClient {
 	pub reason: DeleteReason,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum DeleteReason {
 	PickingModelUp,
@@ -534,6 +586,7 @@ pub enum DeleteReason {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EnterProperty1 {
 	pub index: i32,
 	#[default(true)]
@@ -541,6 +594,7 @@ pub struct EnterProperty1 {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyEntranceSync {
 	pub include_null_address: bool,
 	pub include_null_description: bool,
@@ -554,12 +608,14 @@ pub struct PropertyEntranceSync {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ParseChatMessage {
 	pub client_state: i32,
 	pub string: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetMissionTypeState {
 	#[default(MissionLockState::New)]
 	pub state: MissionLockState,
@@ -567,7 +623,8 @@ pub struct SetMissionTypeState {
 	pub mission_type: GmString,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum MissionLockState {
 	Locked,
@@ -576,6 +633,7 @@ pub enum MissionLockState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdatePropertyOrModelForFilterCheck {
 	pub is_property: bool,
 	pub ugc_id: ObjId,
@@ -586,6 +644,7 @@ pub struct UpdatePropertyOrModelForFilterCheck {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientTradeRequest {
 	#[default(false)]
 	pub need_invite_pop_up: bool,
@@ -593,22 +652,26 @@ pub struct ClientTradeRequest {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientTradeAccept {
 	#[default(false)]
 	pub first: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ReadyForUpdates {
 	pub object_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetLastCustomBuild {
 	pub tokenized_lot_list: GmWString,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyModerationAction {
 	#[default(0)]
 	pub character_id: ObjId,
@@ -618,6 +681,7 @@ pub struct PropertyModerationAction {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BounceNotification {
 	pub obj_id_bounced: ObjId,
 	pub obj_id_bouncer: ObjId,
@@ -625,16 +689,19 @@ pub struct BounceNotification {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetBbbAutosave {
 	pub lxfml_data_compressed: Vec<u8>,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BbbLoadItemRequest {
 	pub item_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BbbSaveRequest {
 	pub local_id: ObjId,
 	pub lxfml_data_compressed: Vec<u8>,
@@ -642,11 +709,13 @@ pub struct BbbSaveRequest {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ZoneSummaryDismissed {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivityStateChangeRequest {
 	pub obj_id: ObjId,
 	pub num_value_1: i32,
@@ -655,6 +724,7 @@ pub struct ActivityStateChangeRequest {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StartBuildingWithItem {
 	#[default(true)]
 	pub first_time: bool,
@@ -670,6 +740,7 @@ pub struct StartBuildingWithItem {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DoneArrangingWithItem {
 	pub new_source_bag: InventoryType,
 	pub new_source_id: ObjId,
@@ -686,6 +757,7 @@ pub struct DoneArrangingWithItem {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetBuildMode {
 	pub start: bool,
 	#[default(-1)]
@@ -700,6 +772,7 @@ pub struct SetBuildMode {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuildModeSet {
 	pub start: bool,
 	#[default(-1)]
@@ -714,11 +787,13 @@ pub struct BuildModeSet {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct BuildExitConfirmation {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MoveItemBetweenInventoryTypes {
 	pub inventory_type_a: InventoryType,
 	pub inventory_type_b: InventoryType,
@@ -732,6 +807,7 @@ pub struct MoveItemBetweenInventoryTypes {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MissionDialogueCancelled {
 	pub is_complete: bool,
 	pub mission_state: MissionState,
@@ -740,6 +816,7 @@ pub struct MissionDialogueCancelled {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SyncSkill {
 	#[default(false)]
 	pub done: bool,
@@ -749,6 +826,7 @@ pub struct SyncSkill {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestServerProjectileImpact {
 	#[default(OBJID_EMPTY)]
 	pub local_id: ObjId,
@@ -758,17 +836,20 @@ pub struct RequestServerProjectileImpact {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ToggleSendingPositionUpdates {
 	#[default(false)]
 	pub send_updates: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlacePropertyModel {
 	pub model_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ReportBug {
 	pub body: GmWString,
 	pub client_version: GmString,
@@ -777,11 +858,13 @@ pub struct ReportBug {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingPlayerInfoResetFinished {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleSetWheelLockState {
 	#[default(true)]
 	pub extra_friction: bool,
@@ -790,12 +873,14 @@ pub struct VehicleSetWheelLockState {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PropertyContentsFromClient {
 	#[default(false)]
 	pub query_db: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ZonePropertyModelRotated {
 	#[default(OBJID_EMPTY)]
 	pub player_id: ObjId,
@@ -804,6 +889,7 @@ pub struct ZonePropertyModelRotated {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ZonePropertyModelRemovedWhileEquipped {
 	#[default(OBJID_EMPTY)]
 	pub player_id: ObjId,
@@ -812,6 +898,7 @@ pub struct ZonePropertyModelRemovedWhileEquipped {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ZonePropertyModelEquipped {
 	#[default(OBJID_EMPTY)]
 	pub player_id: ObjId,
@@ -820,11 +907,13 @@ pub struct ZonePropertyModelEquipped {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RacingClientReady {
 	pub player_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ResetPropertyBehaviors {
 	#[default(true)]
 	pub force: bool,
@@ -833,16 +922,19 @@ pub struct ResetPropertyBehaviors {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetConsumableItem {
 	pub item_template_id: Lot,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UsedInformationPlaque {
 	pub plaque: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ActivateBrickMode {
 	#[default(OBJID_EMPTY)]
 	pub build_object_id: ObjId,
@@ -854,7 +946,8 @@ pub struct ActivateBrickMode {
 	pub enter_flag: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, EnumDeserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum BuildType {
 	Nowhere,
@@ -863,41 +956,48 @@ pub enum BuildType {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CancelRailMovement {
 	#[default(false)]
 	pub immediate: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PlayerRailArrivedNotification {
 	pub path_name: GmWString,
 	pub waypoint_number: i32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModifyGhostingDistance {
 	#[default(1.0)]
 	pub distance: f32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ModularAssemblyNifCompleted {
 	pub object_id: ObjId,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UpdatePropertyPerformanceCost {
 	#[default(0.0)]
 	pub performance_cost: f32,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct SetEmotesEnabled {
 	#[default(true)]
 	pub enable_emotes: bool,
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct VehicleNotifyHitImaginationServer {
 	#[default(OBJID_EMPTY)]
 	pub pickup_obj_id: ObjId,
@@ -910,6 +1010,7 @@ pub struct VehicleNotifyHitImaginationServer {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RequestMoveItemBetweenInventoryTypes {
 	#[default(true)]
 	pub allow_partial: bool,
@@ -932,6 +1033,7 @@ pub struct RequestMoveItemBetweenInventoryTypes {
 }
 
 #[derive(Debug, GameMessage, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DismountComplete {
 	pub mount_id: ObjId,
 }
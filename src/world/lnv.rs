@@ -7,12 +7,22 @@ use std::io::Result as Res;
 use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
 
 use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
+use lu_packets_derive::{Dissect, EnumDeserialize};
 use super::gm::GmParam;
 
-use crate::common::{LuStrExt, LuVarString, LuVarWString, LuWStr};
+use crate::Error;
+use crate::dissect::Dissector;
+use crate::common::{LuStrExt, LuVarString, LuVarWString, ObjId};
+use crate::limits;
+pub use lu_packets_derive::{FromLnv, IntoLnv};
 
-/// A value contained in a [`LuNameValue`].
-#[derive(Deserialize, PartialEq, Serialize)]
+/**
+	A value contained in a [`LuNameValue`].
+
+	The discriminant is the type ID used in both the binary and the text format, with the meanings of DLU's `eLDFType`. The IDs 2, 6 and 10 to 12 aren't defined there and don't appear in any known data, so they are rejected rather than guessed at.
+*/
+#[derive(Clone, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u8)]
 pub enum LnvValue {
 	WString(LuVarWString<u32>) = 0,
@@ -20,29 +30,68 @@ pub enum LnvValue {
 	F32(f32) = 3,
 	F64(f64) = 4,
 	U32(u32) = 5,
+	/// Written as `0` or `1` in the text format.
 	Bool(bool) = 7,
-	I64(i64) = 8,
-	U64(u64) = 9,
+	U64(u64) = 8,
+	/// Used for object IDs, which the client stores as signed, see [`LuNameValue::get_obj_id`].
+	I64(i64) = 9,
 	String(LuVarString<u32>) = 13,
 }
 
 impl LnvValue {
+	/// The type ID of the value.
+	#[rustfmt::skip]
+	pub fn ty(&self) -> u8 {
+		match self {
+			LnvValue::WString(_) => 0,
+			LnvValue::I32    (_) => 1,
+			LnvValue::F32    (_) => 3,
+			LnvValue::F64    (_) => 4,
+			LnvValue::U32    (_) => 5,
+			LnvValue::Bool   (_) => 7,
+			LnvValue::U64    (_) => 8,
+			LnvValue::I64    (_) => 9,
+			LnvValue::String (_) => 13,
+		}
+	}
+
+	/// Parses the `type:value` part of a line of the text format.
+	#[rustfmt::skip]
+	fn parse_ty_val(string: &str) -> Result<Self, &'static str> {
+		let (ty, val) = string.split_at(string.find(':').ok_or("missing ':'")?);
+		let val = &val[1..];
+		Ok(match ty {
+			"0"  => LnvValue::WString(val.try_into().map_err(|_| "invalid wstring")?),
+			"1"  => LnvValue::I32(val.parse().map_err(|_| "invalid i32")?),
+			"3"  => LnvValue::F32(val.parse().map_err(|_| "invalid f32")?),
+			"4"  => LnvValue::F64(val.parse().map_err(|_| "invalid f64")?),
+			"5"  => LnvValue::U32(val.parse().map_err(|_| "invalid u32")?),
+			"7"  => match val {
+				"0" => LnvValue::Bool(false),
+				"1" => LnvValue::Bool(true),
+				_ => return Err("invalid bool"),
+			},
+			"8"  => LnvValue::U64(val.parse().map_err(|_| "invalid u64")?),
+			"9"  => LnvValue::I64(val.parse().map_err(|_| "invalid i64")?),
+			"13" => LnvValue::String(val.as_bytes().try_into().map_err(|_| "invalid string")?),
+			"2" | "6" | "10" | "11" | "12" => return Err("unused type"),
+			_ => return Err("unknown type"),
+		})
+	}
+
+	/// The `value` part of a line of the text format.
 	#[rustfmt::skip]
-	fn parse_ty_val(wstr: &LuWStr) -> Self {
-		let string: String = wstr.to_string();
-		let (ty, val) = string.split_at(string.find(":").unwrap());
-		let val = val.split_at(1).1;
-		match ty {
-			"0"  => LnvValue::WString(val.try_into().unwrap()),
-			"1"  => LnvValue::I32(val.parse().unwrap()),
-			"3"  => LnvValue::F32(val.parse().unwrap()),
-			"4"  => LnvValue::F64(val.parse().unwrap()),
-			"5"  => LnvValue::U32(val.parse().unwrap()),
-			"7"  => LnvValue::Bool(val == "1"),
-			"8"  => LnvValue::I64(val.parse().unwrap()),
-			"9"  => LnvValue::U64(val.parse().unwrap()),
-			"13" => LnvValue::String(val.as_bytes().try_into().unwrap()),
-			_ => panic!(),
+	fn val_string(&self) -> String {
+		match self {
+			LnvValue::WString(val) => val.to_string(),
+			LnvValue::I32    (val) => val.to_string(),
+			LnvValue::F32    (val) => val.to_string(),
+			LnvValue::F64    (val) => val.to_string(),
+			LnvValue::U32    (val) => val.to_string(),
+			LnvValue::Bool   (val) => (*val as u8).to_string(),
+			LnvValue::I64    (val) => val.to_string(),
+			LnvValue::U64    (val) => val.to_string(),
+			LnvValue::String (val) => val.to_string(),
 		}
 	}
 }
@@ -124,6 +173,12 @@ impl From<&[u8]> for LnvValue {
 	}
 }
 
+impl From<LuVarString<u32>> for LnvValue {
+	fn from(val: LuVarString<u32>) -> Self {
+		LnvValue::String(val)
+	}
+}
+
 impl<const N: usize> From<&[u8; N]> for LnvValue {
 	fn from(val: &[u8; N]) -> Self {
 		LnvValue::String(val.try_into().unwrap())
@@ -131,13 +186,153 @@ impl<const N: usize> From<&[u8; N]> for LnvValue {
 }
 
 /// A hash map with values being one of multiple possible types.
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct LuNameValue(HashMap<LuVarWString<u32>, LnvValue>);
 
+/// A type stored as one variant of [`LnvValue`].
+pub trait LnvType: Sized + Into<LnvValue> {
+	/// Converts the value, returning `None` if it is of another variant.
+	fn from_value(value: &LnvValue) -> Option<Self>;
+}
+
+macro_rules! lnv_type {
+	($ty:ty, $variant:ident) => {
+		impl LnvType for $ty {
+			fn from_value(value: &LnvValue) -> Option<Self> {
+				match value {
+					LnvValue::$variant(x) => Some(Clone::clone(x)),
+					_ => None,
+				}
+			}
+		}
+	};
+}
+
+lnv_type!(LuVarWString<u32>, WString);
+lnv_type!(i32, I32);
+lnv_type!(f32, F32);
+lnv_type!(f64, F64);
+lnv_type!(u32, U32);
+lnv_type!(bool, Bool);
+lnv_type!(i64, I64);
+lnv_type!(u64, U64);
+lnv_type!(LuVarString<u32>, String);
+
+/**
+	Conversion from a [`LuNameValue`], such as the config of an object.
+
+	Can be derived for structs with named fields. Each field is read from the value with the same name, which can be changed with `#[rename = "name"]`. Fields of type `Option` are `None` if their value is missing, other fields are required unless they have a `#[default]` or `#[default(expr)]` attribute:
+
+	```
+	# #[macro_use] extern crate lu_packets;
+	use lu_packets::world::{FromLnv, IntoLnv, Lot};
+
+	#[derive(FromLnv, IntoLnv)]
+	struct SpawnerConfig {
+		#[rename = "spawntemplate"]
+		template: Lot,
+		#[default(5.0)]
+		respawn: f32,
+		#[default]
+		no_auto_spawn: bool,
+		max_to_spawn: Option<i32>,
+	}
+
+	# fn main() {
+	let config = SpawnerConfig::from_lnv(&lnv! { "spawntemplate": 4712u32, "max_to_spawn": 3i32, }).unwrap();
+	assert_eq!(config.template, 4712);
+	assert_eq!(config.respawn, 5.0);
+	assert!(!config.no_auto_spawn);
+	assert_eq!(config.max_to_spawn, Some(3));
+	assert_eq!(config.to_lnv(), lnv! { "spawntemplate": 4712u32, "respawn": 5.0f32, "no_auto_spawn": false, "max_to_spawn": 3i32, });
+	# }
+	```
+
+	Values of another type than the field are an error, see [`LuNameValue::get_field`].
+*/
+pub trait FromLnv: Sized {
+	fn from_lnv(lnv: &LuNameValue) -> Result<Self, Error>;
+}
+
+/**
+	Conversion to a [`LuNameValue`].
+
+	Can be derived together with [`FromLnv`], using the same attributes. Fields of type `Option` are left out if they are `None`.
+*/
+pub trait IntoLnv {
+	fn to_lnv(&self) -> LuNameValue;
+}
+
 impl LuNameValue {
 	pub fn new() -> Self {
 		LuNameValue(HashMap::new())
 	}
+
+	/// The value named `name`, of any type.
+	pub fn get_value(&self, name: &str) -> Option<&LnvValue> {
+		let key: LuVarWString<u32> = name.try_into().ok()?;
+		self.0.get(&key)
+	}
+
+	/// The value named `name`, or `None` if it is missing or of another type.
+	pub fn get_as<T: LnvType>(&self, name: &str) -> Option<T> {
+		self.get_value(name).and_then(T::from_value)
+	}
+
+	/**
+		The value named `name`, or `None` if it is missing.
+
+		Unlike [`get_as`](Self::get_as), a value of another type is an error, [`Error::LnvField`]. Used by derived implementations of [`FromLnv`].
+	*/
+	pub fn get_field<T: LnvType>(&self, name: &'static str) -> Result<Option<T>, Error> {
+		match self.get_value(name) {
+			None => Ok(None),
+			Some(value) => T::from_value(value).map(Some).ok_or(Error::LnvField { name, reason: "has the wrong type" }),
+		}
+	}
+
+	pub fn get_wstring(&self, name: &str) -> Option<&LuVarWString<u32>> {
+		match self.get_value(name)? {
+			LnvValue::WString(x) => Some(x),
+			_ => None,
+		}
+	}
+
+	pub fn get_i32(&self, name: &str) -> Option<i32> {
+		self.get_as(name)
+	}
+
+	pub fn get_f32(&self, name: &str) -> Option<f32> {
+		self.get_as(name)
+	}
+
+	pub fn get_f64(&self, name: &str) -> Option<f64> {
+		self.get_as(name)
+	}
+
+	pub fn get_u32(&self, name: &str) -> Option<u32> {
+		self.get_as(name)
+	}
+
+	pub fn get_bool(&self, name: &str) -> Option<bool> {
+		self.get_as(name)
+	}
+
+	pub fn get_i64(&self, name: &str) -> Option<i64> {
+		self.get_as(name)
+	}
+
+	/// An object ID, stored as [`LnvValue::I64`].
+	pub fn get_obj_id(&self, name: &str) -> Option<ObjId> {
+		self.get_i64(name).map(|x| x as ObjId)
+	}
+
+	pub fn get_string(&self, name: &str) -> Option<&LuVarString<u32>> {
+		match self.get_value(name)? {
+			LnvValue::String(x) => Some(x),
+			_ => None,
+		}
+	}
 }
 
 impl std::ops::Deref for LuNameValue {
@@ -163,24 +358,44 @@ impl std::fmt::Debug for LuNameValue {
 	}
 }
 
+/// Represented as a map from names to values, ordered by name so that equal values are represented equally.
+#[cfg(feature = "serde")]
+impl serde::Serialize for LuNameValue {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut entries: Vec<_> = self.0.iter().collect();
+		entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+		serializer.collect_map(entries)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LuNameValue {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		<HashMap<_, _> as serde::Deserialize>::deserialize(deserializer).map(LuNameValue)
+	}
+}
+
 impl<R: Read> Deserialize<LE, R> for LuNameValue {
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let len: u32 = LERead::read(reader)?;
 		let is_compressed: bool = LERead::read(reader)?;
 		let uncompressed = if is_compressed {
 			let uncomp_len: u32 = LERead::read(reader)?;
+			let uncomp_len = limits::check("LuNameValue", "max_lnv_inflated_len", |x| x.max_lnv_inflated_len, uncomp_len as u64)?;
 			let comp_len: u32 = LERead::read(reader)?;
-			let mut comp = vec![0; comp_len as usize];
-			reader.read_exact(&mut comp)?;
-			let mut inflater = ZlibDecoder::new(&comp[..]);
-			let mut uncomp = Vec::with_capacity(uncomp_len as usize);
-			inflater.read_to_end(&mut uncomp)?;
-			assert_eq!(uncomp.len(), uncomp_len as usize);
+			let comp = limits::read_bytes(reader, comp_len as usize)?;
+			// inflate at most one byte more than announced, to detect larger data without inflating all of it
+			let inflater = ZlibDecoder::new(&comp[..]);
+			let mut uncomp = limits::vec_with_capacity(uncomp_len);
+			inflater.take(uncomp_len as u64 + 1).read_to_end(&mut uncomp)?;
+			if uncomp.len() != uncomp_len {
+				return Err(Error::LengthOverflow { type_name: "LuNameValue", length: uncomp.len() as u64 }.into());
+			}
 			uncomp
 		} else {
-			let mut uncomp = vec![0; len as usize - 1];
-			reader.read_exact(&mut uncomp)?;
-			uncomp
+			let len = len.checked_sub(1).ok_or(Error::LengthOverflow { type_name: "LuNameValue", length: len as u64 })?;
+			let len = limits::check("LuNameValue", "max_lnv_inflated_len", |x| x.max_lnv_inflated_len, len as u64)?;
+			limits::read_bytes(reader, len)?
 		};
 		let unc_reader = &mut &uncompressed[..];
 		let lnv_len: u32 = LERead::read(unc_reader)?;
@@ -195,6 +410,13 @@ impl<R: Read> Deserialize<LE, R> for LuNameValue {
 	}
 }
 
+/// Dissected as a single value, since the entries are compressed.
+impl crate::dissect::Dissect for LuNameValue {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.decoded(self, |writer| LEWrite::write(writer, self), |reader| LERead::read::<LuNameValue>(reader).map(drop))
+	}
+}
+
 impl<'a, W: Write> Serialize<LE, W> for &'a LuNameValue {
 	fn serialize(self, writer: &mut W) -> Res<()> {
 		let mut uncompressed: Vec<u8> = vec![];
@@ -230,54 +452,53 @@ impl<'a, W: Write> Serialize<LE, W> for &'a LuNameValue {
 	}
 }
 
-impl From<&LuVarWString<u32>> for LuNameValue {
-	fn from(wstr: &LuVarWString<u32>) -> Self {
-		if wstr.is_empty() {
-			return LuNameValue(HashMap::new());
-		}
+/**
+	Parses the text format, with one `name=type:value` entry per line, where `type` is the [type ID](LnvValue::ty).
+
+	Empty lines are skipped. Errors are reported as [`Error::InvalidLnv`] with the number of the offending line.
+*/
+impl TryFrom<&LuVarWString<u32>> for LuNameValue {
+	type Error = Error;
+
+	fn try_from(wstr: &LuVarWString<u32>) -> Result<Self, Self::Error> {
 		let mut map = HashMap::new();
-		for name_type_val in wstr.split(|c| *c == b'\n'.into()) {
-			let (name, type_val) = name_type_val.split_at(name_type_val.iter().position(|c| *c == b'='.into()).unwrap());
+		for (i, name_type_val) in wstr.split(|c| *c == b'\n'.into()).enumerate() {
+			if name_type_val.is_empty() {
+				continue;
+			}
+			let invalid = |reason| Error::InvalidLnv { line: i + 1, text: name_type_val.to_string(), reason };
+			let equals = name_type_val.iter().position(|c| *c == b'='.into()).ok_or_else(|| invalid("missing '='"))?;
+			let (name, type_val) = name_type_val.split_at(equals);
 			let name: LuVarWString<u32> = name.into();
-			let type_val = type_val.split_at(1).1;
-			let lnv_value = LnvValue::parse_ty_val(type_val);
+			let lnv_value = LnvValue::parse_ty_val(&type_val[1..].to_string()).map_err(invalid)?;
 			map.insert(name, lnv_value);
 		}
-		LuNameValue(map)
+		Ok(LuNameValue(map))
 	}
 }
 
+/**
+	Writes the text format, sorted by name.
+
+	Names containing `=` or newlines and string values containing newlines can't be represented, and won't parse back to the same value.
+*/
 impl From<&LuNameValue> for LuVarWString<u32> {
 	fn from(lnv: &LuNameValue) -> Self {
-		let mut wstr: Self = vec![].into();
-		let mut i = 0;
-		let len = lnv.0.len();
+		let mut string = String::new();
 		let mut key_value: Vec<_> = lnv.0.iter().collect();
 		key_value.sort_unstable_by(|(k1, _), (k2, _)| k1.cmp(k2));
-		for (key, value) in key_value {
-			wstr.extend_from_slice(&key);
-			wstr.push(b'='.into());
-			#[rustfmt::skip]
-			let (disc, val_str) = match value {
-				LnvValue::WString(val) => ("0",  val.to_string()),
-				LnvValue::I32    (val) => ("1",  val.to_string()),
-				LnvValue::F32    (val) => ("3",  val.to_string()),
-				LnvValue::F64    (val) => ("4",  val.to_string()),
-				LnvValue::U32    (val) => ("5",  val.to_string()),
-				LnvValue::Bool   (val) => ("7",  (*val as u8).to_string()),
-				LnvValue::I64    (val) => ("8",  val.to_string()),
-				LnvValue::U64    (val) => ("9",  val.to_string()),
-				LnvValue::String (val) => ("13", val.to_string()),
-			};
-			wstr.extend_from_slice(&LuVarWString::<u32>::try_from(disc).unwrap());
-			wstr.push(b':'.into());
-			wstr.extend_from_slice(&LuVarWString::<u32>::try_from(val_str.as_str()).unwrap());
-			i += 1;
-			if i < len {
-				wstr.push(b'\n'.into());
+		for (i, (key, value)) in key_value.into_iter().enumerate() {
+			if i > 0 {
+				string.push('\n');
 			}
+			string.push_str(&key.to_string());
+			string.push('=');
+			string.push_str(&value.ty().to_string());
+			string.push(':');
+			string.push_str(&value.val_string());
 		}
-		wstr
+		// only contains characters converted from UCS-2 and ASCII
+		string.as_str().try_into().unwrap()
 	}
 }
 
@@ -287,7 +508,7 @@ impl GmParam for LuNameValue {
 		if !lu_var_wstr.is_empty() {
 			let _: u16 = LERead::read(reader)?; // for some reason has a null terminator
 		}
-		Ok((&lu_var_wstr).into())
+		Ok(LuNameValue::try_from(&lu_var_wstr)?)
 	}
 
 	fn serialize<W: ::std::io::Write>(&self, writer: &mut W) -> ::std::io::Result<()> {
@@ -298,4 +519,194 @@ impl GmParam for LuNameValue {
 		}
 		Ok(())
 	}
+
+	fn layout() -> crate::dissect::Layout {
+		// whether the null terminator is present depends on the length
+		crate::dissect::Layout::Opaque
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::{TryFrom, TryInto};
+
+	use crate::Error;
+	use crate::common::LuVarWString;
+	use crate::world::Lot;
+	use super::{FromLnv, IntoLnv, LnvValue, LuNameValue};
+
+	fn parse(text: &str) -> Result<LuNameValue, Error> {
+		LuNameValue::try_from(&LuVarWString::<u32>::try_from(text).unwrap())
+	}
+
+	#[test]
+	fn test_parse() {
+		let lnv = parse("name=0:Some=thing:x\nobjid=9:1152921510115197038\nbool=7:1\n\nf32=3:-0.5").unwrap();
+		assert_eq!(
+			lnv,
+			lnv! {
+				"name": "Some=thing:x",
+				"objid": 1152921510115197038i64,
+				"bool": true,
+				"f32": -0.5f32,
+			}
+		);
+	}
+
+	#[test]
+	fn test_invalid() {
+		#[rustfmt::skip]
+		let cases = [
+			("a=1:1\nb",         2, "missing '='"),
+			("a=1",              1, "missing ':'"),
+			("a=1:x",            1, "invalid i32"),
+			("a=5:-1",           1, "invalid u32"),
+			("a=7:true",         1, "invalid bool"),
+			("a=1:1\n\nb=2:1",   3, "unused type"),
+			("a=6:1",            1, "unused type"),
+			("a=14:1",           1, "unknown type"),
+		];
+		for (text, line, reason) in &cases {
+			match parse(text) {
+				Err(Error::InvalidLnv { line: l, reason: r, .. }) => assert_eq!((l, r), (*line, *reason), "{:?}", text),
+				x => panic!("{:?} parsed as {:?}", text, x),
+			}
+		}
+	}
+
+	#[derive(Debug, FromLnv, IntoLnv, PartialEq)]
+	struct SpawnerConfig {
+		#[rename = "spawntemplate"]
+		template: Lot,
+		#[default(5.0)]
+		respawn: f32,
+		#[default]
+		no_auto_spawn: bool,
+		max_to_spawn: Option<i32>,
+	}
+
+	#[test]
+	fn test_getters() {
+		let lnv = lnv! {
+			"modelType": 2i32,
+			"objid": 1152921510115197038i64,
+			"name": "Spawner",
+		};
+		assert_eq!(lnv.get_i32("modelType"), Some(2));
+		assert_eq!(lnv.get_u32("modelType"), None);
+		assert_eq!(lnv.get_obj_id("objid"), Some(1152921510115197038));
+		assert_eq!(lnv.get_wstring("name").map(String::from), Some("Spawner".into()));
+		assert_eq!(lnv.get_i32("missing"), None);
+	}
+
+	#[test]
+	fn test_derive() {
+		let config = SpawnerConfig::from_lnv(&lnv! { "spawntemplate": 4712u32, "no_auto_spawn": true, }).unwrap();
+		assert_eq!(config, SpawnerConfig { template: 4712, respawn: 5.0, no_auto_spawn: true, max_to_spawn: None });
+
+		let config = SpawnerConfig { template: 4712, respawn: 10.0, no_auto_spawn: false, max_to_spawn: Some(3) };
+		let lnv = config.to_lnv();
+		assert_eq!(lnv, lnv! { "spawntemplate": 4712u32, "respawn": 10.0f32, "no_auto_spawn": false, "max_to_spawn": 3i32, });
+		assert_eq!(SpawnerConfig::from_lnv(&lnv).unwrap(), config);
+
+		assert!(matches!(SpawnerConfig::from_lnv(&lnv! {}), Err(Error::LnvField { name: "spawntemplate", reason: "is missing" })));
+		assert!(matches!(SpawnerConfig::from_lnv(&lnv! { "spawntemplate": 4712i32, }), Err(Error::LnvField { name: "spawntemplate", reason: "has the wrong type" })));
+	}
+
+	/// Small xorshift generator, so that the round trip test can cover many values without any dependencies.
+	struct Rng(u64);
+
+	impl Rng {
+		fn next(&mut self) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			self.0
+		}
+
+		fn string(&mut self, chars: &[char]) -> String {
+			let len = self.next() % 8;
+			(0..len).map(|_| chars[(self.next() % chars.len() as u64) as usize]).collect()
+		}
+
+		fn value(&mut self) -> LnvValue {
+			const CHARS: &[char] = &['a', 'Z', '0', ' ', ':', '=', '\\', 'é', 'ß', '中'];
+			const ASCII: &[char] = &['a', 'Z', '0', ' ', ':', '=', '\\', '~'];
+			let x = self.next();
+			let float = || -> f64 {
+				let float = f64::from_bits(x);
+				if float.is_nan() {
+					0.0
+				} else {
+					float
+				}
+			};
+			match self.next() % 9 {
+				0 => self.string(CHARS).as_str().into(),
+				1 => LnvValue::I32(x as i32),
+				2 => LnvValue::F32(float() as f32),
+				3 => LnvValue::F64(float()),
+				4 => LnvValue::U32(x as u32),
+				5 => LnvValue::Bool(x & 1 != 0),
+				6 => LnvValue::I64(x as i64),
+				7 => LnvValue::U64(x),
+				_ => self.string(ASCII).as_bytes().into(),
+			}
+		}
+	}
+
+	#[test]
+	fn test_roundtrip() {
+		const NAME_CHARS: &[char] = &['a', 'Z', '0', '_', ':', ' ', 'é'];
+		let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+		for _ in 0..1000 {
+			let mut lnv = LuNameValue::new();
+			for _ in 0..rng.next() % 6 {
+				lnv.insert(rng.string(NAME_CHARS).as_str().try_into().unwrap(), rng.value());
+			}
+			let text: LuVarWString<u32> = (&lnv).into();
+			assert_eq!(LuNameValue::try_from(&text).unwrap(), lnv);
+		}
+	}
+
+	#[test]
+	fn test_limits() {
+		use std::io::Write;
+
+		use endio::LEWrite;
+		use flate2::{Compression, write::ZlibEncoder};
+
+		let mut encoder = ZlibEncoder::new(vec![], Compression::new(9));
+		encoder.write_all(&[0; 1 << 20]).unwrap();
+		let compressed = encoder.finish().unwrap();
+		let packet = |uncomp_len: u32| {
+			let mut bytes = vec![];
+			LEWrite::write(&mut bytes, compressed.len() as u32 + 1 + 4 + 4).unwrap();
+			LEWrite::write(&mut bytes, true).unwrap();
+			LEWrite::write(&mut bytes, uncomp_len).unwrap();
+			LEWrite::write(&mut bytes, compressed.len() as u32).unwrap();
+			bytes.write_all(&compressed).unwrap();
+			bytes
+		};
+		// announced size over the limit
+		let err = crate::from_slice::<LuNameValue>(&packet(u32::MAX)).unwrap_err();
+		assert!(matches!(err.root(), Error::LimitExceeded { limit: "max_lnv_inflated_len", .. }));
+		// inflating to more than announced stops right after the announced size
+		let err = crate::from_slice::<LuNameValue>(&packet(16)).unwrap_err();
+		assert!(matches!(err.root(), Error::LengthOverflow { type_name: "LuNameValue", length: 17 }));
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn test_json() {
+		let lnv = lnv! {
+			"name": "Some=thing",
+			"bool": true,
+			"objid": 1152921510115197038i64,
+			"bytes": b"raw",
+		};
+		let json = serde_json::to_string(&lnv).unwrap();
+		assert_eq!(json, r#"{"bool":{"Bool":true},"bytes":{"String":"raw"},"name":{"WString":"Some=thing"},"objid":{"I64":1152921510115197038}}"#);
+		assert_eq!(serde_json::from_str::<LuNameValue>(&json).unwrap(), lnv);
+	}
 }
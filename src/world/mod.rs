@@ -2,13 +2,15 @@
 pub mod client;
 pub mod gm;
 pub mod amf3;
+pub mod behaviors;
 mod lnv;
 pub mod server;
+pub mod skill;
 
 use std::cmp::PartialEq;
 
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::GmParam;
+use lu_packets_derive::{Dissect, GmParam};
 pub use lnv::*;
 
 pub type Lot = u32;
@@ -21,7 +23,8 @@ const MAP_ID_INVALID: MapId = 0;
 type CloneId = u32;
 const CLONE_ID_INVALID: CloneId = 0;
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Debug, Deserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ZoneId {
 	pub map_id: MapId,
 	pub instance_id: u16,
@@ -32,7 +35,8 @@ impl ZoneId {
 	const INVALID: Self = Self { map_id: 0, instance_id: 0, clone_id: 0 };
 }
 
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Copy, Clone, Debug, Default, Deserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Vector3 {
 	pub x: f32,
 	pub y: f32,
@@ -43,7 +47,8 @@ impl Vector3 {
 	pub const ZERO: Self = Self { x: 0.0, y: 0.0, z: 0.0 };
 }
 
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, GmParam)]
+#[derive(Copy, Clone, Debug, Default, Deserialize, Dissect, Serialize, PartialEq, GmParam)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Quaternion {
 	pub x: f32,
 	pub y: f32,
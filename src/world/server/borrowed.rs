@@ -0,0 +1,159 @@
+/*!
+	Borrowed decoding of world messages received many times per second.
+
+	Chat messages and position updates are decoded completely. Game messages are only decoded up to their ID, see [`SubjectGameMessageRef`].
+*/
+use std::io::Result as Res;
+
+use endio::LERead;
+
+use crate::{DecodeBorrowed, Error};
+use crate::chat::ChatChannel;
+use crate::common::{LuWStrRef, ObjId};
+use crate::world::gm::server::{GameMessage, SubjectGameMessage};
+use super::{GeneralChatMessage, PositionUpdate};
+
+/**
+	A [`WorldMessage`](super::WorldMessage), decoded without allocation for the messages received most often.
+
+	Like [`WorldMessage`](super::WorldMessage), this starts after the RakNet message ID and service ID. Other messages are kept as [`Other`](Self::Other), to be decoded with the owned path:
+
+	```
+	use lu_packets::world::server::{WorldMessage, WorldMessageRef};
+
+	fn handle(data: &[u8]) -> Result<(), lu_packets::Error> {
+		match lu_packets::decode_borrowed(data)? {
+			WorldMessageRef::PositionUpdate(update) => { /* ... */ }
+			WorldMessageRef::Other(data) => {
+				let message: WorldMessage = lu_packets::from_slice(data)?;
+				/* ... */
+			}
+			_ => { /* ... */ }
+		}
+		Ok(())
+	}
+	```
+*/
+#[derive(Debug, PartialEq)]
+pub enum WorldMessageRef<'a> {
+	SubjectGameMessage(SubjectGameMessageRef<'a>),
+	GeneralChatMessage(GeneralChatMessageRef<'a>),
+	PositionUpdate(PositionUpdate),
+	/// Any other message, including its discriminant.
+	Other(&'a [u8]),
+}
+
+impl<'a> DecodeBorrowed<'a> for WorldMessageRef<'a> {
+	fn decode_borrowed(data: &mut &'a [u8]) -> Res<Self> {
+		let all = *data;
+		let disc: u32 = LERead::read(data)?;
+		let _padding: u8 = LERead::read(data)?;
+		// discriminants of `WorldMessage`
+		Ok(match disc {
+			5 => Self::SubjectGameMessage(DecodeBorrowed::decode_borrowed(data)?),
+			14 => Self::GeneralChatMessage(DecodeBorrowed::decode_borrowed(data)?),
+			22 => Self::PositionUpdate(DecodeBorrowed::decode_borrowed(data)?),
+			_ => {
+				*data = &[];
+				Self::Other(all)
+			}
+		})
+	}
+}
+
+/**
+	A [`SubjectGameMessage`] with its game message left encoded.
+
+	The game message is assumed to extend to the end of the data. Only the subject and the message ID are decoded without allocation, so that servers can filter and dispatch messages before decoding them. Game messages themselves have no borrowed form, so [`decode`](Self::decode) is the owned path.
+*/
+#[derive(Debug, PartialEq)]
+pub struct SubjectGameMessageRef<'a> {
+	pub subject_id: ObjId,
+	/// The discriminant of the [`GameMessage`].
+	pub message_id: u16,
+	/// The encoded [`GameMessage`], including its ID.
+	pub message: &'a [u8],
+}
+
+impl SubjectGameMessageRef<'_> {
+	/// Decodes the game message with the owned path, which allocates for messages containing strings, lists or LNV.
+	pub fn decode(&self) -> Res<SubjectGameMessage> {
+		let message: GameMessage = LERead::read(&mut &self.message[..])?;
+		Ok(SubjectGameMessage { subject_id: self.subject_id, message })
+	}
+}
+
+impl<'a> DecodeBorrowed<'a> for SubjectGameMessageRef<'a> {
+	fn decode_borrowed(data: &mut &'a [u8]) -> Res<Self> {
+		let subject_id = LERead::read(data)?;
+		let message = *data;
+		let message_id = LERead::read(data)?;
+		*data = &[];
+		Ok(Self { subject_id, message_id, message })
+	}
+}
+
+/// A [`GeneralChatMessage`] borrowing its message.
+#[derive(Debug, PartialEq)]
+pub struct GeneralChatMessageRef<'a> {
+	pub chat_channel: ChatChannel,
+	pub source_id: u16,
+	pub message: LuWStrRef<'a>,
+}
+
+impl<'a> DecodeBorrowed<'a> for GeneralChatMessageRef<'a> {
+	fn decode_borrowed(data: &mut &'a [u8]) -> Res<Self> {
+		let chat_channel = LERead::read(data)?;
+		let source_id = LERead::read(data)?;
+		let str_len: u32 = LERead::read(data)?;
+		let str_len = str_len.checked_sub(1).ok_or(Error::InvalidString { type_name: "GeneralChatMessage", reason: "missing null terminator" })?;
+		let message = LuWStrRef::take(data, str_len as usize)?;
+		let _: u16 = LERead::read(data)?;
+		Ok(Self { chat_channel, source_id, message })
+	}
+}
+
+impl From<GeneralChatMessageRef<'_>> for GeneralChatMessage {
+	fn from(message: GeneralChatMessageRef<'_>) -> Self {
+		Self { chat_channel: message.chat_channel, source_id: message.source_id, message: message.message.into() }
+	}
+}
+
+/// Position updates don't allocate, so this is the same as the owned path.
+impl<'a> DecodeBorrowed<'a> for PositionUpdate {
+	fn decode_borrowed(data: &mut &'a [u8]) -> Res<Self> {
+		LERead::read(data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{decode_borrowed, from_slice};
+	use super::super::WorldMessage;
+	use super::WorldMessageRef;
+
+	#[test]
+	fn test_same_as_owned() {
+		let fixtures: [&[u8]; 4] = [include_bytes!("tests/SubjectGameMessage.bin"), include_bytes!("tests/GeneralChatMessage.bin"), include_bytes!("tests/PositionUpdate.bin"), include_bytes!("tests/LevelLoadComplete.bin")];
+		for data in &fixtures {
+			let owned: WorldMessage = from_slice(data).unwrap();
+			let converted = match decode_borrowed(data).unwrap() {
+				WorldMessageRef::SubjectGameMessage(x) => WorldMessage::SubjectGameMessage(x.decode().unwrap()),
+				WorldMessageRef::GeneralChatMessage(x) => WorldMessage::GeneralChatMessage(x.into()),
+				WorldMessageRef::PositionUpdate(x) => WorldMessage::PositionUpdate(x),
+				WorldMessageRef::Other(x) => from_slice(x).unwrap(),
+			};
+			assert_eq!(converted, owned);
+		}
+	}
+
+	#[test]
+	fn test_general_chat_message() {
+		let data = include_bytes!("tests/GeneralChatMessage.bin");
+		match decode_borrowed(data).unwrap() {
+			WorldMessageRef::GeneralChatMessage(x) => assert_eq!(x.message.to_string(), "how long are you going to stay there?"),
+			x => panic!("{:?}", x),
+		}
+		assert!(decode_borrowed::<WorldMessageRef>(&data[..data.len() - 4]).is_err());
+	}
+}
@@ -1,9 +1,10 @@
 use endio::{Deserialize, Serialize};
-use lu_packets_derive::VariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, VariantTests};
 
 use crate::common::{LuWString32, LuWString400, LuWString50, ObjId};
 
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum Mail {
 	CreateRequest(CreateRequest) = 0,
@@ -14,7 +15,8 @@ pub enum Mail {
 	UnreadCountRequest = 11,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 4]
 pub struct CreateRequest {
 	pub subject: LuWString50,
@@ -26,21 +28,24 @@ pub struct CreateRequest {
 	pub locale_id: u16,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ContentCollectRequest {
 	#[padding = 4]
 	pub mail_id: ObjId,
 	pub receiver_id: ObjId,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DeleteRequest {
 	#[padding = 4]
 	pub mail_id: ObjId,
 	pub receiver_id: ObjId,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct MarkAsReadRequest {
 	#[padding = 4]
 	pub mail_id: ObjId,
@@ -1,4 +1,5 @@
 //! Server-received world messages.
+mod borrowed;
 pub mod mail;
 
 use std::io::{Read, Write};
@@ -7,9 +8,11 @@ use std::io::Result as Res;
 use endio::{Deserialize, LERead, LEWrite, Serialize};
 use endio::LittleEndian as LE;
 use endio_bit::{BEBitReader, BEBitWriter};
-use lu_packets_derive::VariantTests;
+use lu_packets_derive::{Dissect, EnumDeserialize, VariantTests};
 
+use crate::Error;
 use crate::common::{ObjId, LuVarWString, LuWString33, LuWString42, ServiceId};
+use crate::dissect::{Dissector, Layout};
 use crate::chat::ChatChannel;
 use crate::chat::server::ChatMessage;
 use crate::raknet::client::replica::controllable_physics::FrameStats;
@@ -18,12 +21,14 @@ use super::gm::server::SubjectGameMessage;
 use self::mail::Mail;
 
 pub use crate::general::server::GeneralMessage;
+pub use self::borrowed::*;
 
 /// All messages that can be received by a world server.
 pub type Message = crate::raknet::server::Message<LuMessage>;
 
 /// All LU messages that can be received by a world server.
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u16)]
 pub enum LuMessage {
 	General(GeneralMessage) = ServiceId::General as u16,
@@ -31,7 +36,8 @@ pub enum LuMessage {
 }
 
 /// All server-received world messages.
-#[derive(Debug, Deserialize, PartialEq, Serialize, VariantTests)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize, VariantTests)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[post_disc_padding = 1]
 #[repr(u32)]
 pub enum WorldMessage {
@@ -72,6 +78,7 @@ pub enum WorldMessage {
 	**Important**: Do **not** handle any other packets from clients that have not yet been validated. Handling other packets before validation can lead to errors because the connection has not yet been associated with a username, and can lead to security vulnerabilities if session keys are not validated properly.
 */
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ClientValidation {
 	/// Account username.
 	pub username: LuWString33,
@@ -116,6 +123,20 @@ where
 	}
 }
 
+impl crate::dissect::Dissect for ClientValidation {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("username", &self.username)?;
+		dissector.field("session_key", &self.session_key)?;
+		dissector.field("fdb_checksum", &self.fdb_checksum)?;
+		// garbage byte
+		dissector.skip(8);
+		Ok(())
+	}
+	fn layout() -> Layout {
+		Layout::Struct(vec![("username", Layout::of::<LuWString33>()), ("session_key", Layout::of::<LuWString33>()), ("fdb_checksum", Layout::of::<[u8; 32]>()), ("", Layout::Padding(8))])
+	}
+}
+
 /**
 	Requests a new character to be created.
 
@@ -130,7 +151,8 @@ where
 	### Response
 	Respond with [`CharacterCreateResponse`](super::client::CharacterCreateResponse), using the appropriate variant to indicate the result. If the character creation is successful, additionally send a [`CharacterListResponse`](super::client::CharacterListResponse) afterwards with the new character included.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[trailing_padding = 1]
 pub struct CharacterCreateRequest {
 	/// The custom name, or blank if the predefined name is to be used.
@@ -172,7 +194,8 @@ pub struct CharacterCreateRequest {
 	### Response
 	Respond with [`LoadStaticZone`](super::client::LoadStaticZone) if you're not switching instances, or [`TransferToWorld`](super::client::TransferToWorld) if you do.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterLoginRequest {
 	/// The object ID of the chosen character.
 	pub char_id: ObjId,
@@ -190,13 +213,15 @@ pub struct CharacterLoginRequest {
 	### Response
 	Respond with [`CharacterDeleteResponse`](super::client::CharacterDeleteResponse) indicating whether deletion was successful.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct CharacterDeleteRequest {
 	/// The object ID of the chosen character.
 	pub char_id: ObjId,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct GeneralChatMessage {
 	pub chat_channel: ChatChannel,
 	pub source_id: u16,
@@ -211,8 +236,8 @@ where
 	fn deserialize(reader: &mut R) -> Res<Self> {
 		let chat_channel = LERead::read(reader)?;
 		let source_id = LERead::read(reader)?;
-		let mut str_len: u32 = LERead::read(reader)?;
-		str_len -= 1;
+		let str_len: u32 = LERead::read(reader)?;
+		let str_len = str_len.checked_sub(1).ok_or(Error::InvalidString { type_name: "GeneralChatMessage", reason: "missing null terminator" })?;
 		let message = LuVarWString::deser_content(reader, str_len)?;
 		let _: u16 = LERead::read(reader)?;
 		Ok(Self { chat_channel, source_id, message })
@@ -231,6 +256,21 @@ impl<'a, W: Write + LEWrite> Serialize<LE, W> for &'a GeneralChatMessage {
 	}
 }
 
+impl crate::dissect::Dissect for GeneralChatMessage {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("chat_channel", &self.chat_channel)?;
+		dissector.field("source_id", &self.source_id)?;
+		dissector.field("length", &(self.message.len() as u32 + 1))?;
+		dissector.node("message", std::any::type_name::<LuVarWString<u32>>(), |dissector| {
+			dissector.leaf(&self.message, self.message.len() as u64 * 16);
+			Ok(())
+		})?;
+		// null terminator
+		dissector.skip(16);
+		Ok(())
+	}
+}
+
 /**
 	Reports to the server that client-side loading has finished.
 
@@ -238,15 +278,17 @@ impl<'a, W: Write + LEWrite> Serialize<LE, W> for &'a GeneralChatMessage {
 	The client finishing a zone load initiated by [`LoadStaticZone`](super::client::LoadStaticZone).
 
 	### Handling / Response
-	Respond with [`CreateCharacter`](super::client::CreateCharacter) containing details about the player's character. Add the client to your server's replica manager, so that existing objects in range are replicated using [`ReplicaConstruction`](crate::raknet::client::replica::ReplicaConstruction). Create the character's replica object and and let the replica manager broadcast its construction to all clients in range. Finally, send [`ServerDoneLoadingAllObjects`](crate::world::gm::client::GameMessage::ServerDoneLoadingAllObjects) from the character object to the client.
+	Respond with [`CreateCharacter`](super::client::CreateCharacter) containing details about the player's character. Add the client to your server's [replica manager](crate::raknet::replica_manager::ReplicaManager), so that existing objects in range are replicated using [`ReplicaConstruction`](crate::raknet::client::replica::ReplicaConstruction). Create the character's replica object and and let the replica manager broadcast its construction to all clients in range. Finally, send [`ServerDoneLoadingAllObjects`](crate::world::gm::client::GameMessage::ServerDoneLoadingAllObjects) from the character object to the client.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LevelLoadComplete {
 	/// The ID of the zone that was loaded. Servers should not trust this, as a player could use it to get into zones they don't belong.
 	pub zone_id: ZoneId,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[pre_disc_padding = 4]
 #[repr(u16)]
 pub enum RouteMessage {
@@ -254,6 +296,7 @@ pub enum RouteMessage {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct PositionUpdate {
 	pub frame_stats: FrameStats,
 }
@@ -273,6 +316,17 @@ impl<'a, W: Write> Serialize<LE, W> for &'a PositionUpdate {
 	}
 }
 
+impl crate::dissect::Dissect for PositionUpdate {
+	fn dissect(&self, dissector: &mut Dissector) -> Res<()> {
+		dissector.field("frame_stats", &self.frame_stats)?;
+		dissector.align();
+		Ok(())
+	}
+	fn layout() -> Layout {
+		Layout::Struct(vec![("frame_stats", Layout::of::<FrameStats>()), ("", Layout::Align)])
+	}
+}
+
 /**
 	Asks the server whether a string the player entered is acceptable.
 
@@ -288,7 +342,8 @@ impl<'a, W: Write> Serialize<LE, W> for &'a PositionUpdate {
 	### Notes
 	This message is only for quick player feedback on acceptability. Final string submissions by the player will be sent in different messages (e.g. [`GeneralChatMessage`] or `Mail` (todo)). Those messages will need to be checked for moderation as well. This means that there's no harm in trusting the client to provide accurate context ([`chat_mode`](Self::chat_mode), [`recipient_name`](Self::recipient_name) in this message.
 */
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct StringCheck {
 	pub chat_mode: u8, // todo: type?
 	pub request_id: u8,
@@ -297,7 +352,8 @@ pub struct StringCheck {
 	pub string: LuVarWString<u16>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 #[allow(non_camel_case_types)]
 pub enum Language {
@@ -307,12 +363,14 @@ pub enum Language {
 	en_GB,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Top5IssuesRequest {
 	pub language: Language,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, EnumDeserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[repr(u32)]
 pub enum UgcResType {
 	Lxfml,
@@ -321,7 +379,8 @@ pub enum UgcResType {
 	Dds,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Dissect, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct UgcDownloadFailed {
 	pub res_type: UgcResType,
 	pub blueprint_id: ObjId,
@@ -0,0 +1,958 @@
+/*!
+	Decoding and encoding of the behavior data of skills.
+
+	When casting a skill, the client runs its behaviors and writes their results to a bitstream, sent as the `bitstream` of [`StartSkill`], which the server echoes to other clients in [`EchoStartSkill`]. Which behaviors a skill consists of, and what they write, isn't part of the data. It is determined by the `SkillBehavior`, `BehaviorTemplate` and `BehaviorParameter` tables of the CDClient, provided through a [`BehaviorSource`].
+
+	Some behaviors continue later, with a handle in the data of the skill, and a [`SyncSkill`] with that handle as `behavior_handle`, see [`Behavior::sync_handles`] and [`SyncData`].
+
+	Behaviors are read as the client writes them. Only behaviors that write data or contain other behaviors are known, see [`BehaviorTemplate`]. All others are assumed to write nothing.
+*/
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{Result as Res, Write};
+
+use endio::{Deserialize, LE, LERead, LEWrite, Serialize};
+use endio_bit::{BEBitReader, BEBitWriter};
+
+use crate::{error, limits, Error};
+use crate::common::{ObjId, OBJID_EMPTY};
+use crate::world::Vector3;
+use crate::world::gm::client::{EchoStartSkill, EchoSyncSkill};
+use crate::world::gm::server::{StartSkill, SyncSkill};
+
+/// ID of a behavior in the `BehaviorTemplate` table.
+pub type BehaviorId = u32;
+
+/**
+	The templates of behaviors that write data or contain other behaviors, with their `templateID` as discriminant.
+
+	Behaviors contained in others are referenced by parameters, noted for each template. Numbered parameters start at 1 and end at the first number missing.
+*/
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+#[repr(u32)]
+pub enum BehaviorTemplate {
+	/// Contains `on_success` and `on_fail_armor`.
+	BasicAttack = 1,
+	/// Contains `action`, `miss action` and `blocked action`. Parameters `use_picked_target` and `check_env`.
+	TacArc = 2,
+	/// Contains `behavior 1`, `behavior 2`, ….
+	And = 3,
+	/// Parameters `use_mouseposit` and `spread_count`.
+	ProjectileAttack = 4,
+	/// Contains `ground_action`, `jump_action`, `falling_action`, `double_jump_action`, `air_action`, `jetpack_action` and `moving_action`.
+	MovementSwitch = 6,
+	/// Contains `action`.
+	AreaOfEffect = 7,
+	/// Contains `action`, targeting the originator.
+	TargetCaster = 14,
+	/// Parameter `stun_caster`.
+	Stun = 15,
+	/// Contains `action`.
+	Duration = 16,
+	Knockback = 17,
+	/// Contains `action`, in a sync.
+	AttackDelay = 18,
+	/// Contains `action`.
+	CarBoost = 19,
+	/// Contains `action_true` and `action_false`. Parameters `imagination` and `isEnemyFaction`.
+	Switch = 29,
+	/// Contains `behavior 1`, `behavior 2`, ….
+	Chain = 38,
+	/// Parameters `hit_action`, `hit_action_enemy` and `hit_action_faction`. Syncs with any behavior.
+	ForceMovement = 40,
+	/// Parameter `interrupt_block`.
+	Interrupt = 41,
+	/// Contains `action`, in a sync.
+	ChargeUp = 43,
+	/// Contains `behavior 1`, `behavior 2`, …, chosen by `value 1`, `value 2`, ….
+	SwitchMultiple = 44,
+	/// Contains `action`.
+	Start = 45,
+	/// Syncs with any behavior.
+	AirMovement = 56,
+	/// Contains `action`, without a target. Parameter `clear_if_caster`, which only clears the target if it's the originator.
+	ClearTarget = 62,
+}
+
+impl BehaviorTemplate {
+	pub fn from_id(id: u32) -> Option<Self> {
+		use BehaviorTemplate::*;
+		Some(match id {
+			1 => BasicAttack,
+			2 => TacArc,
+			3 => And,
+			4 => ProjectileAttack,
+			6 => MovementSwitch,
+			7 => AreaOfEffect,
+			14 => TargetCaster,
+			15 => Stun,
+			16 => Duration,
+			17 => Knockback,
+			18 => AttackDelay,
+			19 => CarBoost,
+			29 => Switch,
+			38 => Chain,
+			40 => ForceMovement,
+			41 => Interrupt,
+			43 => ChargeUp,
+			44 => SwitchMultiple,
+			45 => Start,
+			56 => AirMovement,
+			62 => ClearTarget,
+			_ => return None,
+		})
+	}
+}
+
+/**
+	Provides skills and behaviors from the CDClient.
+
+	[`BehaviorTable`] is an in-memory implementation.
+*/
+pub trait BehaviorSource {
+	/// The `behaviorID` of the skill in `SkillBehavior`.
+	fn skill_behavior(&mut self, skill_id: u32) -> Option<BehaviorId>;
+	/// The `templateID` of the behavior in `BehaviorTemplate`.
+	fn template(&mut self, behavior_id: BehaviorId) -> Option<u32>;
+	/// The `value` of the row of `BehaviorParameter` with the behavior's ID and `parameterID` equal to `name`.
+	fn parameter(&mut self, behavior_id: BehaviorId, name: &str) -> Option<f32>;
+}
+
+impl<S: BehaviorSource + ?Sized> BehaviorSource for &mut S {
+	fn skill_behavior(&mut self, skill_id: u32) -> Option<BehaviorId> {
+		(**self).skill_behavior(skill_id)
+	}
+
+	fn template(&mut self, behavior_id: BehaviorId) -> Option<u32> {
+		(**self).template(behavior_id)
+	}
+
+	fn parameter(&mut self, behavior_id: BehaviorId, name: &str) -> Option<f32> {
+		(**self).parameter(behavior_id, name)
+	}
+}
+
+/// A behavior's row of `BehaviorTemplate` and its rows of `BehaviorParameter`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BehaviorDef {
+	pub template: u32,
+	pub parameters: HashMap<String, f32>,
+}
+
+/// Skills and behaviors held in memory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BehaviorTable {
+	pub skills: HashMap<u32, BehaviorId>,
+	pub behaviors: HashMap<BehaviorId, BehaviorDef>,
+}
+
+impl BehaviorTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a behavior of a template known to this module.
+	pub fn insert(&mut self, behavior_id: BehaviorId, template: BehaviorTemplate, parameters: &[(&str, f32)]) {
+		let parameters = parameters.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+		self.behaviors.insert(behavior_id, BehaviorDef { template: template as u32, parameters });
+	}
+}
+
+impl BehaviorSource for BehaviorTable {
+	fn skill_behavior(&mut self, skill_id: u32) -> Option<BehaviorId> {
+		self.skills.get(&skill_id).copied()
+	}
+
+	fn template(&mut self, behavior_id: BehaviorId) -> Option<u32> {
+		self.behaviors.get(&behavior_id).map(|x| x.template)
+	}
+
+	fn parameter(&mut self, behavior_id: BehaviorId, name: &str) -> Option<f32> {
+		self.behaviors.get(&behavior_id)?.parameters.get(name).copied()
+	}
+}
+
+/// The objects a behavior is run with. Some behaviors write data depending on whether the target is the originator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Branch {
+	/// The caster of the skill.
+	pub originator: ObjId,
+	/// The current target, [`OBJID_EMPTY`] if none. Behaviors that pick targets run their contained behaviors with those instead.
+	pub target: ObjId,
+}
+
+/// The data written by a behavior and the behaviors it contains.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Behavior {
+	pub id: BehaviorId,
+	pub data: BehaviorData,
+}
+
+/// The data of a [`Behavior`], depending on its [`BehaviorTemplate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BehaviorData {
+	/// A behavior that doesn't write anything in this case, or of a template that never does.
+	None,
+	BasicAttack(BasicAttack),
+	TacArc(TacArc),
+	And(Vec<Behavior>),
+	ProjectileAttack(ProjectileAttack),
+	/// The current movement of the caster, and the contained behavior for it, if any.
+	MovementSwitch {
+		movement_type: u32,
+		action: Option<Box<Behavior>>,
+	},
+	/// The targets in the area, each with the `action` run on it.
+	AreaOfEffect(Vec<(ObjId, Behavior)>),
+	/// Whether the stun was blocked, present unless the stun affects the caster.
+	Stun {
+		blocked: Option<bool>,
+	},
+	Knockback {
+		unknown: bool,
+	},
+	/// The flags of an interrupt, each ending it early if `true`. `target_flag` is present if the target isn't the originator, `block_flag` unless the interrupt is `interrupt_block`.
+	Interrupt {
+		target_flag: Option<bool>,
+		block_flag: Option<bool>,
+	},
+	/// Present unless the switch depends on neither imagination nor faction.
+	Switch {
+		state: Option<bool>,
+		action: Box<Behavior>,
+	},
+	/// The 1-based index of the behavior chosen, and the behavior, if the index is in range.
+	Chain {
+		index: u32,
+		action: Option<Box<Behavior>>,
+	},
+	/// The value compared to the `value` parameters, and the behavior chosen by it.
+	SwitchMultiple {
+		value: f32,
+		action: Box<Behavior>,
+	},
+	/// The behavior continues in a [`SyncSkill`] with this handle.
+	Sync {
+		handle: u32,
+	},
+	/// The contained `action`, for behaviors that only pass through.
+	Action(Box<Behavior>),
+}
+
+/**
+	The data of a basic attack.
+
+	This data is preceded by its length, so that it can be skipped. It is aligned to a byte boundary.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasicAttack {
+	pub blocked: bool,
+	/// Present if not blocked.
+	pub immune: Option<bool>,
+	/// Present if neither blocked nor immune.
+	pub result: Option<AttackResult>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttackResult {
+	/// Present if the attack succeeded.
+	pub damage: Option<AttackDamage>,
+	/// 1 for success, running `on_success`, 2 for failure due to armor, running `on_fail_armor`.
+	pub success_state: u8,
+	pub action: Option<Box<Behavior>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttackDamage {
+	pub armor: u32,
+	pub health: u32,
+	pub died: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TacArc {
+	/// Whether any target was hit. `None` if the behavior used the target picked before it, in which case `action` ran on that target.
+	pub hit: Option<bool>,
+	/// Whether the arc was blocked by the environment, present if the behavior checks for it.
+	pub blocked: Option<bool>,
+	/// The targets hit, each with the `action` run on it.
+	pub targets: Vec<(ObjId, Behavior)>,
+	/// The `action` on the picked target, or the `blocked action`, or the `miss action`.
+	pub action: Option<Box<Behavior>>,
+}
+
+/// The projectiles launched, whose impacts are sent separately.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectileAttack {
+	pub target: ObjId,
+	/// Present if the behavior aims at the mouse position.
+	pub target_position: Option<Vector3>,
+	/// One ID per projectile, `spread_count` of them, at least one.
+	pub projectile_ids: Vec<ObjId>,
+}
+
+/// The data of a [`SyncSkill`], for the behavior with its handle.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncData {
+	/// The `action` of a delayed or charged up behavior.
+	Action(Behavior),
+	/// A behavior chosen by the client when a movement ends, run on `target`.
+	Redirect { target: ObjId, behavior: Behavior },
+}
+
+impl Behavior {
+	/// Decodes the data written by the behavior `id`.
+	pub fn decode<S: BehaviorSource>(source: &mut S, id: BehaviorId, branch: Branch, data: &[u8]) -> Res<Self> {
+		Decoder::new(source, data).run(|decoder| {
+			let behavior = decoder.behavior(id, branch)?;
+			decoder.reader.finish()?;
+			Ok(behavior)
+		})
+	}
+
+	pub fn encode(&self) -> Res<Vec<u8>> {
+		let mut writer = BitWriter::new();
+		writer.behavior(self)?;
+		writer.finish()
+	}
+
+	/// The handles of behaviors continuing in a sync, with the IDs of those behaviors, to be passed to [`SyncData::decode`].
+	pub fn sync_handles(&self) -> Vec<(u32, BehaviorId)> {
+		let mut handles = vec![];
+		self.visit(&mut |behavior| {
+			if let BehaviorData::Sync { handle } = behavior.data {
+				handles.push((handle, behavior.id));
+			}
+		});
+		handles
+	}
+
+	/// Calls `f` with this behavior and all contained ones, depth first.
+	pub fn visit(&self, f: &mut impl FnMut(&Behavior)) {
+		f(self);
+		match &self.data {
+			BehaviorData::BasicAttack(BasicAttack { result: Some(AttackResult { action: Some(x), .. }), .. }) | BehaviorData::MovementSwitch { action: Some(x), .. } | BehaviorData::Switch { action: x, .. } | BehaviorData::Chain { action: Some(x), .. } | BehaviorData::SwitchMultiple { action: x, .. } | BehaviorData::Action(x) => x.visit(f),
+			BehaviorData::And(behaviors) => behaviors.iter().for_each(|x| x.visit(f)),
+			BehaviorData::AreaOfEffect(targets) => targets.iter().for_each(|(_, x)| x.visit(f)),
+			// in the order they are encoded
+			BehaviorData::TacArc(arc) => {
+				arc.targets.iter().for_each(|(_, x)| x.visit(f));
+				if let Some(x) = &arc.action {
+					x.visit(f);
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+impl SyncData {
+	/// Decodes the data of a sync for the behavior `id`, which has to be one that syncs.
+	pub fn decode<S: BehaviorSource>(source: &mut S, id: BehaviorId, branch: Branch, data: &[u8]) -> Res<Self> {
+		Decoder::new(source, data).run(|decoder| {
+			let sync = match decoder.template(id) {
+				Some(BehaviorTemplate::AttackDelay) | Some(BehaviorTemplate::ChargeUp) => Self::Action(*decoder.contained(id, "action", branch)?),
+				Some(BehaviorTemplate::ForceMovement) | Some(BehaviorTemplate::AirMovement) => {
+					let next = decoder.reader.read()?;
+					let target = decoder.reader.read()?;
+					Self::Redirect { target, behavior: decoder.behavior(next, Branch { target, ..branch })? }
+				}
+				_ => return Err(Error::Malformed { type_name: "SyncSkill", reason: "behavior doesn't sync" }.into()),
+			};
+			decoder.reader.finish()?;
+			Ok(sync)
+		})
+	}
+
+	pub fn encode(&self) -> Res<Vec<u8>> {
+		let mut writer = BitWriter::new();
+		match self {
+			Self::Action(behavior) => writer.behavior(behavior)?,
+			Self::Redirect { target, behavior } => {
+				writer.write(behavior.id)?;
+				writer.write(*target)?;
+				writer.behavior(behavior)?;
+			}
+		}
+		writer.finish()
+	}
+}
+
+impl StartSkill {
+	/// Decodes `bitstream` for the skill cast by `originator`, the subject of the message.
+	pub fn decode_behavior<S: BehaviorSource>(&self, source: &mut S, originator: ObjId) -> Res<Behavior> {
+		decode_skill("GameMessage::StartSkill", source, self.skill_id, Branch { originator, target: self.optional_target_id }, &self.bitstream)
+	}
+}
+
+impl EchoStartSkill {
+	/// Decodes `bitstream` for the skill cast by `originator`, the subject of the message.
+	pub fn decode_behavior<S: BehaviorSource>(&self, source: &mut S, originator: ObjId) -> Res<Behavior> {
+		decode_skill("GameMessage::EchoStartSkill", source, self.skill_id, Branch { originator, target: self.optional_target_id }, &self.bitstream)
+	}
+}
+
+fn decode_skill<S: BehaviorSource>(message: &'static str, source: &mut S, skill_id: u32, branch: Branch, data: &[u8]) -> Res<Behavior> {
+	let id = source.skill_behavior(skill_id).ok_or(Error::Malformed { type_name: "StartSkill", reason: "unknown skill" })?;
+	in_bitstream(message, || Behavior::decode(source, id, branch, data))
+}
+
+/// Runs `f`, adding the `bitstream` of `message` to the path of its errors.
+fn in_bitstream<T>(message: &'static str, f: impl FnOnce() -> Res<T>) -> Res<T> {
+	error::frame(message, || error::frame("bitstream", f))
+}
+
+impl SyncSkill {
+	/// Decodes `bitstream` for the behavior `id`, the one with the message's `behavior_handle`.
+	pub fn decode_sync<S: BehaviorSource>(&self, source: &mut S, id: BehaviorId, branch: Branch) -> Res<SyncData> {
+		in_bitstream("GameMessage::SyncSkill", || SyncData::decode(source, id, branch, &self.bitstream))
+	}
+}
+
+impl EchoSyncSkill {
+	/// Decodes `bitstream` for the behavior `id`, the one with the message's `behavior_handle`.
+	pub fn decode_sync<S: BehaviorSource>(&self, source: &mut S, id: BehaviorId, branch: Branch) -> Res<SyncData> {
+		in_bitstream("GameMessage::EchoSyncSkill", || SyncData::decode(source, id, branch, &self.bitstream))
+	}
+}
+
+/// Reads bits, keeping track of the position, which basic attacks need to skip their data.
+struct BitReader<'a> {
+	inner: BEBitReader<&'a [u8]>,
+	len: usize,
+	pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+	fn new(data: &'a [u8]) -> Self {
+		Self { inner: BEBitReader::new(data), len: data.len() * 8, pos: 0 }
+	}
+
+	fn bit(&mut self) -> Res<bool> {
+		self.pos += 1;
+		self.inner.read_bit()
+	}
+
+	/// Reads a value serialized as its in-memory representation, such as integers and floats.
+	fn read<T: Deserialize<LE, BEBitReader<&'a [u8]>>>(&mut self) -> Res<T> {
+		self.pos += 8 * std::mem::size_of::<T>();
+		LERead::read(&mut self.inner)
+	}
+
+	fn align(&mut self) {
+		if self.pos % 8 != 0 {
+			self.inner.align();
+			self.pos += 8 - self.pos % 8;
+		}
+	}
+
+	fn skip_to(&mut self, pos: usize) -> Res<()> {
+		while self.pos < pos {
+			self.bit()?;
+		}
+		Ok(())
+	}
+
+	/// Checks that at most the padding of the last byte is left.
+	fn finish(&self) -> Res<()> {
+		let remaining = (self.len - self.pos) / 8;
+		if remaining > 0 {
+			return Err(Error::TrailingData { remaining }.into());
+		}
+		Ok(())
+	}
+}
+
+struct Decoder<'a, 'b, S> {
+	source: &'b mut S,
+	reader: BitReader<'a>,
+	depth: usize,
+}
+
+impl<'a, 'b, S: BehaviorSource> Decoder<'a, 'b, S> {
+	fn new(source: &'b mut S, data: &'a [u8]) -> Self {
+		Self { source, reader: BitReader::new(data), depth: 0 }
+	}
+
+	/// Runs `f`, recording the position in the bit stream in its errors.
+	fn run<T>(&mut self, f: impl FnOnce(&mut Self) -> Res<T>) -> Res<T> {
+		f(self).map_err(|err| error::with_bit_offset(err, self.reader.pos as u64))
+	}
+
+	fn template(&mut self, id: BehaviorId) -> Option<BehaviorTemplate> {
+		self.source.template(id).and_then(BehaviorTemplate::from_id)
+	}
+
+	fn param(&mut self, id: BehaviorId, name: &str) -> f32 {
+		self.source.parameter(id, name).unwrap_or(0.0)
+	}
+
+	/// The ID of a contained behavior, 0 if none.
+	fn action_id(&mut self, id: BehaviorId, name: &str) -> BehaviorId {
+		self.param(id, name) as BehaviorId
+	}
+
+	fn contained(&mut self, id: BehaviorId, name: &str, branch: Branch) -> Res<Box<Behavior>> {
+		let action = self.action_id(id, name);
+		Ok(Box::new(self.behavior(action, branch)?))
+	}
+
+	/// The IDs of the behaviors contained as `behavior 1`, `behavior 2`, ….
+	fn numbered(&mut self, id: BehaviorId) -> Vec<BehaviorId> {
+		let mut behaviors = vec![];
+		while let Some(x) = self.source.parameter(id, &format!("behavior {}", behaviors.len() + 1)) {
+			behaviors.push(x as BehaviorId);
+		}
+		behaviors
+	}
+
+	fn targets(&mut self) -> Res<Vec<ObjId>> {
+		let count: u32 = self.reader.read()?;
+		let count = limits::check_vec("Behavior", count as u64)?;
+		let mut targets = limits::vec_with_capacity(count);
+		for _ in 0..count {
+			targets.push(self.reader.read()?);
+		}
+		Ok(targets)
+	}
+
+	fn behavior(&mut self, id: BehaviorId, branch: Branch) -> Res<Behavior> {
+		self.depth += 1;
+		limits::check("Behavior", "max_skill_depth", |x| x.max_skill_depth, self.depth as u64)?;
+		let data = self.data(id, branch)?;
+		self.depth -= 1;
+		Ok(Behavior { id, data })
+	}
+
+	fn data(&mut self, id: BehaviorId, branch: Branch) -> Res<BehaviorData> {
+		use BehaviorTemplate::*;
+		let template = match self.template(id) {
+			Some(x) => x,
+			None => return Ok(BehaviorData::None),
+		};
+		Ok(match template {
+			BasicAttack => BehaviorData::BasicAttack(self.basic_attack(id, branch)?),
+			TacArc => BehaviorData::TacArc(self.tac_arc(id, branch)?),
+			And => {
+				let mut behaviors = vec![];
+				for action in self.numbered(id) {
+					behaviors.push(self.behavior(action, branch)?);
+				}
+				BehaviorData::And(behaviors)
+			}
+			ProjectileAttack => {
+				let target = self.reader.read()?;
+				let target_position = if self.param(id, "use_mouseposit") != 0.0 { Some(Vector3 { x: self.reader.read()?, y: self.reader.read()?, z: self.reader.read()? }) } else { None };
+				let count = (self.param(id, "spread_count") as usize).max(1);
+				let mut projectile_ids = limits::vec_with_capacity(count);
+				for _ in 0..count {
+					projectile_ids.push(self.reader.read()?);
+				}
+				BehaviorData::ProjectileAttack(self::ProjectileAttack { target, target_position, projectile_ids })
+			}
+			MovementSwitch => {
+				const ACTIONS: [&str; 7] = ["ground_action", "jump_action", "falling_action", "double_jump_action", "air_action", "jetpack_action", "moving_action"];
+				if ACTIONS.iter().all(|x| self.action_id(id, x) == 0) {
+					return Ok(BehaviorData::None);
+				}
+				let movement_type = self.reader.read()?;
+				let action = match movement_type {
+					1 | 3 => Some(ACTIONS[0]),
+					2 => Some(ACTIONS[1]),
+					4 => Some(ACTIONS[2]),
+					5 => Some(ACTIONS[3]),
+					6 => Some(ACTIONS[4]),
+					7 => Some(ACTIONS[5]),
+					_ => None,
+				};
+				let action = match action {
+					Some(x) => Some(self.contained(id, x, branch)?),
+					None => None,
+				};
+				BehaviorData::MovementSwitch { movement_type, action }
+			}
+			AreaOfEffect => {
+				let mut targets = vec![];
+				for target in self.targets()? {
+					let action = self.contained(id, "action", Branch { target, ..branch })?;
+					targets.push((target, *action));
+				}
+				BehaviorData::AreaOfEffect(targets)
+			}
+			Stun => {
+				let blocked = if self.param(id, "stun_caster") != 0.0 || branch.target == branch.originator { None } else { Some(self.reader.bit()?) };
+				BehaviorData::Stun { blocked }
+			}
+			Knockback => BehaviorData::Knockback { unknown: self.reader.bit()? },
+			Interrupt => {
+				let target_flag = if branch.target != branch.originator { Some(self.reader.bit()?) } else { None };
+				let block_flag = if target_flag != Some(true) && self.param(id, "interrupt_block") == 0.0 { Some(self.reader.bit()?) } else { None };
+				BehaviorData::Interrupt { target_flag, block_flag }
+			}
+			Switch => {
+				let state = if self.param(id, "imagination") > 0.0 || self.param(id, "isEnemyFaction") == 0.0 { Some(self.reader.bit()?) } else { None };
+				let action = self.contained(id, if state.unwrap_or(true) { "action_true" } else { "action_false" }, branch)?;
+				BehaviorData::Switch { state, action }
+			}
+			Chain => {
+				let index: u32 = self.reader.read()?;
+				let behaviors = self.numbered(id);
+				let action = match index.checked_sub(1).and_then(|x| behaviors.get(x as usize)) {
+					Some(x) => Some(Box::new(self.behavior(*x, branch)?)),
+					None => None,
+				};
+				BehaviorData::Chain { index, action }
+			}
+			SwitchMultiple => {
+				let value: f32 = self.reader.read()?;
+				let behaviors = self.numbered(id);
+				let mut chosen = behaviors.first().copied().unwrap_or(0);
+				for (i, behavior) in behaviors.iter().enumerate() {
+					if value <= self.param(id, &format!("value {}", i + 1)) {
+						chosen = *behavior;
+						break;
+					}
+				}
+				BehaviorData::SwitchMultiple { value, action: Box::new(self.behavior(chosen, branch)?) }
+			}
+			AttackDelay | ChargeUp | AirMovement => BehaviorData::Sync { handle: self.reader.read()? },
+			ForceMovement => {
+				if ["hit_action", "hit_action_enemy", "hit_action_faction"].iter().all(|x| self.action_id(id, x) == 0) {
+					return Ok(BehaviorData::None);
+				}
+				BehaviorData::Sync { handle: self.reader.read()? }
+			}
+			TargetCaster => BehaviorData::Action(self.contained(id, "action", Branch { target: branch.originator, ..branch })?),
+			ClearTarget => {
+				let target = if self.param(id, "clear_if_caster") == 0.0 || branch.target == branch.originator { OBJID_EMPTY } else { branch.target };
+				BehaviorData::Action(self.contained(id, "action", Branch { target, ..branch })?)
+			}
+			Duration | CarBoost | Start => BehaviorData::Action(self.contained(id, "action", branch)?),
+		})
+	}
+
+	fn basic_attack(&mut self, id: BehaviorId, branch: Branch) -> Res<BasicAttack> {
+		self.reader.align();
+		let allocated_bits: u16 = self.reader.read()?;
+		let end = self.reader.pos + allocated_bits as usize;
+		let mut attack = BasicAttack { blocked: false, immune: None, result: None };
+		if allocated_bits == 0 {
+			return Ok(attack);
+		}
+		attack.blocked = self.reader.bit()?;
+		if !attack.blocked {
+			let immune = self.reader.bit()?;
+			attack.immune = Some(immune);
+			if !immune {
+				let damage = if self.reader.bit()? { Some(AttackDamage { armor: self.reader.read()?, health: self.reader.read()?, died: self.reader.bit()? }) } else { None };
+				let success_state = self.reader.read()?;
+				let action = match success_state {
+					1 => Some(self.contained(id, "on_success", branch)?),
+					2 => Some(self.contained(id, "on_fail_armor", branch)?),
+					_ => None,
+				};
+				attack.result = Some(AttackResult { damage, success_state, action });
+			}
+		}
+		if self.reader.pos > end {
+			return Err(Error::Malformed { type_name: "BasicAttack", reason: "data exceeds its length" }.into());
+		}
+		self.reader.skip_to(end)?;
+		Ok(attack)
+	}
+
+	fn tac_arc(&mut self, id: BehaviorId, branch: Branch) -> Res<TacArc> {
+		let mut arc = TacArc { hit: None, blocked: None, targets: vec![], action: None };
+		if self.param(id, "use_picked_target") != 0.0 && branch.target != OBJID_EMPTY {
+			arc.action = Some(self.contained(id, "action", branch)?);
+			return Ok(arc);
+		}
+		let hit = self.reader.bit()?;
+		arc.hit = Some(hit);
+		if self.param(id, "check_env") != 0.0 {
+			let blocked = self.reader.bit()?;
+			arc.blocked = Some(blocked);
+			if blocked {
+				arc.action = Some(self.contained(id, "blocked action", branch)?);
+				return Ok(arc);
+			}
+		}
+		if hit {
+			for target in self.targets()? {
+				let action = self.contained(id, "action", Branch { target, ..branch })?;
+				arc.targets.push((target, *action));
+			}
+		} else {
+			arc.action = Some(self.contained(id, "miss action", branch)?);
+		}
+		Ok(arc)
+	}
+}
+
+/// Writes bits, keeping track of the position, which basic attacks need to write their length.
+struct BitWriter {
+	inner: BEBitWriter<Vec<u8>>,
+	pos: usize,
+}
+
+impl BitWriter {
+	fn new() -> Self {
+		Self { inner: BEBitWriter::new(vec![]), pos: 0 }
+	}
+
+	fn bit(&mut self, bit: bool) -> Res<()> {
+		self.pos += 1;
+		self.inner.write_bit(bit)
+	}
+
+	/// Writes a value serialized as its in-memory representation, such as integers and floats.
+	fn write<T: Serialize<LE, BEBitWriter<Vec<u8>>>>(&mut self, val: T) -> Res<()> {
+		self.pos += 8 * std::mem::size_of::<T>();
+		LEWrite::write(&mut self.inner, val)
+	}
+
+	fn align(&mut self) -> Res<()> {
+		while self.pos % 8 != 0 {
+			self.bit(false)?;
+		}
+		Ok(())
+	}
+
+	fn finish(mut self) -> Res<Vec<u8>> {
+		self.inner.flush()?;
+		Ok(self.inner.get_ref().clone())
+	}
+
+	fn targets(&mut self, targets: &[(ObjId, Behavior)]) -> Res<()> {
+		let count = u32::try_from(targets.len()).map_err(|_| Error::LengthOverflow { type_name: "Behavior", length: targets.len() as u64 })?;
+		self.write(count)?;
+		for (target, _) in targets {
+			self.write(*target)?;
+		}
+		for (_, action) in targets {
+			self.behavior(action)?;
+		}
+		Ok(())
+	}
+
+	fn option_bit(&mut self, bit: Option<bool>) -> Res<()> {
+		match bit {
+			Some(x) => self.bit(x),
+			None => Ok(()),
+		}
+	}
+
+	fn option_behavior(&mut self, behavior: &Option<Box<Behavior>>) -> Res<()> {
+		match behavior {
+			Some(x) => self.behavior(x),
+			None => Ok(()),
+		}
+	}
+
+	fn behavior(&mut self, behavior: &Behavior) -> Res<()> {
+		match &behavior.data {
+			BehaviorData::None => {}
+			BehaviorData::BasicAttack(attack) => self.basic_attack(attack)?,
+			BehaviorData::TacArc(arc) => {
+				self.option_bit(arc.hit)?;
+				self.option_bit(arc.blocked)?;
+				if arc.hit == Some(true) && arc.blocked != Some(true) {
+					self.targets(&arc.targets)?;
+				}
+				self.option_behavior(&arc.action)?;
+			}
+			BehaviorData::And(behaviors) => {
+				for x in behaviors {
+					self.behavior(x)?;
+				}
+			}
+			BehaviorData::ProjectileAttack(attack) => {
+				self.write(attack.target)?;
+				if let Some(pos) = &attack.target_position {
+					self.write(pos.x)?;
+					self.write(pos.y)?;
+					self.write(pos.z)?;
+				}
+				for id in &attack.projectile_ids {
+					self.write(*id)?;
+				}
+			}
+			BehaviorData::MovementSwitch { movement_type, action } => {
+				self.write(*movement_type)?;
+				self.option_behavior(action)?;
+			}
+			BehaviorData::AreaOfEffect(targets) => self.targets(targets)?,
+			BehaviorData::Stun { blocked } => self.option_bit(*blocked)?,
+			BehaviorData::Knockback { unknown } => self.bit(*unknown)?,
+			BehaviorData::Interrupt { target_flag, block_flag } => {
+				self.option_bit(*target_flag)?;
+				self.option_bit(*block_flag)?;
+			}
+			BehaviorData::Switch { state, action } => {
+				self.option_bit(*state)?;
+				self.behavior(action)?;
+			}
+			BehaviorData::Chain { index, action } => {
+				self.write(*index)?;
+				self.option_behavior(action)?;
+			}
+			BehaviorData::SwitchMultiple { value, action } => {
+				self.write(*value)?;
+				self.behavior(action)?;
+			}
+			BehaviorData::Sync { handle } => self.write(*handle)?,
+			BehaviorData::Action(action) => self.behavior(action)?,
+		}
+		Ok(())
+	}
+
+	/// Writes the attack's data to a separate writer first, to precede it with its length.
+	fn basic_attack(&mut self, attack: &BasicAttack) -> Res<()> {
+		let mut data = Self::new();
+		if attack.blocked || attack.immune.is_some() {
+			data.bit(attack.blocked)?;
+			data.option_bit(attack.immune)?;
+		}
+		if let Some(result) = &attack.result {
+			data.bit(result.damage.is_some())?;
+			if let Some(damage) = &result.damage {
+				data.write(damage.armor)?;
+				data.write(damage.health)?;
+				data.bit(damage.died)?;
+			}
+			data.write(result.success_state)?;
+			data.option_behavior(&result.action)?;
+		}
+		let bits = data.pos;
+		let allocated_bits = u16::try_from(bits).map_err(|_| Error::LengthOverflow { type_name: "BasicAttack", length: bits as u64 })?;
+		let bytes = data.finish()?;
+		self.align()?;
+		self.write(allocated_bits)?;
+		for i in 0..bits {
+			self.bit(bytes[i / 8] & (0x80 >> (i % 8)) != 0)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Error;
+	use crate::common::{ObjId, OBJID_EMPTY};
+	use crate::world::{Quaternion, Vector3};
+	use crate::world::gm::server::StartSkill;
+	use super::{AttackDamage, AttackResult, BasicAttack, Behavior, BehaviorData, BehaviorTable, BehaviorTemplate, Branch, ProjectileAttack, SyncData, TacArc};
+
+	const CASTER: ObjId = 1152921504606846976;
+	const ENEMY: ObjId = 288300744895889424;
+
+	fn table() -> BehaviorTable {
+		use BehaviorTemplate::*;
+
+		let mut table = BehaviorTable::new();
+		table.skills.insert(1, 10);
+		table.insert(10, MovementSwitch, &[("ground_action", 11.0), ("jump_action", 20.0)]);
+		table.insert(11, Chain, &[("behavior 1", 12.0), ("behavior 2", 12.0)]);
+		table.insert(12, TacArc, &[("action", 13.0), ("miss action", 0.0)]);
+		table.insert(13, And, &[("behavior 1", 14.0), ("behavior 2", 15.0), ("behavior 3", 16.0)]);
+		table.insert(14, BasicAttack, &[("on_success", 17.0)]);
+		table.insert(15, Stun, &[]);
+		table.insert(16, AttackDelay, &[("action", 17.0)]);
+		table.insert(17, Knockback, &[]);
+		table.insert(20, ProjectileAttack, &[("spread_count", 2.0), ("use_mouseposit", 1.0)]);
+		table
+	}
+
+	fn knockback(unknown: bool) -> Box<Behavior> {
+		Box::new(Behavior { id: 17, data: BehaviorData::Knockback { unknown } })
+	}
+
+	#[test]
+	fn test_skill() {
+		let attack = BasicAttack { blocked: false, immune: Some(false), result: Some(AttackResult { damage: Some(AttackDamage { armor: 1, health: 2, died: false }), success_state: 1, action: Some(knockback(true)) }) };
+		let on_enemy = Behavior { id: 13, data: BehaviorData::And(vec![Behavior { id: 14, data: BehaviorData::BasicAttack(attack) }, Behavior { id: 15, data: BehaviorData::Stun { blocked: Some(false) } }, Behavior { id: 16, data: BehaviorData::Sync { handle: 3 } }]) };
+		let arc = Behavior { id: 12, data: BehaviorData::TacArc(TacArc { hit: Some(true), blocked: None, targets: vec![(ENEMY, on_enemy)], action: None }) };
+		let chain = Behavior { id: 11, data: BehaviorData::Chain { index: 2, action: Some(Box::new(arc)) } };
+		let skill = Behavior { id: 10, data: BehaviorData::MovementSwitch { movement_type: 1, action: Some(Box::new(chain)) } };
+
+		let data = skill.encode().unwrap();
+		let branch = Branch { originator: CASTER, target: OBJID_EMPTY };
+		assert_eq!(Behavior::decode(&mut table(), 10, branch, &data).unwrap(), skill);
+		assert_eq!(skill.sync_handles(), vec![(3, 16)]);
+		let mut ids = vec![];
+		skill.visit(&mut |x| ids.push(x.id));
+		assert_eq!(ids, vec![10, 11, 12, 13, 14, 17, 15, 16]);
+
+		let sync = SyncData::Action(*knockback(false));
+		let sync_data = sync.encode().unwrap();
+		assert_eq!(SyncData::decode(&mut table(), 16, branch, &sync_data).unwrap(), sync);
+		assert!(SyncData::decode(&mut table(), 17, branch, &sync_data).is_err());
+	}
+
+	#[test]
+	fn test_start_skill() {
+		let projectile = ProjectileAttack { target: ENEMY, target_position: Some(Vector3 { x: 1.0, y: 2.0, z: 3.0 }), projectile_ids: vec![5, 6] };
+		let skill = Behavior { id: 10, data: BehaviorData::MovementSwitch { movement_type: 2, action: Some(Box::new(Behavior { id: 20, data: BehaviorData::ProjectileAttack(projectile) })) } };
+		let mut bitstream = skill.encode().unwrap();
+		let message = StartSkill { used_mouse: true, consumable_item_id: OBJID_EMPTY, caster_latency: 0.0, cast_type: 0, last_clicked_posit: Vector3::ZERO, optional_originator_id: OBJID_EMPTY, optional_target_id: OBJID_EMPTY, originator_rot: Quaternion::IDENTITY, bitstream: bitstream.clone(), skill_id: 1, skill_handle: 1 };
+		assert_eq!(message.decode_behavior(&mut table(), CASTER).unwrap(), skill);
+
+		bitstream.push(0);
+		let err = Error::from(Behavior::decode(&mut table(), 10, Branch { originator: CASTER, target: OBJID_EMPTY }, &bitstream).unwrap_err());
+		assert!(matches!(err.root(), Error::TrailingData { remaining: 1 }));
+		assert_eq!(err.bit_offset(), Some(320));
+
+		// movement type and the start of the projectile's target
+		bitstream.truncate(10);
+		let message = StartSkill { bitstream, ..message };
+		let err = Error::from(message.decode_behavior(&mut table(), CASTER).unwrap_err());
+		assert_eq!(err.path(), ["GameMessage::StartSkill", "bitstream"]);
+		assert_eq!(err.bit_offset(), Some(96));
+	}
+
+	#[test]
+	fn test_basic_attack_skips_data() {
+		let mut table = table();
+		table.insert(30, BehaviorTemplate::BasicAttack, &[]);
+		// blocked, followed by 3 bits unknown to the decoder
+		let data = [0x04, 0x00, 0b1101_0000];
+		let branch = Branch { originator: CASTER, target: ENEMY };
+		let attack = Behavior::decode(&mut table, 30, branch, &data).unwrap();
+		assert_eq!(attack.data, BehaviorData::BasicAttack(BasicAttack { blocked: true, immune: None, result: None }));
+		assert_eq!(attack.encode().unwrap(), [0x01, 0x00, 0x80]);
+	}
+
+	#[test]
+	fn test_branch_targets() {
+		use BehaviorTemplate::*;
+
+		let mut table = BehaviorTable::new();
+		table.insert(30, TargetCaster, &[("action", 33.0)]);
+		table.insert(31, ClearTarget, &[("action", 33.0)]);
+		table.insert(32, ClearTarget, &[("action", 33.0), ("clear_if_caster", 1.0)]);
+		table.insert(33, Stun, &[]);
+		let stun = |blocked| Box::new(Behavior { id: 33, data: BehaviorData::Stun { blocked } });
+		let on_enemy = Branch { originator: CASTER, target: ENEMY };
+		let on_caster = Branch { originator: CASTER, target: CASTER };
+
+		// stuns of the caster don't have a blocked bit
+		let behavior = Behavior { id: 30, data: BehaviorData::Action(stun(None)) };
+		assert_eq!(Behavior::decode(&mut table, 30, on_enemy, &behavior.encode().unwrap()).unwrap(), behavior);
+		assert!(Behavior::decode(&mut table, 30, on_enemy, &[0x80]).is_err());
+
+		let behavior = Behavior { id: 31, data: BehaviorData::Action(stun(Some(true))) };
+		assert_eq!(Behavior::decode(&mut table, 31, on_caster, &behavior.encode().unwrap()).unwrap(), behavior);
+		let behavior = Behavior { id: 32, data: BehaviorData::Action(stun(Some(false))) };
+		assert_eq!(Behavior::decode(&mut table, 32, on_caster, &behavior.encode().unwrap()).unwrap(), behavior);
+		assert_eq!(Behavior::decode(&mut table, 32, on_enemy, &behavior.encode().unwrap()).unwrap(), behavior);
+	}
+
+	#[test]
+	fn test_depth() {
+		let mut table = BehaviorTable::new();
+		table.insert(1, BehaviorTemplate::And, &[("behavior 1", 1.0)]);
+		let err = Behavior::decode(&mut table, 1, Branch { originator: CASTER, target: OBJID_EMPTY }, &[]).unwrap_err();
+		assert!(matches!(Error::from(err).root(), Error::LimitExceeded { limit: "max_skill_depth", .. }));
+	}
+}